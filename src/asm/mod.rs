@@ -0,0 +1,322 @@
+//! Line assembler for a subset of the Motorola 88000 instruction set.
+//!
+//! This module encodes single lines of assembly into instruction words, and
+//! formats instruction words back into assembly, to make test programs
+//! easier to write and read:
+//! - `addu`, `subu` and `cmp` in triadic register and 16-bit immediate forms
+//! - `ld` and `st` with a signed 16-bit displacement
+//! - `br`, `br.n` and `bcnd` with displacements counted in instructions (words)
+//!
+//! Registers are written `r0`..`r31`; numbers are decimal or `0x` hexadecimal
+//! and may be negative.
+
+use std::fmt;
+
+/// Major opcode of the triadic register instruction format
+const OP_TRIADIC: u32 = 0b111101;
+/// Major opcode of the unconditional branch
+const OP_BR: u32 = 0b110000;
+/// Major opcode of the delayed unconditional branch
+const OP_BR_N: u32 = 0b110001;
+/// Major opcode of the conditional branch
+const OP_BCND: u32 = 0b111010;
+
+/// Integer mnemonics with their immediate-form major opcode, which is also
+/// their triadic sub-opcode
+const INTEGER_OPS: [(&str, u32); 3] = [("addu", 0b011000), ("subu", 0b011001), ("cmp", 0b011111)];
+/// Load/store mnemonics with their major opcode
+const MEMORY_OPS: [(&str, u32); 2] = [("ld", 0b000101), ("st", 0b001001)];
+/// `bcnd` condition names with their M5 field encoding
+const CONDITIONS: [(&str, u32); 6] = [
+    ("gt0", 0b0001),
+    ("eq0", 0b0010),
+    ("ge0", 0b0011),
+    ("lt0", 0b1100),
+    ("ne0", 0b1101),
+    ("le0", 0b1110),
+];
+
+/// Assembler error types
+#[derive(Debug, PartialEq, Eq)]
+pub enum AsmError {
+    /// The line contains no instruction
+    Empty,
+    /// The mnemonic is not supported
+    UnknownMnemonic(String),
+    /// The instruction has the wrong number of operands
+    OperandCount { expected: usize, found: usize },
+    /// An operand is malformed or out of range
+    InvalidOperand(String),
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AsmError::Empty => write!(f, "no instruction on line"),
+            AsmError::UnknownMnemonic(mnemonic) => write!(f, "unknown mnemonic `{}`", mnemonic),
+            AsmError::OperandCount { expected, found } => {
+                write!(f, "expected {} operands, found {}", expected, found)
+            }
+            AsmError::InvalidOperand(operand) => write!(f, "invalid operand `{}`", operand),
+        }
+    }
+}
+
+/// Assembles a single line into an instruction word.
+///
+/// # Arguments
+///
+/// * `line` - The instruction, e.g. `addu r3, r2, 0x1234`
+///
+/// # Returns
+///
+/// The encoded 32-bit instruction word.
+pub fn assemble_line(line: &str) -> Result<u32, AsmError> {
+    let line = line.trim();
+    let (mnemonic, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    if mnemonic.is_empty() {
+        return Err(AsmError::Empty);
+    }
+    let operands: Vec<&str> = if rest.trim().is_empty() {
+        Vec::new()
+    } else {
+        rest.split(',').map(str::trim).collect()
+    };
+
+    if let Some(opcode) = lookup(&INTEGER_OPS, mnemonic) {
+        expect_operands(&operands, 3)?;
+        let d = parse_register(operands[0])?;
+        let s1 = parse_register(operands[1])?;
+        return Ok(if is_register(operands[2]) {
+            let s2 = parse_register(operands[2])?;
+            (OP_TRIADIC << 26) | (d << 21) | (s1 << 16) | (opcode << 10) | s2
+        } else {
+            (opcode << 26) | (d << 21) | (s1 << 16) | parse_imm16(operands[2])?
+        });
+    }
+
+    if let Some(opcode) = lookup(&MEMORY_OPS, mnemonic) {
+        expect_operands(&operands, 3)?;
+        let d = parse_register(operands[0])?;
+        let s1 = parse_register(operands[1])?;
+        return Ok((opcode << 26) | (d << 21) | (s1 << 16) | parse_signed(operands[2], 16)?);
+    }
+
+    match mnemonic {
+        "br" => {
+            expect_operands(&operands, 1)?;
+            Ok((OP_BR << 26) | parse_signed(operands[0], 26)?)
+        }
+        "br.n" => {
+            expect_operands(&operands, 1)?;
+            Ok((OP_BR_N << 26) | parse_signed(operands[0], 26)?)
+        }
+        "bcnd" => {
+            expect_operands(&operands, 3)?;
+            let condition = parse_condition(operands[0])?;
+            let s1 = parse_register(operands[1])?;
+            Ok((OP_BCND << 26) | (condition << 21) | (s1 << 16) | parse_signed(operands[2], 16)?)
+        }
+        _ => Err(AsmError::UnknownMnemonic(mnemonic.to_string())),
+    }
+}
+
+/// Formats an instruction word as a line of assembly.
+///
+/// This is the inverse of [`assemble_line`]: the output assembles back to
+/// the same word.
+///
+/// # Arguments
+///
+/// * `word` - The 32-bit instruction word
+///
+/// # Returns
+///
+/// The assembly text, or `None` if the instruction is outside the supported subset.
+pub fn disassemble_line(word: u32) -> Option<String> {
+    let d = (word >> 21) & 0x1F;
+    let s1 = (word >> 16) & 0x1F;
+    let imm = word & 0xFFFF;
+
+    match word >> 26 {
+        OP_TRIADIC => {
+            if (word >> 5) & 0x1F != 0 {
+                return None;
+            }
+            let mnemonic = name_of(&INTEGER_OPS, (word >> 10) & 0x3F)?;
+            Some(format!("{} r{}, r{}, r{}", mnemonic, d, s1, word & 0x1F))
+        }
+        OP_BR => Some(format!("br {}", sign_extend(word & 0x03FF_FFFF, 26))),
+        OP_BR_N => Some(format!("br.n {}", sign_extend(word & 0x03FF_FFFF, 26))),
+        OP_BCND => {
+            let disp = sign_extend(imm, 16);
+            Some(match name_of(&CONDITIONS, d) {
+                Some(condition) => format!("bcnd {}, r{}, {}", condition, s1, disp),
+                None => format!("bcnd {}, r{}, {}", d, s1, disp),
+            })
+        }
+        opcode => {
+            if let Some(mnemonic) = name_of(&INTEGER_OPS, opcode) {
+                Some(format!("{} r{}, r{}, {:#x}", mnemonic, d, s1, imm))
+            } else {
+                let mnemonic = name_of(&MEMORY_OPS, opcode)?;
+                let disp = sign_extend(imm, 16);
+                Some(format!("{} r{}, r{}, {}", mnemonic, d, s1, disp))
+            }
+        }
+    }
+}
+
+fn lookup(table: &[(&str, u32)], name: &str) -> Option<u32> {
+    table
+        .iter()
+        .find(|&&(entry, _)| entry == name)
+        .map(|&(_, value)| value)
+}
+
+fn name_of(table: &[(&'static str, u32)], value: u32) -> Option<&'static str> {
+    table
+        .iter()
+        .find(|&&(_, entry)| entry == value)
+        .map(|&(name, _)| name)
+}
+
+fn expect_operands(operands: &[&str], expected: usize) -> Result<(), AsmError> {
+    if operands.len() != expected {
+        return Err(AsmError::OperandCount {
+            expected,
+            found: operands.len(),
+        });
+    }
+    Ok(())
+}
+
+fn is_register(operand: &str) -> bool {
+    operand.starts_with('r')
+}
+
+fn parse_register(operand: &str) -> Result<u32, AsmError> {
+    operand
+        .strip_prefix('r')
+        .and_then(|index| index.parse::<u32>().ok())
+        .filter(|&index| index < 32)
+        .ok_or_else(|| AsmError::InvalidOperand(operand.to_string()))
+}
+
+fn parse_number(operand: &str) -> Option<i64> {
+    let (negative, digits) = match operand.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, operand),
+    };
+    let value = match digits.strip_prefix("0x") {
+        Some(hex) => i64::from_str_radix(hex, 16).ok()?,
+        None => digits.parse::<i64>().ok()?,
+    };
+    Some(if negative { -value } else { value })
+}
+
+/// Parses a 16-bit immediate, accepting both signed and unsigned values.
+fn parse_imm16(operand: &str) -> Result<u32, AsmError> {
+    parse_number(operand)
+        .filter(|value| (-0x8000..=0xFFFF).contains(value))
+        .map(|value| value as u32 & 0xFFFF)
+        .ok_or_else(|| AsmError::InvalidOperand(operand.to_string()))
+}
+
+/// Parses a signed value that must fit in a `bits`-wide field.
+fn parse_signed(operand: &str, bits: u32) -> Result<u32, AsmError> {
+    let limit = 1i64 << (bits - 1);
+    parse_number(operand)
+        .filter(|value| (-limit..limit).contains(value))
+        .map(|value| value as u32 & ((1 << bits) - 1))
+        .ok_or_else(|| AsmError::InvalidOperand(operand.to_string()))
+}
+
+fn parse_condition(operand: &str) -> Result<u32, AsmError> {
+    lookup(&CONDITIONS, operand)
+        .or_else(|| {
+            parse_number(operand)
+                .filter(|m5| (0..32).contains(m5))
+                .map(|m5| m5 as u32)
+        })
+        .ok_or_else(|| AsmError::InvalidOperand(operand.to_string()))
+}
+
+fn sign_extend(value: u32, bits: u32) -> i32 {
+    ((value << (32 - bits)) as i32) >> (32 - bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::decoder::decode;
+
+    #[test]
+    fn test_assemble_known_encodings() {
+        assert_eq!(assemble_line("addu r3, r2, 0x1234"), Ok(0x6062_1234));
+        assert_eq!(assemble_line("subu r4, r5, r6"), Ok(0xF485_6406));
+        assert_eq!(assemble_line("cmp r4, r5, r6"), Ok(0xF485_7C06));
+        assert_eq!(assemble_line("br -1"), Ok(0xC3FF_FFFF));
+        assert_eq!(assemble_line("br.n 3"), Ok(0xC400_0003));
+
+        let decoded = decode(assemble_line("ld r1, r2, -8").unwrap()).unwrap();
+        assert_eq!(decoded.mnemonic, "ld");
+        assert_eq!((decoded.d, decoded.s1, decoded.imm), (1, 2, -8));
+        let decoded = decode(assemble_line("st r3, r4, 12").unwrap()).unwrap();
+        assert_eq!(decoded.mnemonic, "st");
+        assert_eq!((decoded.d, decoded.s1, decoded.imm), (3, 4, 12));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for line in [
+            "addu r3, r2, 0x1234",
+            "addu r1, r1, r31",
+            "subu r4, r5, 0xffff",
+            "cmp r2, r3, r4",
+            "cmp r2, r3, 0x7",
+            "ld r1, r2, -8",
+            "st r3, r4, 12",
+            "br -4",
+            "br 33554431",
+            "br.n -2",
+            "bcnd eq0, r2, 10",
+            "bcnd ne0, r7, -3",
+        ] {
+            let word = assemble_line(line).unwrap();
+            assert_eq!(disassemble_line(word).as_deref(), Some(line));
+        }
+
+        // Equivalent spellings disassemble to the canonical form
+        let word = assemble_line("  addu r3,r2,-1 ").unwrap();
+        assert_eq!(
+            disassemble_line(word).as_deref(),
+            Some("addu r3, r2, 0xffff")
+        );
+    }
+
+    #[test]
+    fn test_assemble_errors() {
+        assert_eq!(assemble_line(""), Err(AsmError::Empty));
+        assert_eq!(
+            assemble_line("fadd r1, r2, r3"),
+            Err(AsmError::UnknownMnemonic("fadd".to_string()))
+        );
+        assert_eq!(
+            assemble_line("addu r1, r2"),
+            Err(AsmError::OperandCount {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            assemble_line("addu r32, r2, r3"),
+            Err(AsmError::InvalidOperand("r32".to_string()))
+        );
+        assert_eq!(
+            assemble_line("ld r1, r2, 0x8000"),
+            Err(AsmError::InvalidOperand("0x8000".to_string()))
+        );
+        assert_eq!(disassemble_line(0xFFFF_FFFF), None);
+    }
+}
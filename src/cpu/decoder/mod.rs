@@ -0,0 +1,577 @@
+//! Instruction decoder for the Motorola 88000.
+//!
+//! This module translates raw 32-bit instruction words into executable
+//! instructions. The M88100 uses a small number of fixed instruction formats:
+//! - 16-bit immediate forms (major opcode in bits 31:26)
+//! - Triadic register forms (major opcode 0b111101, sub-opcode in bits 15:10)
+//! - Bit-field immediate forms (major opcode 0b111100, W5<O5> in bits 9:0)
+//! - Control register and floating point forms
+//!
+//! Only encodings with a matching instruction implementation are decoded;
+//! everything else is reported as unimplemented. Encodings that set fields
+//! the instruction reserves, such as the width of a rotate's bit-field
+//! specifier, are rejected.
+
+use crate::cpu::instructions::control::{Ldcr, Stcr};
+use crate::cpu::instructions::opcode::Opcode;
+use crate::cpu::instructions::{ExecError, Instruction, Operands};
+use crate::cpu::CPU;
+use crate::memory::Memory;
+use std::fmt;
+
+/// Major opcode of the triadic register instruction format
+const OP_TRIADIC: u32 = 0b111101;
+/// Major opcode of the bit-field immediate instruction format
+const OP_BIT_FIELD: u32 = 0b111100;
+/// Major opcode of the control register instruction format
+const OP_CONTROL: u32 = 0b100000;
+/// Major opcode of the floating point instruction format
+const OP_FLOAT: u32 = 0b100001;
+
+/// Canonical no-op encoding: `or r0, r0, r0`
+pub const NOP: u32 = 0xF400_5800;
+/// Pipeline synchronization idiom: `tb1 0, r0, 255`
+pub const PIPELINE_SYNC: u32 = 0xF000_D8FF;
+/// Halt idiom: `tb0 0, r0, 511`
+///
+/// Bit 0 of r0 is always clear, so on hardware this always traps through the
+/// last vector. The emulator reserves the encoding to stop the processor; it
+/// is privileged, like `Halt`.
+pub const HALT: u32 = 0xF000_D1FF;
+
+/// Reasons an instruction word fails to decode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The encoding has no matching instruction implementation
+    Unimplemented(u32),
+    /// Bits the instruction's encoding reserves are set
+    ReservedBits {
+        /// Assembler mnemonic of the instruction
+        mnemonic: &'static str,
+        /// The reserved bits that were set
+        bits: u32,
+    },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Unimplemented(word) => {
+                write!(f, "unimplemented instruction {:#010x}", word)
+            }
+            DecodeError::ReservedBits { mnemonic, bits } => {
+                write!(f, "reserved bits {:#010x} set in {}", bits, mnemonic)
+            }
+        }
+    }
+}
+
+/// Destination register field
+const FIELD_D: u32 = 0x1F << 21;
+/// First source register field
+const FIELD_S1: u32 = 0x1F << 16;
+/// Second source register field
+const FIELD_S2: u32 = 0x1F;
+/// Width of a W5<O5> bit-field specifier
+const FIELD_W5: u32 = 0x1F << 5;
+
+/// Returns the fields an instruction's encoding reserves, which must be zero
+fn reserved_fields(opcode: &Opcode) -> u32 {
+    match opcode {
+        // Rotates take only the O5 offset of the bit-field specifier
+        Opcode::RotImm => FIELD_W5,
+        Opcode::Tbnd | Opcode::TbndImmediate | Opcode::Stcr(_) => FIELD_D,
+        Opcode::Rte => FIELD_D | FIELD_S1 | FIELD_S2,
+        Opcode::Jr | Opcode::Jal => FIELD_D | FIELD_S1,
+        Opcode::FF1 | Opcode::FF0 | Opcode::IntToFp | Opcode::FpToInt | Opcode::FTrunc => FIELD_S1,
+        Opcode::Ldcr(_) => FIELD_S1 | FIELD_S2,
+        _ => 0,
+    }
+}
+
+/// A decoded instruction together with its operand fields.
+pub struct DecodedInstruction {
+    /// Assembler mnemonic of the instruction
+    pub mnemonic: &'static str,
+    /// Executable instruction
+    pub opcode: Opcode,
+    /// Destination register field
+    pub d: usize,
+    /// First source register field
+    pub s1: usize,
+    /// Second source register field
+    pub s2: usize,
+    /// 16-bit immediate / displacement field
+    pub imm: i16,
+    /// Sign-extended 26-bit branch displacement field, in words
+    pub disp: i32,
+}
+
+impl DecodedInstruction {
+    fn new(mnemonic: &'static str, opcode: Opcode, word: u32) -> Self {
+        Self {
+            mnemonic,
+            opcode,
+            d: field_d(word),
+            s1: field_s1(word),
+            s2: field_s2(word),
+            imm: word as u16 as i16,
+            disp: field_disp26(word),
+        }
+    }
+
+    /// Returns the decoded operand fields as passed to the instruction.
+    pub fn operands(&self) -> Operands {
+        Operands {
+            d: self.d,
+            s1: self.s1,
+            s2: self.s2,
+            s3: 0,
+            imm: self.imm,
+            offset: self.imm,
+            disp: self.disp,
+        }
+    }
+
+    /// Executes the instruction with its own operand fields.
+    ///
+    /// The instruction runs through [`Instruction::try_execute`], so a
+    /// privileged instruction is checked against the current privilege level.
+    ///
+    /// # Arguments
+    ///
+    /// * `cpu` - The CPU executing the instruction
+    /// * `memory` - The system memory the instruction operates on
+    ///
+    /// # Returns
+    ///
+    /// How the instruction failed, if it did.
+    pub fn execute(&self, cpu: &mut CPU, memory: &mut Memory) -> Result<(), ExecError> {
+        self.opcode.try_execute(cpu, memory, &self.operands())
+    }
+}
+
+fn field_d(word: u32) -> usize {
+    ((word >> 21) & 0x1F) as usize
+}
+
+fn field_s1(word: u32) -> usize {
+    ((word >> 16) & 0x1F) as usize
+}
+
+fn field_s2(word: u32) -> usize {
+    (word & 0x1F) as usize
+}
+
+fn field_disp26(word: u32) -> i32 {
+    ((word << 6) as i32) >> 6
+}
+
+/// Decodes a raw instruction word.
+///
+/// # Arguments
+///
+/// * `word` - The 32-bit instruction word as fetched from memory
+///
+/// # Returns
+///
+/// The decoded instruction, or `None` if the encoding is not implemented or
+/// malformed. Use [`try_decode`] to find out why a word did not decode.
+pub fn decode(word: u32) -> Option<DecodedInstruction> {
+    try_decode(word).ok()
+}
+
+/// Decodes a raw instruction word, reporting why it could not be decoded.
+///
+/// # Arguments
+///
+/// * `word` - The 32-bit instruction word as fetched from memory
+pub fn try_decode(word: u32) -> Result<DecodedInstruction, DecodeError> {
+    let decoded = match word {
+        NOP => Some(DecodedInstruction::new("nop", Opcode::Nop, word)),
+        PIPELINE_SYNC => Some(DecodedInstruction::new("tb1", Opcode::PipelineSync, word)),
+        HALT => Some(DecodedInstruction::new("halt", Opcode::Halt, word)),
+        _ => match word >> 26 {
+            OP_TRIADIC => decode_triadic(word),
+            OP_BIT_FIELD => decode_bit_field(word),
+            OP_CONTROL => decode_control(word),
+            OP_FLOAT => decode_float(word),
+            _ => decode_immediate(word),
+        },
+    }
+    .ok_or(DecodeError::Unimplemented(word))?;
+    match word & reserved_fields(&decoded.opcode) {
+        0 => Ok(decoded),
+        bits => Err(DecodeError::ReservedBits {
+            mnemonic: decoded.mnemonic,
+            bits,
+        }),
+    }
+}
+
+fn decode_immediate(word: u32) -> Option<DecodedInstruction> {
+    let (mnemonic, opcode): (&'static str, Opcode) = match word >> 26 {
+        0b000001 => ("xmem", Opcode::Exchange),
+        0b000010 => ("ld.hu", Opcode::LoadHalfU),
+        0b000011 => ("ld.bu", Opcode::LoadByteU),
+        0b000100 => ("ld.d", Opcode::LoadDouble),
+        0b000101 => ("ld", Opcode::Load),
+        0b000110 => ("ld.h", Opcode::LoadHalf),
+        0b000111 => ("ld.b", Opcode::LoadByte),
+        0b001000 => ("st.d", Opcode::StoreDouble),
+        0b001001 => ("st", Opcode::Store),
+        0b001010 => ("st.h", Opcode::StoreHalf),
+        0b001011 => ("st.b", Opcode::StoreByte),
+        0b010000 => ("and", Opcode::AndImmediate),
+        0b010001 => ("and.u", Opcode::AndUpper),
+        0b010100 => ("xor", Opcode::XorImmediate),
+        0b010110 => ("or", Opcode::OrImmediate),
+        0b010111 => ("or.u", Opcode::OrUpper),
+        0b011000 => ("addu", Opcode::AddUImmediate),
+        0b011001 => ("subu", Opcode::SubUImmediate),
+        0b011100 => ("add", Opcode::AddImmediate),
+        0b011101 => ("sub", Opcode::SubImmediate),
+        // The 88000 zero-extends the cmp immediate
+        0b011111 => ("cmp", Opcode::CmpUImmediate),
+        0b110000 => ("br", Opcode::Br),
+        0b110001 => ("br.n", Opcode::BrN),
+        0b111110 => ("tbnd", Opcode::TbndImmediate),
+        _ => return None,
+    };
+    Some(DecodedInstruction::new(mnemonic, opcode, word))
+}
+
+fn decode_triadic(word: u32) -> Option<DecodedInstruction> {
+    // Bits 9:5 carry modifiers (carry, complement, scaling) that are not modelled
+    if (word >> 5) & 0x1F != 0 {
+        return None;
+    }
+
+    let (mnemonic, opcode): (&'static str, Opcode) = match (word >> 10) & 0x3F {
+        0b010000 => ("and", Opcode::And),
+        0b010100 => ("xor", Opcode::Xor),
+        0b010110 => ("or", Opcode::Or),
+        0b011000 => ("addu", Opcode::AddU),
+        0b011001 => ("subu", Opcode::SubU),
+        0b011010 => ("divu", Opcode::DivU),
+        0b011011 => ("mul", Opcode::Mul),
+        0b011100 => ("add", Opcode::Add),
+        0b011101 => ("sub", Opcode::Sub),
+        0b011110 => ("div", Opcode::Div),
+        0b011111 => ("cmp", Opcode::Cmp),
+        0b100000 => ("clr", Opcode::Clr),
+        0b100010 => ("set", Opcode::Set),
+        0b100100 => ("ext", Opcode::Ext),
+        0b100110 => ("extu", Opcode::ExtU),
+        0b101000 => ("mak", Opcode::Mak),
+        0b101010 => ("rot", Opcode::Rot),
+        0b111110 => ("tbnd", Opcode::Tbnd),
+        0b111111 => ("rte", Opcode::Rte),
+        // jmp, jsr, ff1 and ff0 take their only source operand from the S2 field
+        0b110000 | 0b110010 | 0b111010 | 0b111011 => return decode_single_source(word),
+        _ => return None,
+    };
+    Some(DecodedInstruction::new(mnemonic, opcode, word))
+}
+
+fn decode_bit_field(word: u32) -> Option<DecodedInstruction> {
+    let (mnemonic, opcode): (&'static str, Opcode) = match (word >> 10) & 0x3F {
+        0b100000 => ("clr", Opcode::ClrImm),
+        0b100010 => ("set", Opcode::SetImm),
+        0b100100 => ("ext", Opcode::ExtImm),
+        0b100110 => ("extu", Opcode::ExtUImm),
+        0b101000 => ("mak", Opcode::MakImm),
+        0b101010 => ("rot", Opcode::RotImm),
+        _ => return None,
+    };
+    let mut decoded = DecodedInstruction::new(mnemonic, opcode, word);
+    // The W5<O5> field specifier occupies bits 9:0
+    decoded.imm = (word & 0x3FF) as i16;
+    Some(decoded)
+}
+
+fn decode_single_source(word: u32) -> Option<DecodedInstruction> {
+    let (mnemonic, opcode): (&'static str, Opcode) = match (word >> 10) & 0x3F {
+        0b110000 => ("jmp", Opcode::Jr),
+        0b110010 => ("jsr", Opcode::Jal),
+        0b111010 => ("ff1", Opcode::FF1),
+        0b111011 => ("ff0", Opcode::FF0),
+        _ => return None,
+    };
+    let mut decoded = DecodedInstruction::new(mnemonic, opcode, word);
+    decoded.s1 = field_s2(word);
+    if mnemonic == "jsr" {
+        // The return address is always linked through r1
+        decoded.d = 1;
+    }
+    Some(decoded)
+}
+
+fn decode_control(word: u32) -> Option<DecodedInstruction> {
+    let crs = (word >> 5) & 0x3F;
+
+    let (mnemonic, opcode): (&'static str, Opcode) = match (word >> 11) & 0x1F {
+        // Only the control registers modelled by the CPU are decoded
+        0b01000 if crs < CPU::CONTROL_REGISTERS => ("ldcr", Opcode::Ldcr(Ldcr { cr_index: crs })),
+        0b10000 if crs < CPU::CONTROL_REGISTERS => ("stcr", Opcode::Stcr(Stcr { cr_index: crs })),
+        _ => return None,
+    };
+    Some(DecodedInstruction::new(mnemonic, opcode, word))
+}
+
+fn decode_float(word: u32) -> Option<DecodedInstruction> {
+    // Only single precision operands (T1 = T2 = TD = 0) are modelled
+    if (word >> 5) & 0x3F != 0 {
+        return None;
+    }
+
+    let (mnemonic, opcode): (&'static str, Opcode) = match (word >> 11) & 0x1F {
+        0b00000 => ("fmul", Opcode::FMul),
+        0b00100 => ("flt", Opcode::IntToFp),
+        0b00101 => ("fadd", Opcode::FAdd),
+        0b00110 => ("fsub", Opcode::FSub),
+        0b00111 => ("fcmp", Opcode::FCmp),
+        0b01010 => ("nint", Opcode::FpToInt),
+        0b01011 => ("trnc", Opcode::FTrunc),
+        0b01110 => ("fdiv", Opcode::FDiv),
+        _ => return None,
+    };
+    let mut decoded = DecodedInstruction::new(mnemonic, opcode, word);
+    if matches!(mnemonic, "flt" | "nint" | "trnc") {
+        // Conversions take their only source operand from the S2 field
+        decoded.s1 = field_s2(word);
+    }
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::Memory;
+
+    #[test]
+    fn test_decode_immediate_fields() {
+        // addu r3, r2, 0x1234
+        let decoded = decode(0x6062_1234).unwrap();
+        assert_eq!(decoded.mnemonic, "addu");
+        assert_eq!(decoded.d, 3);
+        assert_eq!(decoded.s1, 2);
+        assert_eq!(decoded.imm, 0x1234);
+    }
+
+    #[test]
+    fn test_decode_disp26() {
+        assert_eq!(field_disp26(0x03FF_FFFF), -1);
+        assert_eq!(field_disp26(0x0200_0000), -(1 << 25));
+        assert_eq!(field_disp26(0xFC01_0000), 0x1_0000);
+    }
+
+    #[test]
+    fn test_decode_triadic_fields() {
+        // subu r4, r5, r6
+        let decoded = decode(0xF485_6406).unwrap();
+        assert_eq!(decoded.mnemonic, "subu");
+        assert_eq!(decoded.d, 4);
+        assert_eq!(decoded.s1, 5);
+        assert_eq!(decoded.s2, 6);
+
+        // jmp r1 reads its target from the S2 field
+        let decoded = decode(0xF400_C001).unwrap();
+        assert_eq!(decoded.mnemonic, "jmp");
+        assert_eq!(decoded.s1, 1);
+
+        // jsr r2 links through r1
+        let decoded = decode(0xF400_C802).unwrap();
+        assert_eq!(decoded.mnemonic, "jsr");
+        assert_eq!(decoded.s1, 2);
+        assert_eq!(decoded.d, 1);
+    }
+
+    #[test]
+    fn test_decode_nop_sync_and_halt() {
+        assert_eq!(decode(NOP).unwrap().mnemonic, "nop");
+        assert_eq!(decode(PIPELINE_SYNC).unwrap().mnemonic, "tb1");
+        assert_eq!(decode(HALT).unwrap().mnemonic, "halt");
+        // Other tb1 forms are not modelled
+        assert!(decode(0xF000_D801).is_none());
+    }
+
+    #[test]
+    fn test_decode_executes() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        cpu.registers[2] = 40;
+        // addu r3, r2, 2
+        let decoded = decode(0x6062_0002).unwrap();
+        decoded.execute(&mut cpu, &mut memory).unwrap();
+        assert_eq!(cpu.registers[3], 42);
+    }
+
+    #[test]
+    fn test_decoded_execute_checks_privilege() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+        cpu.pc = 0x1000;
+        cpu.sxip = 0x4000;
+
+        // rte in user mode
+        let rte = decode(0xF400_FC00).unwrap();
+        assert_eq!(
+            rte.execute(&mut cpu, &mut memory),
+            Err(ExecError::PrivilegeViolation)
+        );
+        assert!(cpu.has_privilege_violation());
+        assert_eq!(cpu.pc, 0x1000);
+    }
+
+    #[test]
+    fn test_operands_do_not_leak_between_instructions() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+        cpu.registers[2] = 40;
+        cpu.registers[5] = 0xF0;
+        cpu.registers[6] = 0x0F;
+
+        // addu r3, r2, 0x1234 followed by or r4, r5, r6
+        let first = decode(0x6062_1234).unwrap();
+        let second = decode(0xF485_5806).unwrap();
+        first.execute(&mut cpu, &mut memory).unwrap();
+        second.execute(&mut cpu, &mut memory).unwrap();
+        assert_eq!(cpu.registers[3], 40 + 0x1234);
+        assert_eq!(cpu.registers[4], 0xFF);
+
+        // Every field of the second instruction comes from its own word
+        assert_eq!(
+            second.operands(),
+            Operands {
+                d: 4,
+                s1: 5,
+                s2: 6,
+                s3: 0,
+                imm: 0x5806,
+                offset: 0x5806,
+                disp: 0x0085_5806,
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_signed_and_unsigned_byte_loads() {
+        let mut memory = Memory::new();
+        memory.write_byte(0x1000, 0x80).unwrap();
+
+        // ld.b r2, r1, 0 and ld.bu r2, r1, 0
+        for (word, mnemonic, expected) in [
+            (0x1C41_0000, "ld.b", 0xFFFF_FF80),
+            (0x0C41_0000, "ld.bu", 0x0000_0080),
+        ] {
+            let mut cpu = CPU::new();
+            cpu.registers[1] = 0x1000;
+            let decoded = decode(word).unwrap();
+            assert_eq!(decoded.mnemonic, mnemonic);
+            decoded.execute(&mut cpu, &mut memory).unwrap();
+            assert_eq!(cpu.registers[2], expected, "{}", mnemonic);
+        }
+    }
+
+    #[test]
+    fn test_decode_float_conversions() {
+        // trnc r2, r3 reads its operand from the S2 field
+        let decoded = decode(0x8440_5803).unwrap();
+        assert_eq!(decoded.mnemonic, "trnc");
+        assert_eq!((decoded.d, decoded.s1), (2, 3));
+        let decoded = decode(0x8440_5003).unwrap();
+        assert_eq!(decoded.mnemonic, "nint");
+    }
+
+    #[test]
+    fn test_decode_br() {
+        // br -8 and br.n 12
+        let br = decode(0xC3FF_FFFE).unwrap();
+        assert_eq!(br.mnemonic, "br");
+        assert!(matches!(br.opcode, Opcode::Br));
+        assert_eq!(br.disp, -2);
+
+        let br_n = decode(0xC400_0003).unwrap();
+        assert_eq!(br_n.mnemonic, "br.n");
+        assert!(matches!(br_n.opcode, Opcode::BrN));
+        assert_eq!(br_n.disp, 3);
+
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+        cpu.pc = 0x1000;
+        br.execute(&mut cpu, &mut memory).unwrap();
+        assert_eq!(cpu.pc, 0x0FF8);
+    }
+
+    #[test]
+    fn test_decode_rejects_reserved_bits() {
+        // rot r3, r4, <8> uses only the O5 field
+        let rot = try_decode(0xF064_A808).unwrap();
+        assert_eq!(rot.mnemonic, "rot");
+        assert_eq!(rot.imm, 8);
+
+        // (instruction word, mnemonic, reserved bits set)
+        let cases = [
+            (0xF064_A828, "rot", 0x0000_0020),  // rot r3, r4, 1<8>
+            (0xF821_0010, "tbnd", 0x0020_0000), // tbnd with D = r1
+            (0xF420_F802, "tbnd", 0x0020_0000), // tbnd r0, r2 with D = r1
+            (0xF401_FC00, "rte", 0x0001_0000),  // rte with S1 = r1
+            (0xF403_C001, "jmp", 0x0003_0000),  // jmp r1 with S1 = r3
+            (0xF442_E803, "ff1", 0x0002_0000),  // ff1 r2, r3 with S1 = r2
+            (0x8441_5803, "trnc", 0x0001_0000), // trnc r2, r3 with S1 = r1
+            (0x8041_4021, "ldcr", 0x0001_0001), // ldcr r2, cr1 with S1 and S2 set
+            (0x8021_8020, "stcr", 0x0020_0000), // stcr r1, cr1 with D = r1
+        ];
+        for (word, mnemonic, bits) in cases {
+            assert_eq!(
+                try_decode(word).err(),
+                Some(DecodeError::ReservedBits { mnemonic, bits }),
+                "{:#010x}",
+                word
+            );
+            assert!(decode(word).is_none());
+            // The same instruction with its reserved bits clear decodes
+            assert_eq!(try_decode(word & !bits).unwrap().mnemonic, mnemonic);
+        }
+
+        // Full-width 16-bit immediates decode with either signedness
+        assert_eq!(try_decode(0x6062_FFFF).unwrap().imm as u16, 0xFFFF);
+        assert_eq!(try_decode(0x1441_8000).unwrap().imm, -0x8000);
+
+        assert_eq!(
+            try_decode(0xFFFF_FFFF).err(),
+            Some(DecodeError::Unimplemented(0xFFFF_FFFF))
+        );
+    }
+
+    #[test]
+    fn test_decode_tbnd_immediate() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        // tbnd r1, 0x10
+        let decoded = decode(0xF801_0010).unwrap();
+        assert_eq!(decoded.mnemonic, "tbnd");
+        assert_eq!(decoded.s1, 1);
+        assert_eq!(decoded.imm, 0x10);
+
+        cpu.registers[1] = 0x11;
+        assert_eq!(
+            decoded
+                .opcode
+                .try_execute(&mut cpu, &mut memory, &decoded.operands()),
+            Err(ExecError::BoundsCheck)
+        );
+    }
+
+    #[test]
+    fn test_decode_unimplemented() {
+        assert!(decode(0xFFFF_FFFF).is_none());
+        // addu.co is not modelled
+        assert!(decode(0xF485_6106).is_none());
+        // ldcr from a control register that is not modelled
+        assert!(decode(0x8060_4280).is_none());
+    }
+}
@@ -5,81 +5,129 @@
 //! - Immediate variants of arithmetic operations
 //! - Unsigned arithmetic operations
 //! - Special arithmetic operations (mask, find first one/zero)
+//! - Signed and unsigned minimum/maximum
+//! - Saturating clamps to unsigned and byte ranges
 
-use crate::cpu::instructions::Instruction;
+use crate::cpu::instructions::{Instruction, Operands};
 use crate::cpu::CPU;
 use crate::memory::Memory;
 
-/// Add instruction: rd = rs1 + rs2
+/// Add instruction: rd = rs1 + rs2, trapping on signed overflow
 pub struct Add;
 
 impl Instruction for Add {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        cpu.registers[cpu.d] = cpu.registers[cpu.s1].wrapping_add(cpu.registers[cpu.s2]);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1] as i32;
+        let b = cpu.registers[ops.s2] as i32;
+        match a.checked_add(b) {
+            Some(sum) => cpu.registers[ops.d] = sum as u32,
+            None => integer_overflow_trap(cpu),
+        }
     }
 }
 
-/// Add immediate instruction: rd = rs1 + immediate
+/// Add immediate instruction: rd = rs1 + immediate, trapping on signed overflow
 pub struct AddImmediate;
 
 impl Instruction for AddImmediate {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        cpu.registers[cpu.d] = cpu.registers[cpu.s1].wrapping_add(cpu.imm as u32);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1] as i32;
+        match a.checked_add(ops.imm as i32) {
+            Some(sum) => cpu.registers[ops.d] = sum as u32,
+            None => integer_overflow_trap(cpu),
+        }
     }
 }
 
-/// Subtract instruction: rd = rs1 - rs2
+/// Subtract instruction: rd = rs1 - rs2, trapping on signed overflow
 pub struct Sub;
 
 impl Instruction for Sub {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        cpu.registers[cpu.d] = cpu.registers[cpu.s1].wrapping_sub(cpu.registers[cpu.s2]);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1] as i32;
+        let b = cpu.registers[ops.s2] as i32;
+        match a.checked_sub(b) {
+            Some(difference) => cpu.registers[ops.d] = difference as u32,
+            None => integer_overflow_trap(cpu),
+        }
     }
 }
 
-/// Subtract immediate instruction: rd = rs1 - immediate
+/// Subtract immediate instruction: rd = rs1 - immediate, trapping on signed overflow
 pub struct SubImmediate;
 
 impl Instruction for SubImmediate {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        cpu.registers[cpu.d] = cpu.registers[cpu.s1].wrapping_sub(cpu.imm as u32);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1] as i32;
+        match a.checked_sub(ops.imm as i32) {
+            Some(difference) => cpu.registers[ops.d] = difference as u32,
+            None => integer_overflow_trap(cpu),
+        }
     }
 }
 
+/// Flags a signed integer overflow and takes the integer overflow exception.
+///
+/// The destination register is left unmodified, as on the hardware.
+fn integer_overflow_trap(cpu: &mut CPU) {
+    cpu.cr0 |= CPU::CR0_OVERFLOW;
+    cpu.raise_exception(CPU::VECTOR_INTEGER_OVERFLOW);
+}
+
 /// Multiply instruction: rd = rs1 * rs2 (signed)
+///
+/// The low 32 bits of the product are always written; `CR0_OVERFLOW` is set
+/// if they do not represent the full signed product.
 pub struct Mul;
 
 impl Instruction for Mul {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        cpu.registers[cpu.d] = cpu.registers[cpu.s1].wrapping_mul(cpu.registers[cpu.s2]);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let product = cpu.registers[ops.s1] as i32 as i64 * cpu.registers[ops.s2] as i32 as i64;
+        if product != product as i32 as i64 {
+            cpu.cr0 |= CPU::CR0_OVERFLOW;
+        }
+        cpu.registers[ops.d] = product as u32;
     }
 }
 
 /// Unsigned multiply instruction: rd = rs1 * rs2 (unsigned)
+///
+/// The low 32 bits of the product are always written; `CR0_OVERFLOW` is set
+/// if the high 32 bits are not zero.
 pub struct MulU;
 
 impl Instruction for MulU {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let result = (cpu.registers[cpu.s1] as u64 * cpu.registers[cpu.s2] as u64) as u32;
-        cpu.registers[cpu.d] = result;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let product = cpu.registers[ops.s1] as u64 * cpu.registers[ops.s2] as u64;
+        if product >> 32 != 0 {
+            cpu.cr0 |= CPU::CR0_OVERFLOW;
+        }
+        cpu.registers[ops.d] = product as u32;
     }
 }
 
+/// Flags an integer division by zero and takes the integer divide exception.
+///
+/// The destination register is left unmodified, as on the hardware.
+fn integer_divide_trap(cpu: &mut CPU) {
+    cpu.cr0 |= CPU::CR0_DIVIDE_ZERO;
+    cpu.raise_exception(CPU::VECTOR_INTEGER_DIVIDE);
+}
+
 /// Divide instruction: rd = rs1 / rs2 (signed)
 pub struct Div;
 
 impl Instruction for Div {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = cpu.registers[cpu.s1] as i32;
-        let b = cpu.registers[cpu.s2] as i32;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1] as i32;
+        let b = cpu.registers[ops.s2] as i32;
         if b == 0 {
-            cpu.cr0 |= CPU::CR0_FP_DIVZERO;
-            cpu.registers[cpu.d] = 0;
+            integer_divide_trap(cpu);
         } else if a == i32::MIN && b == -1 {
-            // Handle MIN_INT / -1 overflow case
-            cpu.registers[cpu.d] = a as u32;
+            // MIN_INT / -1 is not representable: an overflow, not a divide by zero
+            integer_overflow_trap(cpu);
         } else {
-            cpu.registers[cpu.d] = (a / b) as u32;
+            cpu.registers[ops.d] = (a / b) as u32;
         }
     }
 }
@@ -88,14 +136,12 @@ impl Instruction for Div {
 pub struct DivU;
 
 impl Instruction for DivU {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = cpu.registers[cpu.s1];
-        let b = cpu.registers[cpu.s2];
-        if b == 0 {
-            cpu.cr0 |= CPU::CR0_FP_DIVZERO;
-            cpu.registers[cpu.d] = 0;
-        } else {
-            cpu.registers[cpu.d] = a / b;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1];
+        let b = cpu.registers[ops.s2];
+        match a.checked_div(b) {
+            Some(quotient) => cpu.registers[ops.d] = quotient,
+            None => integer_divide_trap(cpu),
         }
     }
 }
@@ -104,38 +150,37 @@ impl Instruction for DivU {
 pub struct Mask;
 
 impl Instruction for Mask {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let value = cpu.registers[cpu.s1];
-        let mask = cpu.registers[cpu.s2];
-        cpu.registers[cpu.d] = value & mask;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let value = cpu.registers[ops.s1];
+        let mask = cpu.registers[ops.s2];
+        cpu.registers[ops.d] = value & mask;
     }
 }
 
-/// Find first 1 instruction: finds position of first set bit
+/// Find first 1 instruction: rd = bit number of the most significant set bit of rs1, or 32
 pub struct FF1;
 
 impl Instruction for FF1 {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let value = cpu.registers[cpu.s1];
-        let mut pos = 0;
-        while pos < 32 && (value & (1 << pos)) == 0 {
-            pos += 1;
-        }
-        cpu.registers[cpu.d] = pos;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = most_significant_one(cpu.registers[ops.s1]);
     }
 }
 
-/// Find first 0 instruction: finds position of first clear bit
+/// Find first 0 instruction: rd = bit number of the most significant clear bit of rs1, or 32
 pub struct FF0;
 
 impl Instruction for FF0 {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let value = cpu.registers[cpu.s1];
-        let mut pos = 0;
-        while pos < 32 && (value & (1 << pos)) != 0 {
-            pos += 1;
-        }
-        cpu.registers[cpu.d] = pos;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = most_significant_one(!cpu.registers[ops.s1]);
+    }
+}
+
+/// Returns the bit number of the most significant set bit, or 32 if none is set.
+fn most_significant_one(value: u32) -> u32 {
+    if value == 0 {
+        32
+    } else {
+        31 - value.leading_zeros()
     }
 }
 
@@ -143,8 +188,8 @@ impl Instruction for FF0 {
 pub struct AddU;
 
 impl Instruction for AddU {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        cpu.registers[cpu.d] = cpu.registers[cpu.s1].wrapping_add(cpu.registers[cpu.s2]);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = cpu.registers[ops.s1].wrapping_add(cpu.registers[ops.s2]);
     }
 }
 
@@ -152,8 +197,8 @@ impl Instruction for AddU {
 pub struct AddUImmediate;
 
 impl Instruction for AddUImmediate {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        cpu.registers[cpu.d] = cpu.registers[cpu.s1].wrapping_add(cpu.imm as u32);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = cpu.registers[ops.s1].wrapping_add(ops.imm as u32);
     }
 }
 
@@ -161,8 +206,8 @@ impl Instruction for AddUImmediate {
 pub struct SubU;
 
 impl Instruction for SubU {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        cpu.registers[cpu.d] = cpu.registers[cpu.s1].wrapping_sub(cpu.registers[cpu.s2]);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = cpu.registers[ops.s1].wrapping_sub(cpu.registers[ops.s2]);
     }
 }
 
@@ -170,37 +215,76 @@ impl Instruction for SubU {
 pub struct SubUImmediate;
 
 impl Instruction for SubUImmediate {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        cpu.registers[cpu.d] = cpu.registers[cpu.s1].wrapping_sub(cpu.imm as u32);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = cpu.registers[ops.s1].wrapping_sub(ops.imm as u32);
     }
 }
 
-/// Compare instruction: sets condition codes based on signed comparison
+/// `cmp` result bit: rs1 == rs2
+pub const CMP_EQ: u32 = 1 << 2;
+/// `cmp` result bit: rs1 != rs2
+pub const CMP_NE: u32 = 1 << 3;
+/// `cmp` result bit: rs1 > rs2, signed
+pub const CMP_GT: u32 = 1 << 4;
+/// `cmp` result bit: rs1 <= rs2, signed
+pub const CMP_LE: u32 = 1 << 5;
+/// `cmp` result bit: rs1 < rs2, signed
+pub const CMP_LT: u32 = 1 << 6;
+/// `cmp` result bit: rs1 >= rs2, signed
+pub const CMP_GE: u32 = 1 << 7;
+/// `cmp` result bit: rs1 > rs2, unsigned (higher)
+pub const CMP_HI: u32 = 1 << 8;
+/// `cmp` result bit: rs1 <= rs2, unsigned (lower or same)
+pub const CMP_LS: u32 = 1 << 9;
+/// `cmp` result bit: rs1 < rs2, unsigned (lower)
+pub const CMP_LO: u32 = 1 << 10;
+/// `cmp` result bit: rs1 >= rs2, unsigned (higher or same)
+pub const CMP_HS: u32 = 1 << 11;
+
+/// Computes the 88000 `cmp` bit vector, encoding every signed and unsigned
+/// relation between `a` and `b` so a single bit test selects the condition.
+pub fn compare_bits(a: u32, b: u32) -> u32 {
+    let relations = [
+        (a == b, CMP_EQ),
+        (a != b, CMP_NE),
+        ((a as i32) > (b as i32), CMP_GT),
+        ((a as i32) <= (b as i32), CMP_LE),
+        ((a as i32) < (b as i32), CMP_LT),
+        ((a as i32) >= (b as i32), CMP_GE),
+        (a > b, CMP_HI),
+        (a <= b, CMP_LS),
+        (a < b, CMP_LO),
+        (a >= b, CMP_HS),
+    ];
+    relations
+        .into_iter()
+        .filter(|&(holds, _)| holds)
+        .fold(0, |bits, (_, bit)| bits | bit)
+}
+
+/// Sets the condition codes in cr0 from the result of a comparison.
+fn set_compare_flags(cpu: &mut CPU, ordering: std::cmp::Ordering) {
+    cpu.cr0 &= !(CPU::CR0_EQUAL | CPU::CR0_LESS | CPU::CR0_GREATER);
+    cpu.cr0 |= match ordering {
+        std::cmp::Ordering::Equal => CPU::CR0_EQUAL,
+        std::cmp::Ordering::Less => CPU::CR0_LESS,
+        std::cmp::Ordering::Greater => CPU::CR0_GREATER,
+    };
+}
+
+/// Compare instruction: rd = the [`compare_bits`] vector of rs1 and rs2
+///
+/// The condition codes are also set based on signed comparison.
 pub struct Cmp;
 
 impl Instruction for Cmp {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = cpu.registers[cpu.s1] as i32;
-        let b = cpu.registers[cpu.s2] as i32;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1] as i32;
+        let b = cpu.registers[ops.s2] as i32;
+        cpu.registers[ops.d] = compare_bits(cpu.registers[ops.s1], cpu.registers[ops.s2]);
 
         // Set condition codes
-        match a.cmp(&b) {
-            std::cmp::Ordering::Equal => {
-                cpu.cr0 |= CPU::CR0_EQUAL;
-                cpu.cr0 &= !CPU::CR0_LESS;
-                cpu.cr0 &= !CPU::CR0_GREATER;
-            }
-            std::cmp::Ordering::Less => {
-                cpu.cr0 |= CPU::CR0_LESS;
-                cpu.cr0 &= !CPU::CR0_EQUAL;
-                cpu.cr0 &= !CPU::CR0_GREATER;
-            }
-            std::cmp::Ordering::Greater => {
-                cpu.cr0 |= CPU::CR0_GREATER;
-                cpu.cr0 &= !CPU::CR0_EQUAL;
-                cpu.cr0 &= !CPU::CR0_LESS;
-            }
-        }
+        set_compare_flags(cpu, a.cmp(&b));
     }
 }
 
@@ -208,28 +292,40 @@ impl Instruction for Cmp {
 pub struct CmpU;
 
 impl Instruction for CmpU {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = cpu.registers[cpu.s1];
-        let b = cpu.registers[cpu.s2];
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1];
+        let b = cpu.registers[ops.s2];
 
         // Set condition codes
-        match a.cmp(&b) {
-            std::cmp::Ordering::Equal => {
-                cpu.cr0 |= CPU::CR0_EQUAL;
-                cpu.cr0 &= !CPU::CR0_LESS;
-                cpu.cr0 &= !CPU::CR0_GREATER;
-            }
-            std::cmp::Ordering::Less => {
-                cpu.cr0 |= CPU::CR0_LESS;
-                cpu.cr0 &= !CPU::CR0_EQUAL;
-                cpu.cr0 &= !CPU::CR0_GREATER;
-            }
-            std::cmp::Ordering::Greater => {
-                cpu.cr0 |= CPU::CR0_GREATER;
-                cpu.cr0 &= !CPU::CR0_EQUAL;
-                cpu.cr0 &= !CPU::CR0_LESS;
-            }
-        }
+        set_compare_flags(cpu, a.cmp(&b));
+    }
+}
+
+/// Compare immediate instruction: rd = the [`compare_bits`] vector of rs1 and SignExtend(imm)
+///
+/// The condition codes are also set based on signed comparison.
+pub struct CmpImmediate;
+
+impl Instruction for CmpImmediate {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1];
+        let b = ops.imm as i32 as u32;
+        cpu.registers[ops.d] = compare_bits(a, b);
+        set_compare_flags(cpu, (a as i32).cmp(&(b as i32)));
+    }
+}
+
+/// Compare unsigned immediate instruction: rd = the [`compare_bits`] vector of rs1 and ZeroExtend(imm)
+///
+/// The condition codes are also set based on unsigned comparison.
+pub struct CmpUImmediate;
+
+impl Instruction for CmpUImmediate {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1];
+        let b = ops.imm as u16 as u32;
+        cpu.registers[ops.d] = compare_bits(a, b);
+        set_compare_flags(cpu, a.cmp(&b));
     }
 }
 
@@ -237,14 +333,10 @@ impl Instruction for CmpU {
 pub struct LMul;
 
 impl Instruction for LMul {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = cpu.registers[cpu.s1] as i32 as i64;
-        let b = cpu.registers[cpu.s2] as i32 as i64;
-        let result = a.wrapping_mul(b);
-
-        // Store high 32 bits in d, low 32 bits in d+1
-        cpu.registers[cpu.d] = (result >> 32) as u32;
-        cpu.registers[cpu.d.wrapping_add(1)] = result as u32;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1] as i32 as i64;
+        let b = cpu.registers[ops.s2] as i32 as i64;
+        cpu.write_register_pair(ops.d, a.wrapping_mul(b) as u64);
     }
 }
 
@@ -252,53 +344,52 @@ impl Instruction for LMul {
 pub struct LMulU;
 
 impl Instruction for LMulU {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = cpu.registers[cpu.s1] as u64;
-        let b = cpu.registers[cpu.s2] as u64;
-        let result = a.wrapping_mul(b);
-
-        // Store high 32 bits in d, low 32 bits in d+1
-        cpu.registers[cpu.d] = (result >> 32) as u32;
-        cpu.registers[cpu.d.wrapping_add(1)] = result as u32;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1] as u64;
+        let b = cpu.registers[ops.s2] as u64;
+        cpu.write_register_pair(ops.d, a.wrapping_mul(b));
     }
 }
 
 /// Double-precision divide unsigned instruction: quotient in rd, remainder in rd+1
+///
+/// The dividend is the register pair rs1:rs1+1. Both pairs must start at an
+/// even register; a quotient destined for r0 is discarded.
 pub struct DivUD;
 
 impl Instruction for DivUD {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let dividend =
-            ((cpu.registers[cpu.s1] as u64) << 32) | cpu.registers[cpu.s1.wrapping_add(1)] as u64;
-        let divisor = cpu.registers[cpu.s2];
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        if !cpu.check_register_pair(ops.s1) || !cpu.check_register_pair(ops.d) {
+            return;
+        }
+        let dividend = ((cpu.registers[ops.s1] as u64) << 32) | cpu.registers[ops.s1 + 1] as u64;
+        let divisor = cpu.registers[ops.s2];
 
         if divisor == 0 {
-            cpu.cr0 |= CPU::CR0_FP_DIVZERO;
-            cpu.registers[cpu.d] = 0;
-            cpu.registers[cpu.d.wrapping_add(1)] = 0;
+            integer_divide_trap(cpu);
         } else {
             let quotient = dividend / divisor as u64;
             let remainder = dividend % divisor as u64;
 
-            cpu.registers[cpu.d] = quotient as u32;
-            cpu.registers[cpu.d.wrapping_add(1)] = remainder as u32;
+            cpu.write_register_pair(ops.d, (quotient << 32) | (remainder & 0xFFFF_FFFF));
         }
     }
 }
 
 /// Remainder instruction: rd = rs1 % rs2
+///
+/// MIN_INT % -1 is 0: unlike the quotient, the remainder is representable.
 pub struct Rem;
 
 impl Instruction for Rem {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = cpu.registers[cpu.s1] as i32;
-        let b = cpu.registers[cpu.s2] as i32;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1] as i32;
+        let b = cpu.registers[ops.s2] as i32;
 
         if b == 0 {
-            cpu.cr0 |= CPU::CR0_FP_DIVZERO;
-            cpu.registers[cpu.d] = 0;
+            integer_divide_trap(cpu);
         } else {
-            cpu.registers[cpu.d] = (a % b) as u32;
+            cpu.registers[ops.d] = a.wrapping_rem(b) as u32;
         }
     }
 }
@@ -307,312 +398,517 @@ impl Instruction for Rem {
 pub struct RemU;
 
 impl Instruction for RemU {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = cpu.registers[cpu.s1];
-        let b = cpu.registers[cpu.s2];
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1];
+        let b = cpu.registers[ops.s2];
 
         if b == 0 {
-            cpu.cr0 |= CPU::CR0_FP_DIVZERO;
-            cpu.registers[cpu.d] = 0;
+            integer_divide_trap(cpu);
         } else {
-            cpu.registers[cpu.d] = a % b;
+            cpu.registers[ops.d] = a % b;
         }
     }
 }
 
+/// Signed minimum instruction: rd = min(rs1, rs2)
+pub struct MinS;
+
+impl Instruction for MinS {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1] as i32;
+        let b = cpu.registers[ops.s2] as i32;
+        cpu.registers[ops.d] = a.min(b) as u32;
+    }
+}
+
+/// Signed maximum instruction: rd = max(rs1, rs2)
+pub struct MaxS;
+
+impl Instruction for MaxS {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1] as i32;
+        let b = cpu.registers[ops.s2] as i32;
+        cpu.registers[ops.d] = a.max(b) as u32;
+    }
+}
+
+/// Unsigned minimum instruction: rd = min(rs1, rs2)
+pub struct MinU;
+
+impl Instruction for MinU {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = cpu.registers[ops.s1].min(cpu.registers[ops.s2]);
+    }
+}
+
+/// Unsigned maximum instruction: rd = max(rs1, rs2)
+pub struct MaxU;
+
+impl Instruction for MaxU {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = cpu.registers[ops.s1].max(cpu.registers[ops.s2]);
+    }
+}
+
+/// Clamp unsigned instruction: rd = max(rs1, 0), treating rs1 as signed
+pub struct ClampU;
+
+impl Instruction for ClampU {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = (cpu.registers[ops.s1] as i32).max(0) as u32;
+    }
+}
+
+/// Clamp to byte instruction: rd = rs1 clamped to 0..=255, treating rs1 as signed
+pub struct ClampTo8;
+
+impl Instruction for ClampTo8 {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = (cpu.registers[ops.s1] as i32).clamp(0, 255) as u32;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cpu::instructions::ExecError;
 
     #[test]
     fn test_add() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 10;
         cpu.registers[2] = 20;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        Add.execute(&mut cpu, &mut memory);
+        Add.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 30);
 
         // Test overflow
         cpu.registers[1] = u32::MAX;
         cpu.registers[2] = 1;
-        Add.execute(&mut cpu, &mut memory);
+        Add.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0);
     }
 
     #[test]
     fn test_add_immediate() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 10;
-        cpu.d = 2;
-        cpu.s1 = 1;
-        cpu.imm = 20;
+        ops.d = 2;
+        ops.s1 = 1;
+        ops.imm = 20;
 
-        AddImmediate.execute(&mut cpu, &mut memory);
+        AddImmediate.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2], 30);
 
         // Test negative immediate
         cpu.registers[1] = 30;
-        cpu.imm = -10;
-        AddImmediate.execute(&mut cpu, &mut memory);
+        ops.imm = -10;
+        AddImmediate.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2], 20);
     }
 
     #[test]
     fn test_sub() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 30;
         cpu.registers[2] = 20;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        Sub.execute(&mut cpu, &mut memory);
+        Sub.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 10);
 
         // Test underflow
         cpu.registers[1] = 0;
         cpu.registers[2] = 1;
-        Sub.execute(&mut cpu, &mut memory);
+        Sub.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], u32::MAX);
     }
 
     #[test]
     fn test_sub_immediate() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 30;
-        cpu.d = 2;
-        cpu.s1 = 1;
-        cpu.imm = 20;
+        ops.d = 2;
+        ops.s1 = 1;
+        ops.imm = 20;
 
-        SubImmediate.execute(&mut cpu, &mut memory);
+        SubImmediate.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2], 10);
 
         // Test negative immediate
         cpu.registers[1] = 20;
-        cpu.imm = -10;
-        SubImmediate.execute(&mut cpu, &mut memory);
+        ops.imm = -10;
+        SubImmediate.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2], 30);
     }
 
     #[test]
     fn test_mul() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 5;
         cpu.registers[2] = 4;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        Mul.execute(&mut cpu, &mut memory);
+        Mul.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 20);
 
         // Test signed multiplication
         cpu.registers[1] = -5i32 as u32;
         cpu.registers[2] = 4;
-        Mul.execute(&mut cpu, &mut memory);
+        Mul.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3] as i32, -20);
+        assert_eq!(cpu.cr0 & CPU::CR0_OVERFLOW, 0);
+    }
+
+    #[test]
+    fn test_mul_overflow_flag() {
+        let mut memory = Memory::new();
+        let mut ops = Operands::default();
+
+        for mul in [&Mul as &dyn Instruction, &MulU] {
+            let mut cpu = CPU::new();
+            cpu.registers[1] = 0x10000;
+            cpu.registers[2] = 0x10000;
+            ops.d = 3;
+            ops.s1 = 1;
+            ops.s2 = 2;
+
+            mul.execute(&mut cpu, &mut memory, &ops);
+            assert_eq!(cpu.registers[3], 0);
+            assert_ne!(cpu.cr0 & CPU::CR0_OVERFLOW, 0);
+        }
+
+        // A negative product that fits is not a signed overflow
+        let mut cpu = CPU::new();
+        cpu.registers[1] = -0x8000i32 as u32;
+        cpu.registers[2] = 0x10000;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
+        Mul.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[3], i32::MIN as u32);
+        assert_eq!(cpu.cr0 & CPU::CR0_OVERFLOW, 0);
     }
 
     #[test]
     fn test_mulu() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 5;
         cpu.registers[2] = 4;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        MulU.execute(&mut cpu, &mut memory);
+        MulU.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 20);
 
         // Test large numbers
+        assert_eq!(cpu.cr0 & CPU::CR0_OVERFLOW, 0);
         cpu.registers[1] = 0xFFFFFFFF;
         cpu.registers[2] = 2;
-        MulU.execute(&mut cpu, &mut memory);
+        MulU.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0xFFFFFFFE);
+        assert_ne!(cpu.cr0 & CPU::CR0_OVERFLOW, 0);
     }
 
     #[test]
     fn test_mask() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 0xFFFFFFFF;
         cpu.registers[2] = 0x0000FFFF;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        Mask.execute(&mut cpu, &mut memory);
+        Mask.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0x0000FFFF);
     }
 
     #[test]
     fn test_ff0() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test finding first 0 in various positions
         cpu.registers[1] = 0xFFFFFFFE; // First 0 at position 0
-        cpu.d = 2;
-        cpu.s1 = 1;
+        ops.d = 2;
+        ops.s1 = 1;
 
-        FF0.execute(&mut cpu, &mut memory);
+        FF0.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2], 0);
 
         cpu.registers[1] = 0xFFFFFEFF; // First 0 at position 8
-        FF0.execute(&mut cpu, &mut memory);
+        FF0.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2], 8);
 
         cpu.registers[1] = 0x7FFFFFFF; // First 0 at position 31
-        FF0.execute(&mut cpu, &mut memory);
+        FF0.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2], 31);
 
         // Test with no 0s
         cpu.registers[1] = 0xFFFFFFFF;
-        FF0.execute(&mut cpu, &mut memory);
+        FF0.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2], 32);
+
+        // The scan starts at the most significant bit
+        cpu.registers[1] = 0xFFFF_0000;
+        FF0.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 15);
+        cpu.registers[1] = 0x7FFF_FFFE;
+        FF0.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 31);
     }
 
     #[test]
     fn test_ff1() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test finding first 1 in various positions
         cpu.registers[1] = 0x00000001; // First 1 at position 0
-        cpu.d = 2;
-        cpu.s1 = 1;
+        ops.d = 2;
+        ops.s1 = 1;
 
-        FF1.execute(&mut cpu, &mut memory);
+        FF1.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2], 0);
 
         cpu.registers[1] = 0x00000100; // First 1 at position 8
-        FF1.execute(&mut cpu, &mut memory);
+        FF1.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2], 8);
 
         cpu.registers[1] = 0x80000000; // First 1 at position 31
-        FF1.execute(&mut cpu, &mut memory);
+        FF1.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2], 31);
 
         // Test with no 1s
         cpu.registers[1] = 0;
-        FF1.execute(&mut cpu, &mut memory);
+        FF1.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2], 32);
+
+        // The scan starts at the most significant bit
+        cpu.registers[1] = 0x0000_0101;
+        FF1.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 8);
+        cpu.registers[1] = 0x8000_0001;
+        FF1.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 31);
     }
 
     #[test]
     fn test_div() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test normal division
         cpu.registers[1] = 20;
         cpu.registers[2] = 5;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        Div.execute(&mut cpu, &mut memory);
+        Div.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 4);
 
         // Test negative division
         cpu.registers[1] = (-20i32) as u32;
         cpu.registers[2] = 5;
-        Div.execute(&mut cpu, &mut memory);
+        Div.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3] as i32, -4);
 
         // Test division by zero
         cpu.registers[2] = 0;
-        Div.execute(&mut cpu, &mut memory);
-        assert_ne!(cpu.cr0 & CPU::CR0_FP_DIVZERO, 0);
+        Div.execute(&mut cpu, &mut memory, &ops);
+        assert_ne!(cpu.cr0 & CPU::CR0_DIVIDE_ZERO, 0);
+        assert_eq!(cpu.vector, CPU::VECTOR_INTEGER_DIVIDE);
+        assert_eq!(cpu.registers[3] as i32, -4);
     }
 
     #[test]
     fn test_divu() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test normal division
         cpu.registers[1] = 20;
         cpu.registers[2] = 5;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        DivU.execute(&mut cpu, &mut memory);
+        DivU.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 4);
 
         // Test large numbers
         cpu.registers[1] = 0xFFFFFFFF;
         cpu.registers[2] = 2;
-        DivU.execute(&mut cpu, &mut memory);
+        DivU.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0x7FFFFFFF);
 
         // Test division by zero
         cpu.registers[2] = 0;
-        DivU.execute(&mut cpu, &mut memory);
-        assert_ne!(cpu.cr0 & CPU::CR0_FP_DIVZERO, 0);
+        DivU.execute(&mut cpu, &mut memory, &ops);
+        assert_ne!(cpu.cr0 & CPU::CR0_DIVIDE_ZERO, 0);
+        assert_eq!(cpu.vector, CPU::VECTOR_INTEGER_DIVIDE);
+        assert_eq!(cpu.registers[3], 0x7FFFFFFF);
     }
 
     #[test]
     fn test_addu() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 0xFFFFFFFF;
         cpu.registers[2] = 1;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        AddU.execute(&mut cpu, &mut memory);
+        AddU.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0); // Unsigned overflow wraps
     }
 
     #[test]
     fn test_subu() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 0;
         cpu.registers[2] = 1;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        SubU.execute(&mut cpu, &mut memory);
+        SubU.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0xFFFFFFFF); // Unsigned underflow wraps
     }
 
+    #[test]
+    fn test_cmp_bit_vector_signed_and_unsigned() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        // -1 is less than 1 signed, but higher unsigned
+        cpu.registers[1] = 0xFFFF_FFFF;
+        cpu.registers[2] = 1;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
+
+        Cmp.execute(&mut cpu, &mut memory, &ops);
+        let bits = cpu.registers[3];
+        assert_eq!(bits, CMP_NE | CMP_LT | CMP_LE | CMP_HI | CMP_HS);
+        assert_ne!(cpu.cr0 & CPU::CR0_LESS, 0);
+
+        assert_eq!(
+            compare_bits(5, 5),
+            CMP_EQ | CMP_LE | CMP_GE | CMP_LS | CMP_HS
+        );
+        assert_eq!(
+            compare_bits(1, 0xFFFF_FFFF),
+            CMP_NE | CMP_GT | CMP_GE | CMP_LO | CMP_LS
+        );
+    }
+
+    #[test]
+    fn test_cmp_immediate_matches_register_compare() {
+        let mut memory = Memory::new();
+        let mut ops = Operands::default();
+
+        for (value, imm) in [
+            (5u32, 0i16),
+            (0, 0),
+            (0xFFFF_FFFF, 0),
+            (-8i32 as u32, -3),
+            (7, -3),
+        ] {
+            let mut reg_cpu = CPU::new();
+            reg_cpu.registers[1] = value;
+            reg_cpu.registers[2] = imm as i32 as u32;
+            ops.d = 3;
+            ops.s1 = 1;
+            ops.s2 = 2;
+            Cmp.execute(&mut reg_cpu, &mut memory, &ops);
+
+            let mut imm_cpu = CPU::new();
+            imm_cpu.registers[1] = value;
+            ops.imm = imm;
+            ops.d = 3;
+            ops.s1 = 1;
+            CmpImmediate.execute(&mut imm_cpu, &mut memory, &ops);
+
+            assert_eq!(
+                imm_cpu.registers[3], reg_cpu.registers[3],
+                "{} {}",
+                value, imm
+            );
+            assert_eq!(imm_cpu.cr0, reg_cpu.cr0, "{} {}", value, imm);
+        }
+
+        // The unsigned form zero-extends: -3 becomes 0xFFFD
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        cpu.registers[1] = 0xFFFD;
+        ops.imm = -3;
+        ops.d = 3;
+        ops.s1 = 1;
+        CmpUImmediate.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[3], compare_bits(0xFFFD, 0xFFFD));
+        assert_ne!(cpu.cr0 & CPU::CR0_EQUAL, 0);
+
+        cpu.registers[1] = 0;
+        ops.imm = 0;
+        CmpUImmediate.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[3], CMP_EQ | CMP_LE | CMP_GE | CMP_LS | CMP_HS);
+    }
+
     #[test]
     fn test_cmp() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test equal
         cpu.registers[1] = 10;
         cpu.registers[2] = 10;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        Cmp.execute(&mut cpu, &mut memory);
+        Cmp.execute(&mut cpu, &mut memory, &ops);
         assert_ne!(cpu.cr0 & CPU::CR0_EQUAL, 0);
         assert_eq!(cpu.cr0 & CPU::CR0_LESS, 0);
         assert_eq!(cpu.cr0 & CPU::CR0_GREATER, 0);
@@ -622,7 +918,7 @@ mod tests {
         cpu.registers[2] = 10;
         cpu.cr0 = 0;
 
-        Cmp.execute(&mut cpu, &mut memory);
+        Cmp.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.cr0 & CPU::CR0_EQUAL, 0);
         assert_ne!(cpu.cr0 & CPU::CR0_LESS, 0);
         assert_eq!(cpu.cr0 & CPU::CR0_GREATER, 0);
@@ -632,7 +928,7 @@ mod tests {
         cpu.registers[2] = 10;
         cpu.cr0 = 0;
 
-        Cmp.execute(&mut cpu, &mut memory);
+        Cmp.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.cr0 & CPU::CR0_EQUAL, 0);
         assert_eq!(cpu.cr0 & CPU::CR0_LESS, 0);
         assert_ne!(cpu.cr0 & CPU::CR0_GREATER, 0);
@@ -641,328 +937,563 @@ mod tests {
     #[test]
     fn test_lmul() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test normal multiplication
         cpu.registers[1] = 0x12345678;
         cpu.registers[2] = 0x11111111;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 4;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        LMul.execute(&mut cpu, &mut memory);
+        LMul.execute(&mut cpu, &mut memory, &ops);
 
         // Expected result: 0x12345678 * 0x11111111
         let expected = (0x12345678i64 * 0x11111111i64) as u64;
-        let actual = ((cpu.registers[3] as u64) << 32) | cpu.registers[4] as u64;
-        assert_eq!(actual, expected as u64);
+        let actual = ((cpu.registers[4] as u64) << 32) | cpu.registers[5] as u64;
+        assert_eq!(actual, expected);
 
         // Test negative numbers
         cpu.registers[1] = (-1i32) as u32;
         cpu.registers[2] = 2;
 
-        LMul.execute(&mut cpu, &mut memory);
+        LMul.execute(&mut cpu, &mut memory, &ops);
         let expected = (-2i64) as u64;
-        let actual = ((cpu.registers[3] as u64) << 32) | cpu.registers[4] as u64;
+        let actual = ((cpu.registers[4] as u64) << 32) | cpu.registers[5] as u64;
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_lmul_register_pair_alignment() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+        cpu.registers[1] = 0xFFFF_FFFF;
+        cpu.registers[2] = 2;
+        ops.s1 = 1;
+        ops.s2 = 2;
+
+        // r31 has no partner register; the result must not wrap into r0
+        ops.d = 31;
+        LMulU.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.vector, CPU::VECTOR_ILLEGAL_OPERAND);
+        assert_eq!(
+            cpu.pc,
+            cpu.exception_handler_address(CPU::VECTOR_ILLEGAL_OPERAND)
+        );
+        assert_eq!(cpu.registers[0], 0);
+        assert_eq!(cpu.registers[31], 0);
+
+        let mut cpu = CPU::new();
+        cpu.registers[1] = 0xFFFF_FFFF;
+        cpu.registers[3] = 2;
+        ops.s1 = 1;
+        ops.s2 = 3;
+        ops.d = 2;
+        LMulU.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.vector, 0);
+        assert_eq!(cpu.registers[2], 1);
+        assert_eq!(cpu.registers[3], 0xFFFF_FFFE);
+    }
+
     #[test]
     fn test_divud() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Set up a 64-bit dividend
-        cpu.registers[1] = 0x00000000; // High word
-        cpu.registers[2] = 0x00000064; // Low word (100 in decimal)
-        cpu.registers[3] = 0x00000002; // Divisor
-        cpu.s1 = 1;
-        cpu.s2 = 3;
-        cpu.d = 4;
-
-        DivUD.execute(&mut cpu, &mut memory);
+        cpu.registers[2] = 0x00000000; // High word
+        cpu.registers[3] = 0x00000064; // Low word (100 in decimal)
+        cpu.registers[1] = 0x00000002; // Divisor
+        ops.s1 = 2;
+        ops.s2 = 1;
+        ops.d = 4;
+
+        DivUD.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[4], 50); // Quotient
         assert_eq!(cpu.registers[5], 0); // Remainder
 
         // Test division by zero
-        cpu.registers[3] = 0; // Divisor
+        cpu.registers[1] = 0; // Divisor
         cpu.cr0 = 0;
 
-        DivUD.execute(&mut cpu, &mut memory);
-        assert_ne!(cpu.cr0 & CPU::CR0_FP_DIVZERO, 0);
-        assert_eq!(cpu.registers[4], 0);
+        DivUD.execute(&mut cpu, &mut memory, &ops);
+        assert_ne!(cpu.cr0 & CPU::CR0_DIVIDE_ZERO, 0);
+        assert_eq!(cpu.registers[4], 50);
         assert_eq!(cpu.registers[5], 0);
     }
 
+    #[test]
+    fn test_divud_register_pairs() {
+        let mut memory = Memory::new();
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        cpu.registers[2] = 0;
+        cpu.registers[3] = 107;
+        cpu.registers[4] = 10;
+        ops.s1 = 2;
+        ops.s2 = 4;
+
+        // The quotient is discarded into r0; the remainder still lands in r1
+        ops.d = 0;
+        assert_eq!(DivUD.try_execute(&mut cpu, &mut memory, &ops), Ok(()));
+        assert_eq!(cpu.registers[0], 0);
+        assert_eq!(cpu.registers[1], 7);
+
+        // r31 has no partner register
+        ops.d = 31;
+        assert_eq!(
+            DivUD.try_execute(&mut cpu, &mut memory, &ops),
+            Err(ExecError::IllegalOperand)
+        );
+        assert_eq!(cpu.registers[31], 0);
+
+        // Nor may the dividend start at an odd register
+        cpu.clear_exceptions();
+        ops.d = 6;
+        ops.s1 = 3;
+        assert_eq!(
+            DivUD.try_execute(&mut cpu, &mut memory, &ops),
+            Err(ExecError::IllegalOperand)
+        );
+        assert_eq!((cpu.registers[6], cpu.registers[7]), (0, 0));
+    }
+
     #[test]
     fn test_rem() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test positive numbers
         cpu.registers[1] = 100;
         cpu.registers[2] = 30;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        Rem.execute(&mut cpu, &mut memory);
+        Rem.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 10);
 
         // Test negative dividend
         cpu.registers[1] = (-100i32) as u32;
         cpu.registers[2] = 30;
 
-        Rem.execute(&mut cpu, &mut memory);
+        Rem.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3] as i32, -10);
 
         // Test division by zero
         cpu.registers[2] = 0;
         cpu.cr0 = 0;
 
-        Rem.execute(&mut cpu, &mut memory);
-        assert_ne!(cpu.cr0 & CPU::CR0_FP_DIVZERO, 0);
+        Rem.execute(&mut cpu, &mut memory, &ops);
+        assert_ne!(cpu.cr0 & CPU::CR0_DIVIDE_ZERO, 0);
+        assert_eq!(cpu.registers[3] as i32, -10);
+    }
+
+    #[test]
+    fn test_rem_min_by_minus_one() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        cpu.registers[1] = 0x80000000;
+        cpu.registers[2] = 0xFFFFFFFF;
+        cpu.registers[3] = 0xDEAD;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
+
+        assert_eq!(Rem.try_execute(&mut cpu, &mut memory, &ops), Ok(()));
         assert_eq!(cpu.registers[3], 0);
     }
 
     #[test]
     fn test_remu() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test normal case
         cpu.registers[1] = 100;
         cpu.registers[2] = 30;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        RemU.execute(&mut cpu, &mut memory);
+        RemU.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 10);
 
         // Test large numbers
         cpu.registers[1] = 0xFFFFFFFF;
         cpu.registers[2] = 0x10000000;
 
-        RemU.execute(&mut cpu, &mut memory);
+        RemU.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0x0FFFFFFF);
 
         // Test division by zero
         cpu.registers[2] = 0;
         cpu.cr0 = 0;
 
-        RemU.execute(&mut cpu, &mut memory);
-        assert_ne!(cpu.cr0 & CPU::CR0_FP_DIVZERO, 0);
-        assert_eq!(cpu.registers[3], 0);
+        RemU.execute(&mut cpu, &mut memory, &ops);
+        assert_ne!(cpu.cr0 & CPU::CR0_DIVIDE_ZERO, 0);
+        assert_eq!(cpu.registers[3], 0x0FFFFFFF);
     }
 
     #[test]
     fn test_add_overflow() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test positive overflow
         cpu.registers[1] = 0x7FFFFFFF; // Max positive 32-bit int
         cpu.registers[2] = 1;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        Add.execute(&mut cpu, &mut memory);
-        assert_eq!(cpu.registers[3], 0x80000000); // Should wrap to negative
+        Add.execute(&mut cpu, &mut memory, &ops);
+        assert_ne!(cpu.cr0 & CPU::CR0_OVERFLOW, 0);
+        assert_eq!(cpu.vector, CPU::VECTOR_INTEGER_OVERFLOW);
+        assert_eq!(cpu.registers[3], 0); // Destination unmodified
 
         // Test negative overflow
+        cpu.cr0 = 0;
         cpu.registers[1] = 0x80000000; // Min negative 32-bit int
         cpu.registers[2] = 0xFFFFFFFF; // -1 in two's complement
-        Add.execute(&mut cpu, &mut memory);
-        assert_eq!(cpu.registers[3], 0x7FFFFFFF); // Should wrap to positive
+        Add.execute(&mut cpu, &mut memory, &ops);
+        assert_ne!(cpu.cr0 & CPU::CR0_OVERFLOW, 0);
+        assert_eq!(cpu.registers[3], 0);
+
+        // Unsigned addition wraps without trapping
+        cpu.cr0 = 0;
+        cpu.registers[1] = 0x7FFFFFFF;
+        cpu.registers[2] = 1;
+        AddU.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.cr0 & CPU::CR0_OVERFLOW, 0);
+        assert_eq!(cpu.registers[3], 0x80000000);
+    }
+
+    #[test]
+    fn test_sub_overflow() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        cpu.pc = 0x1000;
+        cpu.registers[1] = 0x80000000; // Min negative 32-bit int
+        cpu.registers[2] = 1;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
+
+        Sub.execute(&mut cpu, &mut memory, &ops);
+        assert_ne!(cpu.cr0 & CPU::CR0_OVERFLOW, 0);
+        assert_eq!(cpu.sxip, 0x1000);
+        assert_eq!(
+            cpu.pc,
+            cpu.exception_handler_address(CPU::VECTOR_INTEGER_OVERFLOW)
+        );
+
+        // Immediate forms trap as well
+        cpu.cr0 = 0;
+        ops.imm = 1;
+        SubImmediate.execute(&mut cpu, &mut memory, &ops);
+        assert_ne!(cpu.cr0 & CPU::CR0_OVERFLOW, 0);
+
+        // Unsigned subtraction wraps without trapping
+        cpu.cr0 = 0;
+        SubU.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.cr0 & CPU::CR0_OVERFLOW, 0);
+        assert_eq!(cpu.registers[3], 0x7FFFFFFF);
     }
 
     #[test]
     fn test_sub_underflow() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test positive to negative underflow
         cpu.registers[1] = 0;
         cpu.registers[2] = 1;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        Sub.execute(&mut cpu, &mut memory);
+        Sub.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0xFFFFFFFF); // -1 in two's complement
 
         // Test negative to positive underflow
         cpu.registers[1] = 0x80000000; // Min negative 32-bit int
         cpu.registers[2] = 0xFFFFFFFF; // -1 in two's complement
-        Sub.execute(&mut cpu, &mut memory);
+        Sub.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0x80000001);
     }
 
     #[test]
     fn test_div_by_zero() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
-        // Test division by zero
+        // Test division by zero leaves the destination unmodified
         cpu.registers[1] = 42;
         cpu.registers[2] = 0;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        cpu.registers[3] = 7;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        Div.execute(&mut cpu, &mut memory);
-        assert_eq!(cpu.registers[3], 0);
+        Div.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[3], 7);
 
         // Test unsigned division by zero
-        DivU.execute(&mut cpu, &mut memory);
-        assert_eq!(cpu.registers[3], 0);
+        DivU.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[3], 7);
+    }
+
+    #[test]
+    fn test_div_by_zero_traps() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        cpu.pc = 0x1000;
+        cpu.registers[1] = 42;
+        cpu.registers[2] = 0;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
+
+        Div.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.sxip, 0x1000);
+        assert_eq!(
+            cpu.pc,
+            cpu.exception_handler_address(CPU::VECTOR_INTEGER_DIVIDE)
+        );
+        // Integer division never touches the floating point flags
+        assert_eq!(cpu.cr0 & CPU::CR0_FP_DIVZERO, 0);
     }
 
     #[test]
     fn test_div_overflow() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test division overflow (MIN_INT / -1)
         cpu.registers[1] = 0x80000000; // Min negative 32-bit int
         cpu.registers[2] = 0xFFFFFFFF; // -1 in two's complement
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
+
+        Div.execute(&mut cpu, &mut memory, &ops);
+        assert_ne!(cpu.cr0 & CPU::CR0_OVERFLOW, 0);
+        assert_eq!(cpu.cr0 & CPU::CR0_DIVIDE_ZERO, 0);
+        assert_eq!(cpu.vector, CPU::VECTOR_INTEGER_OVERFLOW);
+        assert_eq!(cpu.registers[3], 0); // Destination unmodified
 
-        Div.execute(&mut cpu, &mut memory);
-        assert_eq!(cpu.registers[3], 0x80000000); // Should remain MIN_INT
+        // Division by zero is reported separately
+        cpu.cr0 = 0;
+        cpu.registers[1] = 5;
+        cpu.registers[2] = 0;
+        Div.execute(&mut cpu, &mut memory, &ops);
+        assert_ne!(cpu.cr0 & CPU::CR0_DIVIDE_ZERO, 0);
+        assert_eq!(cpu.cr0 & CPU::CR0_OVERFLOW, 0);
+        assert_eq!(cpu.vector, CPU::VECTOR_INTEGER_DIVIDE);
     }
 
     #[test]
     fn test_mul_overflow() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test multiplication overflow
         cpu.registers[1] = 0x7FFFFFFF; // Max positive 32-bit int
         cpu.registers[2] = 2;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        Mul.execute(&mut cpu, &mut memory);
+        Mul.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0xFFFFFFFE); // Wrapped result
+        assert_ne!(cpu.cr0 & CPU::CR0_OVERFLOW, 0);
 
         // Test negative multiplication overflow
         cpu.registers[1] = 0x80000000; // Min negative 32-bit int
         cpu.registers[2] = 2;
-        Mul.execute(&mut cpu, &mut memory);
+        Mul.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0); // Wrapped result
     }
 
     #[test]
     fn test_rem_by_zero() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
-        // Test remainder by zero
+        // Test remainder by zero leaves the destination unmodified
         cpu.registers[1] = 42;
         cpu.registers[2] = 0;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        cpu.registers[3] = 7;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        Rem.execute(&mut cpu, &mut memory);
-        assert_eq!(cpu.registers[3], 0);
+        Rem.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[3], 7);
+        assert_eq!(cpu.cr0 & CPU::CR0_FP_DIVZERO, 0);
 
         // Test unsigned remainder by zero
-        RemU.execute(&mut cpu, &mut memory);
-        assert_eq!(cpu.registers[3], 0);
+        RemU.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[3], 7);
+        assert_eq!(cpu.cr0 & CPU::CR0_FP_DIVZERO, 0);
     }
 
     #[test]
     fn test_addu_wraparound() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test unsigned addition wraparound
         cpu.registers[1] = 0xFFFFFFFF;
         cpu.registers[2] = 1;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        AddU.execute(&mut cpu, &mut memory);
+        AddU.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0); // Should wrap to 0
     }
 
     #[test]
     fn test_subu_wraparound() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test unsigned subtraction wraparound
         cpu.registers[1] = 0;
         cpu.registers[2] = 1;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        SubU.execute(&mut cpu, &mut memory);
+        SubU.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0xFFFFFFFF); // Should wrap to max unsigned
     }
 
     #[test]
     fn test_mul_boundary_cases() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test multiplication by 0
         cpu.registers[1] = 0xFFFFFFFF;
         cpu.registers[2] = 0;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        Mul.execute(&mut cpu, &mut memory);
+        Mul.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0);
 
         // Test multiplication by 1
         cpu.registers[1] = 0x12345678;
         cpu.registers[2] = 1;
-        Mul.execute(&mut cpu, &mut memory);
+        Mul.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0x12345678);
 
         // Test multiplication by -1
         cpu.registers[1] = 0x12345678;
         cpu.registers[2] = 0xFFFFFFFF; // -1 in two's complement
-        Mul.execute(&mut cpu, &mut memory);
+        Mul.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0xEDCBA988); // Negated value
     }
 
     #[test]
     fn test_div_boundary_cases() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test division by 1
         cpu.registers[1] = 0x12345678;
         cpu.registers[2] = 1;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        Div.execute(&mut cpu, &mut memory);
+        Div.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0x12345678);
 
         // Test division by -1 (normal case)
         cpu.registers[1] = 0x12345678;
         cpu.registers[2] = 0xFFFFFFFF; // -1 in two's complement
-        Div.execute(&mut cpu, &mut memory);
+        Div.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0xEDCBA988); // Negated value
 
         // Test 0 divided by any number
         cpu.registers[1] = 0;
         cpu.registers[2] = 0x12345678;
-        Div.execute(&mut cpu, &mut memory);
+        Div.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0);
     }
+
+    #[test]
+    fn test_min_max_signedness() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        // -1 signed, the largest value unsigned
+        cpu.registers[1] = 0xFFFF_FFFF;
+        cpu.registers[2] = 5;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
+
+        MinS.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[3], 0xFFFF_FFFF);
+        MaxS.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[3], 5);
+        MinU.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[3], 5);
+        MaxU.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[3], 0xFFFF_FFFF);
+    }
+
+    #[test]
+    fn test_clamp() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+        ops.d = 2;
+        ops.s1 = 1;
+
+        // (input, ClampU result, ClampTo8 result)
+        for (input, clamp_u, clamp_8) in [
+            (-5i32, 0, 0),
+            (i32::MIN, 0, 0),
+            (0, 0, 0),
+            (100, 100, 100),
+            (255, 255, 255),
+            (300, 300, 255),
+            (i32::MAX, i32::MAX as u32, 255),
+        ] {
+            cpu.registers[1] = input as u32;
+            ClampU.execute(&mut cpu, &mut memory, &ops);
+            assert_eq!(cpu.registers[2], clamp_u, "ClampU {}", input);
+            ClampTo8.execute(&mut cpu, &mut memory, &ops);
+            assert_eq!(cpu.registers[2], clamp_8, "ClampTo8 {}", input);
+        }
+    }
 }
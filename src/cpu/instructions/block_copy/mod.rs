@@ -0,0 +1,114 @@
+//! Block copy pseudo-instruction for the Motorola 88000.
+//!
+//! The 88000 has no block copy instruction. `BlockCopy` models a board-level
+//! DMA engine that copies memory through the memory subsystem, so the copy is
+//! subject to MMU translation and protection like any other access. It is
+//! only built with the `block-copy` feature.
+
+use crate::cpu::instructions::{Instruction, Operands};
+use crate::cpu::CPU;
+use crate::memory::{Memory, MemoryError};
+
+/// Block copy instruction: copies rs2 bytes from Memory\[rs1\] to Memory\[rd\]
+///
+/// The registers track progress: after each byte rs1 and rd advance and rs2
+/// counts down. A fault partway leaves rs2 holding the number of bytes still
+/// to copy, and re-executing the instruction resumes where it stopped.
+pub struct BlockCopy;
+
+impl Instruction for BlockCopy {
+    fn execute(&self, cpu: &mut CPU, memory: &mut Memory, ops: &Operands) {
+        while cpu.registers[ops.s2] != 0 {
+            let src = cpu.registers[ops.s1];
+            let dst = cpu.registers[ops.d];
+
+            let result = memory
+                .read_byte(src)
+                .and_then(|byte| memory.write_byte(dst, byte));
+            if let Err(err) = result {
+                match err {
+                    MemoryError::PageFault(fault) => cpu.set_page_fault(fault),
+                    MemoryError::WriteProtection(fault) => cpu.set_write_protect_fault(fault),
+                    err => cpu.set_page_fault(err.address()),
+                }
+                return;
+            }
+
+            cpu.registers[ops.s1] = src.wrapping_add(1);
+            cpu.registers[ops.d] = dst.wrapping_add(1);
+            cpu.registers[ops.s2] -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::instructions::ExecError;
+    use crate::memory::PageTableEntry;
+
+    #[test]
+    fn test_block_copy() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        memory.load_bytes(0x2000, b"motorola 88000").unwrap();
+        cpu.registers[1] = 0x2000;
+        cpu.registers[2] = 14;
+        cpu.registers[3] = 0x3000;
+        ops.s1 = 1;
+        ops.s2 = 2;
+        ops.d = 3;
+
+        assert_eq!(BlockCopy.try_execute(&mut cpu, &mut memory, &ops), Ok(()));
+        let mut copied = [0; 14];
+        memory.read_bytes(0x3000, &mut copied).unwrap();
+        assert_eq!(&copied, b"motorola 88000");
+        assert_eq!(cpu.registers[1], 0x200E);
+        assert_eq!(cpu.registers[2], 0);
+        assert_eq!(cpu.registers[3], 0x300E);
+    }
+
+    #[test]
+    fn test_block_copy_faults_partway_and_resumes() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        // Only the first 16 pages are mapped; the copy runs off the end
+        memory.install_identity_mapping(0x10_0000, 16).unwrap();
+        memory.set_page_table_base(0x10_0000);
+        for i in 0..0x20 {
+            memory.write_byte(0x8000 + i, i as u8).unwrap();
+        }
+        memory.set_mmu_enabled(true);
+
+        cpu.registers[1] = 0x8000;
+        cpu.registers[2] = 0x20;
+        cpu.registers[3] = 0xFFF0;
+        ops.s1 = 1;
+        ops.s2 = 2;
+        ops.d = 3;
+
+        assert_eq!(
+            BlockCopy.try_execute(&mut cpu, &mut memory, &ops),
+            Err(ExecError::PageFault(0x1_0000))
+        );
+        assert_eq!(cpu.registers[2], 0x10);
+        assert_eq!(cpu.registers[1], 0x8010);
+        assert_eq!(cpu.registers[3], 0x1_0000);
+        assert_eq!(memory.read_byte(0xFFFF).unwrap(), 0x0F);
+
+        // Mapping the page and re-executing completes the copy
+        memory
+            .write_physical_u32(0x10_0000 + 0x10 * 4, PageTableEntry::new(0x1_0000).to_u32())
+            .unwrap();
+        cpu.clear_exceptions();
+        assert_eq!(BlockCopy.try_execute(&mut cpu, &mut memory, &ops), Ok(()));
+        assert_eq!(cpu.registers[2], 0);
+        for i in 0..0x20 {
+            assert_eq!(memory.read_byte(0xFFF0 + i).unwrap(), i as u8);
+        }
+    }
+}
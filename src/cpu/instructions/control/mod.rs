@@ -7,28 +7,53 @@
 //! - Exception handling
 //! - Trap instructions
 
-use crate::cpu::instructions::Instruction;
+use crate::cpu::instructions::{Instruction, Operands};
 use crate::cpu::CPU;
 use crate::memory::Memory;
 
-/// Branch if equal instruction: if rs1 == rs2 then PC += offset
+/// Computes the target of a PC-relative branch from the word-scaled displacement.
+fn branch_target(cpu: &CPU, ops: &Operands) -> u32 {
+    cpu.pc.wrapping_add((ops.disp << 2) as u32)
+}
+
+/// Branch instruction: PC += disp * 4
+pub struct Br;
+
+impl Instruction for Br {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.branch_to(branch_target(cpu, ops));
+    }
+}
+
+/// Delayed branch instruction: PC += disp * 4 once the delay slot executes
+///
+/// The displacement is relative to the branch, not the delay slot.
+pub struct BrN;
+
+impl Instruction for BrN {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.branch_delayed(branch_target(cpu, ops));
+    }
+}
+
+/// Branch if equal instruction: if rs1 == rs2 then PC += disp * 4
 pub struct Beq;
 
 impl Instruction for Beq {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        if cpu.registers[cpu.s1] == cpu.registers[cpu.s2] {
-            cpu.pc = cpu.pc.wrapping_add(cpu.offset as u32);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        if cpu.registers[ops.s1] == cpu.registers[ops.s2] {
+            cpu.branch_to(branch_target(cpu, ops));
         }
     }
 }
 
-/// Branch if not equal instruction: if rs1 != rs2 then PC += offset
+/// Branch if not equal instruction: if rs1 != rs2 then PC += disp * 4
 pub struct Bne;
 
 impl Instruction for Bne {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        if cpu.registers[cpu.s1] != cpu.registers[cpu.s2] {
-            cpu.pc = cpu.pc.wrapping_add(cpu.offset as u32);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        if cpu.registers[ops.s1] != cpu.registers[ops.s2] {
+            cpu.branch_to(branch_target(cpu, ops));
         }
     }
 }
@@ -37,112 +62,218 @@ impl Instruction for Bne {
 pub struct Jr;
 
 impl Instruction for Jr {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        cpu.pc = cpu.registers[cpu.s1];
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.branch_to(cpu.registers[ops.s1]);
     }
 }
 
 /// Jump and link instruction: rd = PC + 4; PC = rs1
+///
+/// The jump target register is left intact. The decoder links through r1,
+/// following the `jsr` convention.
 pub struct Jal;
 
 impl Instruction for Jal {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
         let return_addr = cpu.pc.wrapping_add(4);
-        cpu.pc = cpu.registers[cpu.s1];
-        cpu.registers[cpu.s1] = return_addr;
+        cpu.branch_to(cpu.registers[ops.s1]);
+        cpu.registers[ops.d] = return_addr;
     }
 }
 
-/// Load control register instruction: rd = cr0
-pub struct Ldcr;
+/// Load control register instruction: rd = cr\[cr_index\]
+///
+/// Unmodelled control registers read as zero. Reading a protected control
+/// register outside supervisor mode is a privilege violation.
+#[derive(Debug)]
+pub struct Ldcr {
+    pub cr_index: u32,
+}
 
 impl Instruction for Ldcr {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        cpu.registers[cpu.d] = cpu.cr0;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = cpu.control_register(self.cr_index).unwrap_or(0);
+    }
+
+    fn is_privileged(&self) -> bool {
+        CPU::control_register_protected(self.cr_index)
     }
 }
 
-/// Store control register instruction: cr0 = rs1
-pub struct Stcr;
+/// Store control register instruction: cr\[cr_index\] = rs1
+///
+/// Writes to unmodelled control registers are ignored. Writing a protected
+/// control register or the processor status register outside supervisor mode
+/// is a privilege violation.
+#[derive(Debug)]
+pub struct Stcr {
+    pub cr_index: u32,
+}
 
 impl Instruction for Stcr {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        cpu.cr0 = cpu.registers[cpu.s1];
+    fn execute(&self, cpu: &mut CPU, memory: &mut Memory, ops: &Operands) {
+        cpu.set_control_register(self.cr_index, cpu.registers[ops.s1]);
+        if self.cr_index == CPU::CR_PTBR {
+            memory.set_page_table_base(cpu.ptbr);
+        }
+    }
+
+    fn is_privileged(&self) -> bool {
+        CPU::control_register_write_protected(self.cr_index)
     }
 }
 
 /// Return from exception instruction: restores execution state
+///
+/// Resumes at the shadow instruction pointers in the privilege level saved on
+/// exception entry. Executing `Rte` outside supervisor mode is a privilege
+/// violation.
 pub struct Rte;
 
 impl Instruction for Rte {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        cpu.pc = cpu.sxip;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, _ops: &Operands) {
+        cpu.set_privilege_level(cpu.shadow_privilege_level);
+        cpu.branch_to(cpu.sxip);
         cpu.nip = cpu.snip;
         cpu.fip = cpu.sfip;
     }
+
+    fn is_privileged(&self) -> bool {
+        true
+    }
 }
 
-/// Trap instruction: generates a software trap
+/// Trap instruction: generates a software trap through vector `vector`
+///
+/// Control transfers to the handler in supervisor mode; `Rte` returns to
+/// the instruction after the trap.
 pub struct Trap;
 
 impl Instruction for Trap {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, _ops: &Operands) {
         cpu.cr0 |= CPU::CR0_TRAP;
         cpu.trap_vector = cpu.vector;
+        cpu.raise_trap(cpu.trap_vector);
+    }
+}
+
+/// Trap on bit clear instruction: traps if bit `d` of rs1 is clear
+///
+/// The bit number is taken from the 5-bit B5 field, which occupies the
+/// destination register field of the instruction word.
+pub struct Tb0;
+
+impl Instruction for Tb0 {
+    fn execute(&self, cpu: &mut CPU, memory: &mut Memory, ops: &Operands) {
+        if cpu.registers[ops.s1] & (1 << (ops.d & 0x1F)) == 0 {
+            Trap.execute(cpu, memory, ops);
+        }
+    }
+}
+
+/// Trap on bit set instruction: traps if bit `d` of rs1 is set
+///
+/// The bit number is taken from the 5-bit B5 field, which occupies the
+/// destination register field of the instruction word.
+pub struct Tb1;
+
+impl Instruction for Tb1 {
+    fn execute(&self, cpu: &mut CPU, memory: &mut Memory, ops: &Operands) {
+        if cpu.registers[ops.s1] & (1 << (ops.d & 0x1F)) != 0 {
+            Trap.execute(cpu, memory, ops);
+        }
     }
 }
 
-/// Trap bound instruction: checks if rs1 is within bounds
+/// Flags a bounds check violation and vectors to the bounds check handler.
+fn bounds_check_trap(cpu: &mut CPU) {
+    cpu.cr0 |= CPU::CR0_BOUNDS_CHECK;
+    cpu.raise_exception(CPU::VECTOR_BOUNDS_CHECK);
+}
+
+/// Trap bound instruction: traps if rs1 is above the bound in rs2 (unsigned)
 pub struct Tbnd;
 
 impl Instruction for Tbnd {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        if cpu.registers[cpu.s1] > cpu.registers[cpu.s2] {
-            cpu.cr0 |= CPU::CR0_BOUNDS_CHECK;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        if cpu.registers[ops.s1] > cpu.registers[ops.s2] {
+            bounds_check_trap(cpu);
+        }
+    }
+}
+
+/// Trap bound instruction: traps if rs1 is above the zero-extended immediate bound
+pub struct TbndImmediate;
+
+impl Instruction for TbndImmediate {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        if cpu.registers[ops.s1] > ops.imm as u16 as u32 {
+            bounds_check_trap(cpu);
         }
     }
 }
 
-/// Branch if greater than instruction: if rs1 > rs2 then PC += offset
+/// Branch if greater than instruction: if rs1 > rs2 then PC += disp * 4
 pub struct Bgt;
 
 impl Instruction for Bgt {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        if (cpu.registers[cpu.s1] as i32) > (cpu.registers[cpu.s2] as i32) {
-            cpu.pc = cpu.pc.wrapping_add(cpu.offset as u32);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        if (cpu.registers[ops.s1] as i32) > (cpu.registers[ops.s2] as i32) {
+            cpu.branch_to(branch_target(cpu, ops));
         }
     }
 }
 
-/// Branch if less than instruction: if rs1 < rs2 then PC += offset
+/// Branch if less than instruction: if rs1 < rs2 then PC += disp * 4
 pub struct Blt;
 
 impl Instruction for Blt {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        if (cpu.registers[cpu.s1] as i32) < (cpu.registers[cpu.s2] as i32) {
-            cpu.pc = cpu.pc.wrapping_add(cpu.offset as u32);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        if (cpu.registers[ops.s1] as i32) < (cpu.registers[ops.s2] as i32) {
+            cpu.branch_to(branch_target(cpu, ops));
         }
     }
 }
 
-/// Branch if greater than or equal instruction: if rs1 >= rs2 then PC += offset
+/// Branch if greater than or equal instruction: if rs1 >= rs2 then PC += disp * 4
 pub struct Bge;
 
 impl Instruction for Bge {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        if (cpu.registers[cpu.s1] as i32) >= (cpu.registers[cpu.s2] as i32) {
-            cpu.pc = cpu.pc.wrapping_add(cpu.offset as u32);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        if (cpu.registers[ops.s1] as i32) >= (cpu.registers[ops.s2] as i32) {
+            cpu.branch_to(branch_target(cpu, ops));
         }
     }
 }
 
-/// Branch if less than or equal instruction: if rs1 <= rs2 then PC += offset
+/// Branch if less than or equal instruction: if rs1 <= rs2 then PC += disp * 4
 pub struct Ble;
 
 impl Instruction for Ble {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        if (cpu.registers[cpu.s1] as i32) <= (cpu.registers[cpu.s2] as i32) {
-            cpu.pc = cpu.pc.wrapping_add(cpu.offset as u32);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        if (cpu.registers[ops.s1] as i32) <= (cpu.registers[ops.s2] as i32) {
+            cpu.branch_to(branch_target(cpu, ops));
+        }
+    }
+}
+
+/// Conditional move instruction: rd = rs1 if the selected condition bit is set
+///
+/// Bits 4:0 of the immediate select the condition bit. With bit 5 clear the
+/// bit is tested in cr0; with bit 5 set it is tested in rs2, such as a bit
+/// vector produced by `cmp`. The destination is unchanged if the bit is clear.
+pub struct CMov;
+
+impl Instruction for CMov {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let selector = ops.imm as u16 as u32;
+        let source = if selector & 0x20 != 0 {
+            cpu.registers[ops.s2]
+        } else {
+            cpu.cr0
+        };
+        if source & (1 << (selector & 0x1F)) != 0 {
+            cpu.registers[ops.d] = cpu.registers[ops.s1];
         }
     }
 }
@@ -150,226 +281,407 @@ impl Instruction for Ble {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cpu::instructions::system::PrivilegeLevel;
+    use crate::cpu::instructions::ExecError;
+    use crate::cpu::StepOutcome;
 
     #[test]
     fn test_beq() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test branch taken
         cpu.registers[1] = 10;
         cpu.registers[2] = 10;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
-        cpu.offset = 100;
+        ops.s1 = 1;
+        ops.s2 = 2;
+        ops.disp = 25;
         cpu.pc = 1000;
 
-        Beq.execute(&mut cpu, &mut memory);
-        assert_eq!(cpu.pc, 1100); // PC + offset
+        Beq.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.pc, 1100); // PC + disp * 4
 
         // Test branch not taken
         cpu.registers[1] = 10;
         cpu.registers[2] = 20;
         cpu.pc = 1000;
 
-        Beq.execute(&mut cpu, &mut memory);
+        Beq.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.pc, 1000); // PC unchanged
     }
 
     #[test]
     fn test_bne() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test branch taken
         cpu.registers[1] = 10;
         cpu.registers[2] = 20;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
-        cpu.offset = 100;
+        ops.s1 = 1;
+        ops.s2 = 2;
+        ops.disp = 25;
         cpu.pc = 1000;
 
-        Bne.execute(&mut cpu, &mut memory);
-        assert_eq!(cpu.pc, 1100); // PC + offset
+        Bne.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.pc, 1100); // PC + disp * 4
 
         // Test branch not taken
         cpu.registers[1] = 10;
         cpu.registers[2] = 10;
         cpu.pc = 1000;
 
-        Bne.execute(&mut cpu, &mut memory);
+        Bne.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.pc, 1000); // PC unchanged
     }
 
     #[test]
     fn test_bgt() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test branch taken
         cpu.registers[1] = 20;
         cpu.registers[2] = 10;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
-        cpu.offset = 100;
+        ops.s1 = 1;
+        ops.s2 = 2;
+        ops.disp = 25;
         cpu.pc = 1000;
 
-        Bgt.execute(&mut cpu, &mut memory);
-        assert_eq!(cpu.pc, 1100); // PC + offset
+        Bgt.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.pc, 1100); // PC + disp * 4
 
         // Test branch not taken
         cpu.registers[1] = 10;
         cpu.registers[2] = 20;
         cpu.pc = 1000;
 
-        Bgt.execute(&mut cpu, &mut memory);
+        Bgt.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.pc, 1000); // PC unchanged
     }
 
     #[test]
     fn test_blt() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test branch taken
         cpu.registers[1] = 10;
         cpu.registers[2] = 20;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
-        cpu.offset = 100;
+        ops.s1 = 1;
+        ops.s2 = 2;
+        ops.disp = 25;
         cpu.pc = 1000;
 
-        Blt.execute(&mut cpu, &mut memory);
-        assert_eq!(cpu.pc, 1100); // PC + offset
+        Blt.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.pc, 1100); // PC + disp * 4
 
         // Test branch not taken
         cpu.registers[1] = 20;
         cpu.registers[2] = 10;
         cpu.pc = 1000;
 
-        Blt.execute(&mut cpu, &mut memory);
+        Blt.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.pc, 1000); // PC unchanged
     }
 
     #[test]
     fn test_bge() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test branch taken (greater)
         cpu.registers[1] = 20;
         cpu.registers[2] = 10;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
-        cpu.offset = 100;
+        ops.s1 = 1;
+        ops.s2 = 2;
+        ops.disp = 25;
         cpu.pc = 1000;
 
-        Bge.execute(&mut cpu, &mut memory);
-        assert_eq!(cpu.pc, 1100); // PC + offset
+        Bge.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.pc, 1100); // PC + disp * 4
 
         // Test branch taken (equal)
         cpu.registers[1] = 10;
         cpu.registers[2] = 10;
         cpu.pc = 1000;
 
-        Bge.execute(&mut cpu, &mut memory);
-        assert_eq!(cpu.pc, 1100); // PC + offset
+        Bge.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.pc, 1100); // PC + disp * 4
 
         // Test branch not taken
         cpu.registers[1] = 10;
         cpu.registers[2] = 20;
         cpu.pc = 1000;
 
-        Bge.execute(&mut cpu, &mut memory);
+        Bge.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.pc, 1000); // PC unchanged
     }
 
     #[test]
     fn test_ble() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test branch taken (less)
         cpu.registers[1] = 10;
         cpu.registers[2] = 20;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
-        cpu.offset = 100;
+        ops.s1 = 1;
+        ops.s2 = 2;
+        ops.disp = 25;
         cpu.pc = 1000;
 
-        Ble.execute(&mut cpu, &mut memory);
-        assert_eq!(cpu.pc, 1100); // PC + offset
+        Ble.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.pc, 1100); // PC + disp * 4
 
         // Test branch taken (equal)
         cpu.registers[1] = 10;
         cpu.registers[2] = 10;
         cpu.pc = 1000;
 
-        Ble.execute(&mut cpu, &mut memory);
-        assert_eq!(cpu.pc, 1100); // PC + offset
+        Ble.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.pc, 1100); // PC + disp * 4
 
         // Test branch not taken
         cpu.registers[1] = 20;
         cpu.registers[2] = 10;
         cpu.pc = 1000;
 
-        Ble.execute(&mut cpu, &mut memory);
+        Ble.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.pc, 1000); // PC unchanged
     }
 
+    #[test]
+    fn test_branch_beyond_16_bit_reach() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+        cpu.registers[1] = 10;
+        cpu.registers[2] = 10;
+        ops.s1 = 1;
+        ops.s2 = 2;
+
+        // Forward branch to the top of the 26-bit word-scaled range
+        cpu.pc = 0x0100_0000;
+        ops.disp = (1 << 25) - 1;
+        Beq.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.pc, 0x0100_0000 + 0x07FF_FFFC);
+
+        // Backward branch far outside a 16-bit displacement
+        cpu.pc = 0x0100_0000;
+        ops.disp = -0x10_0000;
+        Beq.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.pc, 0x00C0_0000);
+    }
+
+    #[test]
+    fn test_br() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        // Forward branch
+        cpu.pc = 0x1000;
+        ops.disp = 0x40;
+        Br.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.pc, 0x1100);
+        assert!(cpu.branch_taken);
+
+        // Backward branch
+        ops.disp = -0x80;
+        Br.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.pc, 0x0F00);
+    }
+
+    #[test]
+    fn test_br_n_executes_delay_slot() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+        memory
+            .load_words(
+                0x1000,
+                &[
+                    0xC400_0003, // br.n 0x100C
+                    0x6020_0005, // addu r1, r0, 5 (delay slot)
+                    0x6040_0007, // addu r2, r0, 7 (skipped)
+                    0x6060_0009, // addu r3, r0, 9
+                ],
+            )
+            .unwrap();
+        cpu.pc = 0x1000;
+
+        assert_eq!(cpu.step(&mut memory), Ok(StepOutcome::Retired));
+        assert!(cpu.in_delay_slot());
+        assert_eq!(cpu.pc, 0x1004);
+        assert_eq!(cpu.step(&mut memory), Ok(StepOutcome::BranchTaken(0x100C)));
+        assert_eq!(cpu.registers[1], 5);
+        cpu.step(&mut memory).unwrap();
+        assert_eq!(cpu.registers[2], 0);
+        assert_eq!(cpu.registers[3], 9);
+    }
+
     #[test]
     fn test_jr() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 0x1000;
-        cpu.s1 = 1;
+        ops.s1 = 1;
 
-        Jr.execute(&mut cpu, &mut memory);
+        Jr.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.pc, 0x1000);
     }
 
     #[test]
     fn test_jal() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
-        cpu.registers[1] = 0x1000;
-        cpu.s1 = 1;
+        cpu.registers[2] = 0x1000;
+        ops.s1 = 2;
+        ops.d = 1;
         cpu.pc = 0x500;
 
-        Jal.execute(&mut cpu, &mut memory);
+        Jal.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.pc, 0x1000);
         assert_eq!(cpu.registers[1], 0x504); // PC + 4
+        assert_eq!(cpu.registers[2], 0x1000); // Target preserved
     }
 
     #[test]
     fn test_ldcr() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.cr0 = 0xFFFFFFFF;
-        cpu.d = 1;
+        ops.d = 1;
 
-        Ldcr.execute(&mut cpu, &mut memory);
+        Ldcr {
+            cr_index: CPU::CR_PSR,
+        }
+        .execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[1], 0xFFFFFFFF);
     }
 
     #[test]
     fn test_stcr() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 0xFFFFFFFF;
-        cpu.s1 = 1;
+        ops.s1 = 1;
 
-        Stcr.execute(&mut cpu, &mut memory);
+        Stcr {
+            cr_index: CPU::CR_PSR,
+        }
+        .execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.cr0, 0xFFFFFFFF);
     }
 
+    #[test]
+    fn test_control_registers_by_index() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+        cpu.set_privilege_level(PrivilegeLevel::Supervisor);
+        ops.s1 = 1;
+        ops.d = 2;
+
+        for (index, value) in [
+            (CPU::CR_SXIP, 0x1000),
+            (CPU::CR_SNIP, 0x1004),
+            (CPU::CR_SFIP, 0x1008),
+            (CPU::CR_PTBR, 0x0004_0000),
+        ] {
+            cpu.registers[1] = value;
+            Stcr { cr_index: index }.execute(&mut cpu, &mut memory, &ops);
+            Ldcr { cr_index: index }.execute(&mut cpu, &mut memory, &ops);
+            assert_eq!(cpu.registers[2], value);
+        }
+        assert_eq!(cpu.sxip, 0x1000);
+        assert_eq!(cpu.snip, 0x1004);
+        assert_eq!(cpu.sfip, 0x1008);
+        assert_eq!(cpu.ptbr, 0x0004_0000);
+        assert!(!cpu.has_privilege_violation());
+
+        // Unmodelled control registers read as zero
+        Ldcr { cr_index: 40 }.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 0);
+    }
+
+    #[test]
+    fn test_protected_control_register_in_user_mode() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+        cpu.set_privilege_level(PrivilegeLevel::User);
+        cpu.sxip = 0x1000;
+        cpu.registers[2] = 0xDEAD;
+        ops.d = 2;
+
+        let result = Ldcr {
+            cr_index: CPU::CR_SXIP,
+        }
+        .try_execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(result, Err(ExecError::PrivilegeViolation));
+        assert!(cpu.has_privilege_violation());
+        assert_eq!(cpu.registers[2], 0xDEAD);
+
+        cpu.clear_privilege_violation();
+        cpu.registers[1] = CPU::MMU_ENABLE;
+        ops.s1 = 1;
+        let result = Stcr {
+            cr_index: CPU::CR_MMU_CONTROL,
+        }
+        .try_execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(result, Err(ExecError::PrivilegeViolation));
+        assert!(cpu.has_privilege_violation());
+        assert_eq!(cpu.mmu_control, 0);
+
+        // The processor status register remains readable
+        cpu.clear_privilege_violation();
+        Ldcr {
+            cr_index: CPU::CR_PSR,
+        }
+        .execute(&mut cpu, &mut memory, &ops);
+        assert!(!cpu.has_privilege_violation());
+    }
+
+    #[test]
+    fn test_psr_write_in_user_mode() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+        cpu.set_privilege_level(PrivilegeLevel::User);
+        cpu.cr0 = CPU::CR0_INTERRUPT_DISABLE;
+        cpu.registers[1] = 0;
+        ops.s1 = 1;
+
+        let result = Stcr {
+            cr_index: CPU::CR_PSR,
+        }
+        .try_execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(result, Err(ExecError::PrivilegeViolation));
+        assert_eq!(
+            cpu.cr0,
+            CPU::CR0_INTERRUPT_DISABLE | CPU::CR0_PRIVILEGE_VIOLATION
+        );
+    }
+
     #[test]
     fn test_rte() {
         let mut cpu = CPU::new();
+        let ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.sxip = 0x1000;
@@ -377,7 +689,20 @@ mod tests {
         cpu.sfip = 0x1008;
         cpu.pc = 0x500;
 
-        Rte.execute(&mut cpu, &mut memory);
+        // Rte is privileged
+        cpu.set_privilege_level(PrivilegeLevel::User);
+        assert_eq!(
+            Rte.try_execute(&mut cpu, &mut memory, &ops),
+            Err(ExecError::PrivilegeViolation)
+        );
+        assert!(cpu.has_privilege_violation());
+        assert_eq!(cpu.pc, 0x500);
+        assert_eq!(cpu.get_privilege_level(), PrivilegeLevel::User);
+
+        cpu.clear_privilege_violation();
+        cpu.set_privilege_level(PrivilegeLevel::Supervisor);
+        Rte.execute(&mut cpu, &mut memory, &ops);
+        assert!(!cpu.has_privilege_violation());
         assert_eq!(cpu.pc, 0x1000);
         assert_eq!(cpu.nip, 0x1004);
         assert_eq!(cpu.fip, 0x1008);
@@ -386,35 +711,241 @@ mod tests {
     #[test]
     fn test_tbnd() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test within bounds
         cpu.registers[1] = 100;
         cpu.registers[2] = 200;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        Tbnd.execute(&mut cpu, &mut memory);
+        Tbnd.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.cr0 & CPU::CR0_BOUNDS_CHECK, 0);
 
         // Test out of bounds
         cpu.registers[1] = 300;
         cpu.registers[2] = 200;
 
-        Tbnd.execute(&mut cpu, &mut memory);
+        Tbnd.execute(&mut cpu, &mut memory, &ops);
         assert_ne!(cpu.cr0 & CPU::CR0_BOUNDS_CHECK, 0);
     }
 
+    #[test]
+    fn test_tbnd_vectors_to_handler() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+        let handler = cpu.exception_handler_address(CPU::VECTOR_BOUNDS_CHECK);
+
+        // tbnd r1, 0x8000: the bound is zero-extended
+        cpu.pc = 0x1000;
+        cpu.registers[1] = 0x8000;
+        ops.s1 = 1;
+        ops.imm = 0x8000u16 as i16;
+        TbndImmediate.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.exception_taken, None);
+        assert_eq!(cpu.pc, 0x1000);
+
+        cpu.registers[1] = 0x8001;
+        TbndImmediate.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.exception_taken, Some(CPU::VECTOR_BOUNDS_CHECK));
+        assert_eq!(cpu.pc, handler);
+        assert_eq!(cpu.sxip, 0x1000);
+
+        // tbnd r1, r2
+        let mut cpu = CPU::new();
+        cpu.pc = 0x1000;
+        cpu.registers[1] = 5;
+        cpu.registers[2] = 5;
+        ops.s1 = 1;
+        ops.s2 = 2;
+        Tbnd.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.exception_taken, None);
+
+        cpu.registers[1] = 6;
+        Tbnd.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.exception_taken, Some(CPU::VECTOR_BOUNDS_CHECK));
+        assert_eq!(cpu.pc, handler);
+    }
+
     #[test]
     fn test_trap() {
         let mut cpu = CPU::new();
+        let ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test trap vector 5
         cpu.vector = 5;
 
-        Trap.execute(&mut cpu, &mut memory);
+        Trap.execute(&mut cpu, &mut memory, &ops);
+        assert_ne!(cpu.cr0 & CPU::CR0_TRAP, 0);
+        assert_eq!(cpu.trap_vector, 5);
+    }
+
+    #[test]
+    fn test_trap_vectors_to_handler() {
+        let mut cpu = CPU::new();
+        let ops = Operands::default();
+        let mut memory = Memory::new();
+
+        // Handler for vector 128: addu r1, r1, 1; rte
+        let handler = cpu.exception_handler_address(128);
+        memory
+            .load_words(handler, &[0x6021_0001, 0xF400_FC00])
+            .unwrap();
+
+        cpu.pc = 0x1000;
+        cpu.set_privilege_level(PrivilegeLevel::User);
+        cpu.vector = 128;
+        Trap.execute(&mut cpu, &mut memory, &ops);
+
+        assert_eq!(cpu.pc, handler);
+        assert_eq!(cpu.get_privilege_level(), PrivilegeLevel::Supervisor);
+        assert_eq!(cpu.sxip, 0x1004);
+        assert_eq!(cpu.snip, 0x1008);
+
+        // The handler runs and returns past the trap
+        cpu.step(&mut memory).unwrap();
+        cpu.step(&mut memory).unwrap();
+        assert_eq!(cpu.registers[1], 1);
+        assert_eq!(cpu.pc, 0x1004);
+        assert_eq!(cpu.get_privilege_level(), PrivilegeLevel::User);
+    }
+
+    #[test]
+    fn test_rte_restores_privilege_level() {
+        let mut cpu = CPU::new();
+        let ops = Operands::default();
+        let mut memory = Memory::new();
+
+        // An exception taken from user mode enters supervisor mode
+        cpu.pc = 0x1000;
+        cpu.set_privilege_level(PrivilegeLevel::User);
+        cpu.raise_exception(CPU::VECTOR_BOUNDS_CHECK);
+        assert_eq!(cpu.get_privilege_level(), PrivilegeLevel::Supervisor);
+        assert_eq!(cpu.shadow_privilege_level, PrivilegeLevel::User);
+
+        Rte.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.get_privilege_level(), PrivilegeLevel::User);
+        assert_eq!(cpu.pc, 0x1000);
+
+        // An exception taken from supervisor mode stays in supervisor mode
+        cpu.set_privilege_level(PrivilegeLevel::Supervisor);
+        cpu.raise_exception(CPU::VECTOR_BOUNDS_CHECK);
+        Rte.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.get_privilege_level(), PrivilegeLevel::Supervisor);
+    }
+
+    #[test]
+    fn test_cmov_on_cr0() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+        cpu.registers[1] = 0x1234;
+        cpu.registers[2] = 0x5678;
+        ops.s1 = 1;
+        ops.d = 2;
+
+        // Condition false: destination preserved
+        ops.imm = CPU::CR0_EQUAL.trailing_zeros() as i16;
+        cpu.cr0 = CPU::CR0_LESS;
+        CMov.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 0x5678);
+
+        // Condition true: the move happens
+        ops.imm = CPU::CR0_LESS.trailing_zeros() as i16;
+        CMov.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 0x1234);
+    }
+
+    #[test]
+    fn test_cmov_on_register_bit() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+        cpu.registers[1] = 0xAAAA;
+        cpu.registers[2] = 0xBBBB;
+        cpu.registers[3] = 1 << 7;
+        ops.s1 = 1;
+        ops.s2 = 3;
+        ops.d = 2;
+
+        ops.imm = 0x20 | 6;
+        CMov.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 0xBBBB);
+
+        ops.imm = 0x20 | 7;
+        CMov.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 0xAAAA);
+    }
+
+    #[test]
+    fn test_tb0() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        cpu.vector = 5;
+        ops.s1 = 1;
+
+        // Bit 0 set: no trap
+        cpu.registers[1] = 0x0000_0001;
+        ops.d = 0;
+        Tb0.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.cr0 & CPU::CR0_TRAP, 0);
+
+        // Bit 31 clear: trap
+        ops.d = 31;
+        Tb0.execute(&mut cpu, &mut memory, &ops);
         assert_ne!(cpu.cr0 & CPU::CR0_TRAP, 0);
         assert_eq!(cpu.trap_vector, 5);
+
+        // Bit 0 clear: trap
+        cpu.cr0 = 0;
+        cpu.registers[1] = 0x8000_0000;
+        ops.d = 0;
+        Tb0.execute(&mut cpu, &mut memory, &ops);
+        assert_ne!(cpu.cr0 & CPU::CR0_TRAP, 0);
+
+        // Bit 31 set: no trap
+        cpu.cr0 = 0;
+        ops.d = 31;
+        Tb0.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.cr0 & CPU::CR0_TRAP, 0);
+    }
+
+    #[test]
+    fn test_tb1() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        cpu.vector = 6;
+        ops.s1 = 1;
+
+        // Bit 0 set: trap
+        cpu.registers[1] = 0x0000_0001;
+        ops.d = 0;
+        Tb1.execute(&mut cpu, &mut memory, &ops);
+        assert_ne!(cpu.cr0 & CPU::CR0_TRAP, 0);
+        assert_eq!(cpu.trap_vector, 6);
+
+        // Bit 31 clear: no trap
+        cpu.cr0 = 0;
+        ops.d = 31;
+        Tb1.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.cr0 & CPU::CR0_TRAP, 0);
+
+        // Bit 0 clear: no trap
+        cpu.registers[1] = 0x8000_0000;
+        ops.d = 0;
+        Tb1.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.cr0 & CPU::CR0_TRAP, 0);
+
+        // Bit 31 set: trap
+        ops.d = 31;
+        Tb1.execute(&mut cpu, &mut memory, &ops);
+        assert_ne!(cpu.cr0 & CPU::CR0_TRAP, 0);
     }
 }
@@ -6,19 +6,128 @@
 //! - Type conversions
 //! - Special value handling (NaN, infinity)
 //! - Exception handling
+//!
+//! Arithmetic goes through `arith`, which uses the host floating point unit
+//! or, with the `softfloat` feature, a deterministic software implementation.
+
+#[cfg(feature = "softfloat")]
+pub mod softfloat;
 
-use crate::cpu::instructions::Instruction;
+use crate::cpu::instructions::{Instruction, Operands};
 use crate::cpu::CPU;
 use crate::memory::Memory;
 
+/// Floating point arithmetic on the host floating point unit.
+#[cfg(not(feature = "softfloat"))]
+pub(crate) mod arith {
+    pub fn add(a: f32, b: f32) -> f32 {
+        a + b
+    }
+
+    pub fn sub(a: f32, b: f32) -> f32 {
+        a - b
+    }
+
+    pub fn mul(a: f32, b: f32) -> f32 {
+        a * b
+    }
+
+    pub fn div(a: f32, b: f32) -> f32 {
+        a / b
+    }
+
+    pub fn mul_add(a: f32, b: f32, c: f32) -> f32 {
+        a.mul_add(b, c)
+    }
+
+    pub fn sqrt(a: f32) -> f32 {
+        a.sqrt()
+    }
+
+    pub fn sqrt_d(a: f64) -> f64 {
+        a.sqrt()
+    }
+
+    pub fn from_i32(value: i32) -> f32 {
+        value as f32
+    }
+}
+
+/// Floating point arithmetic in software, identical on every host.
+#[cfg(feature = "softfloat")]
+pub(crate) use softfloat as arith;
+
+/// Canonical quiet NaN produced by every NaN-returning operation
+pub const CANONICAL_NAN: u32 = 0x7FC0_0000;
+
+/// Checks whether a single precision bit pattern is a signaling NaN.
+fn is_signaling_nan(bits: u32) -> bool {
+    f32::from_bits(bits).is_nan() && bits & 0x0040_0000 == 0
+}
+
+/// Handles NaN operands of a two-operand instruction.
+///
+/// Returns the canonical quiet NaN if either operand is a NaN, raising the
+/// invalid operation flag if either is a signaling NaN.
+fn propagate_nan(cpu: &mut CPU, a: u32, b: u32) -> Option<u32> {
+    if is_signaling_nan(a) || is_signaling_nan(b) {
+        cpu.set_fp_flag(CPU::CR0_FP_INVALID);
+    }
+    if f32::from_bits(a).is_nan() || f32::from_bits(b).is_nan() {
+        Some(CANONICAL_NAN)
+    } else {
+        None
+    }
+}
+
+/// Canonicalizes a NaN produced from non-NaN operands, an invalid operation.
+fn canonicalize_result(cpu: &mut CPU, result: f32) -> u32 {
+    if result.is_nan() {
+        cpu.set_fp_flag(CPU::CR0_FP_INVALID);
+        CANONICAL_NAN
+    } else {
+        result.to_bits()
+    }
+}
+
+/// Raises the inexact flag if a finite rounded result differs from the exact value.
+///
+/// Products of single precision values are computed exactly in double
+/// precision, so the widened product serves as the reference.
+fn check_inexact(cpu: &mut CPU, result: f32, exact: f64) {
+    if result.is_finite() && result as f64 != exact {
+        cpu.set_fp_flag(CPU::CR0_FP_INEXACT);
+    }
+}
+
+/// Raises the inexact flag if a finite rounded sum differs from the exact `a + b`.
+///
+/// Operands far apart in magnitude do not sum exactly even in double
+/// precision, so the exact sum is kept as the double precision sum plus its
+/// rounding error, computed with TwoSum.
+fn check_inexact_sum(cpu: &mut CPU, result: f32, a: f64, b: f64) {
+    let sum = a + b;
+    let b_virtual = sum - a;
+    let a_virtual = sum - b_virtual;
+    let error = (a - a_virtual) + (b - b_virtual);
+    if result.is_finite() && (error != 0.0 || result as f64 != sum) {
+        cpu.set_fp_flag(CPU::CR0_FP_INEXACT);
+    }
+}
+
 /// Floating point add instruction: rd = rs1 + rs2
 pub struct FAdd;
 
 impl Instruction for FAdd {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = f32::from_bits(cpu.registers[cpu.s1]);
-        let b = f32::from_bits(cpu.registers[cpu.s2]);
-        let result = a + b;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        if let Some(nan) = propagate_nan(cpu, cpu.registers[ops.s1], cpu.registers[ops.s2]) {
+            cpu.registers[ops.d] = nan;
+            return;
+        }
+        let a = cpu.freg_s(ops.s1);
+        let b = cpu.freg_s(ops.s2);
+        let result = arith::add(a, b);
+        check_inexact_sum(cpu, result, a as f64, b as f64);
 
         // Check for floating point exceptions
         if result.is_infinite() && !a.is_infinite() && !b.is_infinite() {
@@ -28,7 +137,7 @@ impl Instruction for FAdd {
             cpu.set_fp_flag(CPU::CR0_FP_UNDERFLOW);
         }
 
-        cpu.registers[cpu.d] = result.to_bits();
+        cpu.registers[ops.d] = canonicalize_result(cpu, result);
     }
 }
 
@@ -36,16 +145,17 @@ impl Instruction for FAdd {
 pub struct FSub;
 
 impl Instruction for FSub {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = f32::from_bits(cpu.registers[cpu.s1]);
-        let b = f32::from_bits(cpu.registers[cpu.s2]);
-        let result = a - b;
-
-        if result.is_nan() {
-            cpu.cr0 |= CPU::CR0_FP_INVALID;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        if let Some(nan) = propagate_nan(cpu, cpu.registers[ops.s1], cpu.registers[ops.s2]) {
+            cpu.registers[ops.d] = nan;
+            return;
         }
+        let a = cpu.freg_s(ops.s1);
+        let b = cpu.freg_s(ops.s2);
+        let result = arith::sub(a, b);
+        check_inexact_sum(cpu, result, a as f64, -b as f64);
 
-        cpu.registers[cpu.d] = result.to_bits();
+        cpu.registers[ops.d] = canonicalize_result(cpu, result);
     }
 }
 
@@ -53,10 +163,15 @@ impl Instruction for FSub {
 pub struct FMul;
 
 impl Instruction for FMul {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = f32::from_bits(cpu.registers[cpu.s1]);
-        let b = f32::from_bits(cpu.registers[cpu.s2]);
-        let result = a * b;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        if let Some(nan) = propagate_nan(cpu, cpu.registers[ops.s1], cpu.registers[ops.s2]) {
+            cpu.registers[ops.d] = nan;
+            return;
+        }
+        let a = cpu.freg_s(ops.s1);
+        let b = cpu.freg_s(ops.s2);
+        let result = arith::mul(a, b);
+        check_inexact(cpu, result, a as f64 * b as f64);
 
         // Check for floating point exceptions
         if result.is_infinite() && !a.is_infinite() && !b.is_infinite() {
@@ -66,7 +181,43 @@ impl Instruction for FMul {
             cpu.set_fp_flag(CPU::CR0_FP_UNDERFLOW);
         }
 
-        cpu.registers[cpu.d] = result.to_bits();
+        cpu.registers[ops.d] = canonicalize_result(cpu, result);
+    }
+}
+
+/// Fused multiply-add instruction: rd = rs1 * rs2 + rs3 with a single rounding
+pub struct FMAdd;
+
+impl Instruction for FMAdd {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.freg_s(ops.s1);
+        let b = cpu.freg_s(ops.s2);
+        let c = cpu.freg_s(ops.s3);
+        let result = arith::mul_add(a, b, c);
+
+        if result.is_nan() {
+            cpu.set_fp_flag(CPU::CR0_FP_INVALID);
+        }
+
+        cpu.set_freg_s(ops.d, result);
+    }
+}
+
+/// Fused multiply-subtract instruction: rd = rs1 * rs2 - rs3 with a single rounding
+pub struct FMSub;
+
+impl Instruction for FMSub {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.freg_s(ops.s1);
+        let b = cpu.freg_s(ops.s2);
+        let c = cpu.freg_s(ops.s3);
+        let result = arith::mul_add(a, b, -c);
+
+        if result.is_nan() {
+            cpu.set_fp_flag(CPU::CR0_FP_INVALID);
+        }
+
+        cpu.set_freg_s(ops.d, result);
     }
 }
 
@@ -74,29 +225,38 @@ impl Instruction for FMul {
 pub struct FDiv;
 
 impl Instruction for FDiv {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = f32::from_bits(cpu.registers[cpu.s1]);
-        let b = f32::from_bits(cpu.registers[cpu.s2]);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        if let Some(nan) = propagate_nan(cpu, cpu.registers[ops.s1], cpu.registers[ops.s2]) {
+            cpu.registers[ops.d] = nan;
+            return;
+        }
+        let a = cpu.freg_s(ops.s1);
+        let b = cpu.freg_s(ops.s2);
 
         // Check for division by zero
         if b == 0.0 {
             cpu.set_fp_flag(CPU::CR0_FP_DIVZERO);
             if a == 0.0 {
                 // 0.0 / 0.0 = NaN
-                cpu.registers[cpu.d] = f32::NAN.to_bits();
+                cpu.registers[ops.d] = CANONICAL_NAN;
                 cpu.set_fp_flag(CPU::CR0_FP_INVALID);
             } else {
                 // x / 0 = infinity with sign of x
-                cpu.registers[cpu.d] = if a.is_sign_positive() {
-                    f32::INFINITY.to_bits()
+                let infinity = if a.is_sign_positive() {
+                    f32::INFINITY
                 } else {
-                    f32::NEG_INFINITY.to_bits()
+                    f32::NEG_INFINITY
                 };
+                cpu.set_freg_s(ops.d, infinity);
             }
             return;
         }
 
-        let result = a / b;
+        let result = arith::div(a, b);
+        // The quotient is exact only if multiplying it back recovers the dividend
+        if result.is_finite() && result as f64 * b as f64 != a as f64 {
+            cpu.set_fp_flag(CPU::CR0_FP_INEXACT);
+        }
 
         // Check for floating point exceptions
         if result.is_infinite() && !a.is_infinite() {
@@ -106,7 +266,7 @@ impl Instruction for FDiv {
             cpu.set_fp_flag(CPU::CR0_FP_UNDERFLOW);
         }
 
-        cpu.registers[cpu.d] = result.to_bits();
+        cpu.registers[ops.d] = canonicalize_result(cpu, result);
     }
 }
 
@@ -114,9 +274,9 @@ impl Instruction for FDiv {
 pub struct FCmp;
 
 impl Instruction for FCmp {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = f32::from_bits(cpu.registers[cpu.s1]);
-        let b = f32::from_bits(cpu.registers[cpu.s2]);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.freg_s(ops.s1);
+        let b = cpu.freg_s(ops.s2);
 
         cpu.cr0 &= !CPU::CR0_FP_COMPARE_MASK;
         if a.is_nan() || b.is_nan() {
@@ -135,49 +295,138 @@ impl Instruction for FCmp {
 pub struct IntToFp;
 
 impl Instruction for IntToFp {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let int_val = cpu.registers[cpu.s1] as i32;
-        let float_val = int_val as f32;
-        cpu.registers[cpu.d] = float_val.to_bits();
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let int_val = cpu.registers[ops.s1] as i32;
+        let float_val = arith::from_i32(int_val);
+        cpu.set_freg_s(ops.d, float_val);
+    }
+}
+
+/// Converts rs1 to an integer in rd, rounding with `round`.
+///
+/// NaN and infinite operands set the invalid flag and produce 0; out of range
+/// operands set the overflow flag and saturate.
+fn convert_to_int(cpu: &mut CPU, ops: &Operands, round: impl Fn(f32) -> f32) {
+    let value = cpu.freg_s(ops.s1);
+
+    // Check for NaN or infinity
+    if value.is_nan() || value.is_infinite() {
+        cpu.set_fp_flag(CPU::CR0_FP_INVALID);
+        cpu.registers[ops.d] = 0;
+        return;
+    }
+
+    // Check for overflow
+    if value > i32::MAX as f32 || value < i32::MIN as f32 {
+        cpu.set_fp_flag(CPU::CR0_FP_OVERFLOW);
+        cpu.registers[ops.d] = if value > 0.0 { i32::MAX } else { i32::MIN } as u32;
+        return;
     }
+
+    let result = round(value);
+    if result != value {
+        cpu.set_fp_flag(CPU::CR0_FP_INEXACT);
+    }
+
+    cpu.registers[ops.d] = result as i32 as u32;
 }
 
-/// Floating point to integer conversion instruction: rd = int(rs1)
+/// Floating point to integer conversion instruction: rd = int(rs1), rounding to nearest even
 pub struct FpToInt;
 
 impl Instruction for FpToInt {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let value = f32::from_bits(cpu.registers[cpu.s1]);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        convert_to_int(cpu, ops, f32::round_ties_even);
+    }
+}
+
+/// Truncating floating point to integer conversion instruction: rd = trunc(rs1)
+///
+/// Rounds toward zero, as C's float-to-int conversion does.
+pub struct FTrunc;
 
-        // Check for NaN or infinity
-        if value.is_nan() || value.is_infinite() {
+impl Instruction for FTrunc {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        convert_to_int(cpu, ops, f32::trunc);
+    }
+}
+
+/// Floating point square root instruction: rd = sqrt(rs1)
+pub struct FSqrt;
+
+impl Instruction for FSqrt {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let value = cpu.freg_s(ops.s1);
+
+        // Square root of a negative number (other than -0.0) is invalid
+        if value < 0.0 {
             cpu.set_fp_flag(CPU::CR0_FP_INVALID);
-            cpu.registers[cpu.d] = 0;
+            cpu.set_freg_s(ops.d, f32::NAN);
             return;
         }
 
-        // Check for overflow
-        if value > i32::MAX as f32 || value < i32::MIN as f32 {
-            cpu.set_fp_flag(CPU::CR0_FP_OVERFLOW);
-            cpu.registers[cpu.d] = if value > 0.0 { i32::MAX } else { i32::MIN } as u32;
+        cpu.set_freg_s(ops.d, arith::sqrt(value));
+    }
+}
+
+/// Double precision floating point square root instruction: rd:rd+1 = sqrt(rs1:rs1+1)
+pub struct FSqrtD;
+
+impl Instruction for FSqrtD {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        if !cpu.check_register_pair(ops.s1) || !cpu.check_register_pair(ops.d) {
             return;
         }
+        let value = cpu.freg_d(ops.s1);
 
-        // Round to nearest even integer
-        let rounded = value.round();
-        // If we're exactly halfway between two integers, round to even
-        let result = if (value.fract().abs() - 0.5).abs() < f32::EPSILON {
-            let floor = value.floor();
-            if floor as i32 % 2 == 0 {
-                floor
-            } else {
-                value.ceil()
-            }
+        let result = if value < 0.0 {
+            cpu.set_fp_flag(CPU::CR0_FP_INVALID);
+            f64::NAN
         } else {
-            rounded
+            arith::sqrt_d(value)
         };
 
-        cpu.registers[cpu.d] = result as i32 as u32;
+        cpu.write_register_pair(ops.d, result.to_bits());
+    }
+}
+
+/// Floating point absolute value instruction: clears the sign bit of rs1
+pub struct FAbs;
+
+impl Instruction for FAbs {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = cpu.registers[ops.s1] & 0x7FFF_FFFF;
+    }
+}
+
+/// Floating point negate instruction: flips the sign bit of rs1
+pub struct FNeg;
+
+impl Instruction for FNeg {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = cpu.registers[ops.s1] ^ 0x8000_0000;
+    }
+}
+
+/// Move to extended register instruction: xd = rs1
+///
+/// Copies a general purpose register into the 88110 extended register file.
+pub struct MoveToX;
+
+impl Instruction for MoveToX {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.x_registers[ops.d] = cpu.registers[ops.s1];
+    }
+}
+
+/// Move from extended register instruction: rd = xs1
+///
+/// Copies an 88110 extended register into the general purpose register file.
+pub struct MoveFromX;
+
+impl Instruction for MoveFromX {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = cpu.x_registers[ops.s1];
     }
 }
 
@@ -186,30 +435,32 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_fadd() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test normal addition
         cpu.registers[1] = f32::to_bits(3.14);
         cpu.registers[2] = f32::to_bits(2.86);
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        FAdd.execute(&mut cpu, &mut memory);
+        FAdd.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(f32::from_bits(cpu.registers[3]), 6.0);
 
         // Test with zero
         cpu.registers[1] = f32::to_bits(3.14);
         cpu.registers[2] = f32::to_bits(0.0);
-        FAdd.execute(&mut cpu, &mut memory);
+        FAdd.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(f32::from_bits(cpu.registers[3]), 3.14);
 
         // Test overflow
         cpu.registers[1] = f32::to_bits(f32::MAX);
         cpu.registers[2] = f32::to_bits(f32::MAX);
-        FAdd.execute(&mut cpu, &mut memory);
+        FAdd.execute(&mut cpu, &mut memory, &ops);
         assert!(f32::from_bits(cpu.registers[3]).is_infinite());
         assert_ne!(cpu.cr0 & CPU::CR0_FP_OVERFLOW, 0);
     }
@@ -217,57 +468,60 @@ mod tests {
     #[test]
     fn test_fsub() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
-        cpu.s1 = 1;
-        cpu.s2 = 2;
-        cpu.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
+        ops.d = 3;
 
         // Test normal subtraction
         cpu.registers[1] = 3.0f32.to_bits();
         cpu.registers[2] = 1.5f32.to_bits();
-        FSub.execute(&mut cpu, &mut memory);
+        FSub.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(f32::from_bits(cpu.registers[3]), 1.5);
 
         // Test negative result
         cpu.registers[1] = 1.0f32.to_bits();
         cpu.registers[2] = 2.0f32.to_bits();
-        FSub.execute(&mut cpu, &mut memory);
+        FSub.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(f32::from_bits(cpu.registers[3]), -1.0);
 
         // Test subtraction with infinity
         cpu.registers[1] = f32::INFINITY.to_bits();
         cpu.registers[2] = f32::INFINITY.to_bits();
-        FSub.execute(&mut cpu, &mut memory);
+        FSub.execute(&mut cpu, &mut memory, &ops);
         assert!(f32::from_bits(cpu.registers[3]).is_nan());
         assert_ne!(cpu.cr0 & CPU::CR0_FP_INVALID, 0);
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_fmul() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test normal multiplication
         cpu.registers[1] = f32::to_bits(3.0);
         cpu.registers[2] = f32::to_bits(2.0);
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        FMul.execute(&mut cpu, &mut memory);
+        FMul.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(f32::from_bits(cpu.registers[3]), 6.0);
 
         // Test with zero
         cpu.registers[1] = f32::to_bits(3.14);
         cpu.registers[2] = f32::to_bits(0.0);
-        FMul.execute(&mut cpu, &mut memory);
+        FMul.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(f32::from_bits(cpu.registers[3]), 0.0);
 
         // Test overflow
         cpu.registers[1] = f32::to_bits(f32::MAX);
         cpu.registers[2] = f32::to_bits(2.0);
-        FMul.execute(&mut cpu, &mut memory);
+        FMul.execute(&mut cpu, &mut memory, &ops);
         assert!(f32::from_bits(cpu.registers[3]).is_infinite());
         assert_ne!(cpu.cr0 & CPU::CR0_FP_OVERFLOW, 0);
     }
@@ -275,121 +529,179 @@ mod tests {
     #[test]
     fn test_fdiv() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test normal division
         cpu.registers[1] = f32::to_bits(6.0);
         cpu.registers[2] = f32::to_bits(2.0);
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        FDiv.execute(&mut cpu, &mut memory);
+        FDiv.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(f32::from_bits(cpu.registers[3]), 3.0);
 
         // Test division by zero
         cpu.registers[1] = f32::to_bits(1.0);
         cpu.registers[2] = f32::to_bits(0.0);
-        FDiv.execute(&mut cpu, &mut memory);
+        FDiv.execute(&mut cpu, &mut memory, &ops);
         assert!(f32::from_bits(cpu.registers[3]).is_infinite());
         assert_ne!(cpu.cr0 & CPU::CR0_FP_DIVZERO, 0);
 
         // Test underflow
         cpu.registers[1] = f32::to_bits(f32::MIN_POSITIVE);
         cpu.registers[2] = f32::to_bits(f32::MAX);
-        FDiv.execute(&mut cpu, &mut memory);
+        FDiv.execute(&mut cpu, &mut memory, &ops);
         assert_ne!(cpu.cr0 & CPU::CR0_FP_UNDERFLOW, 0);
     }
 
     #[test]
     fn test_fcmp() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Setup registers for comparison
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
         // Test equal values
         cpu.registers[1] = 1.0f32.to_bits();
         cpu.registers[2] = 1.0f32.to_bits();
-        FCmp.execute(&mut cpu, &mut memory);
+        FCmp.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.cr0 & CPU::CR0_FP_COMPARE_MASK, CPU::CR0_FP_EQUAL);
 
         // Test less than
         cpu.registers[1] = 0.5f32.to_bits();
         cpu.registers[2] = 1.0f32.to_bits();
-        FCmp.execute(&mut cpu, &mut memory);
+        FCmp.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.cr0 & CPU::CR0_FP_COMPARE_MASK, CPU::CR0_FP_LESS);
 
         // Test greater than
         cpu.registers[1] = 2.0f32.to_bits();
         cpu.registers[2] = 1.0f32.to_bits();
-        FCmp.execute(&mut cpu, &mut memory);
+        FCmp.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.cr0 & CPU::CR0_FP_COMPARE_MASK, CPU::CR0_FP_GREATER);
 
         // Test NaN
         cpu.registers[1] = f32::NAN.to_bits();
         cpu.registers[2] = 1.0f32.to_bits();
-        FCmp.execute(&mut cpu, &mut memory);
+        FCmp.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.cr0 & CPU::CR0_FP_COMPARE_MASK, CPU::CR0_FP_UNORDERED);
     }
 
     #[test]
     fn test_int_to_fp() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test positive integer
         cpu.registers[1] = 42;
-        cpu.d = 2;
-        cpu.s1 = 1;
+        ops.d = 2;
+        ops.s1 = 1;
 
-        IntToFp.execute(&mut cpu, &mut memory);
+        IntToFp.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(f32::from_bits(cpu.registers[2]), 42.0);
 
         // Test negative integer
         cpu.registers[1] = -42i32 as u32;
-        IntToFp.execute(&mut cpu, &mut memory);
+        IntToFp.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(f32::from_bits(cpu.registers[2]), -42.0);
 
         // Test zero
         cpu.registers[1] = 0;
-        IntToFp.execute(&mut cpu, &mut memory);
+        IntToFp.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(f32::from_bits(cpu.registers[2]), 0.0);
     }
 
     #[test]
     fn test_fp_to_int() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
-        cpu.s1 = 1;
-        cpu.d = 2;
+        ops.s1 = 1;
+        ops.d = 2;
 
         // Test normal conversion
         cpu.registers[1] = 42.5f32.to_bits();
-        FpToInt.execute(&mut cpu, &mut memory);
+        FpToInt.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2], 42);
 
         // Test negative number
         cpu.registers[1] = (-42.5f32).to_bits();
-        FpToInt.execute(&mut cpu, &mut memory);
+        FpToInt.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2] as i32, -42);
 
         // Test overflow
         cpu.registers[1] = (2147483648.0f32).to_bits();
-        FpToInt.execute(&mut cpu, &mut memory);
+        FpToInt.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2], 0x7FFFFFFF);
 
         // Test underflow
         cpu.registers[1] = (-2147483904.0f32).to_bits();
-        FpToInt.execute(&mut cpu, &mut memory);
+        FpToInt.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2], 0x80000000);
 
         // Test NaN
         cpu.registers[1] = f32::NAN.to_bits();
-        FpToInt.execute(&mut cpu, &mut memory);
+        FpToInt.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 0);
+        assert_ne!(cpu.cr0 & CPU::CR0_FP_INVALID, 0);
+    }
+
+    #[test]
+    fn test_fp_to_int_ties_to_even() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        ops.s1 = 1;
+        ops.d = 2;
+
+        for (value, expected) in [
+            (1.5f32, 2),
+            (2.5, 2),
+            (3.5, 4),
+            (-2.5, -2),
+            (-3.5, -4),
+            (-0.5, 0),
+            // Just above a tie rounds up, not to even
+            (0.500_000_06, 1),
+            (-0.500_000_06, -1),
+        ] {
+            cpu.registers[1] = value.to_bits();
+            FpToInt.execute(&mut cpu, &mut memory, &ops);
+            assert_eq!(cpu.registers[2] as i32, expected, "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_ftrunc_rounds_toward_zero() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        ops.s1 = 1;
+        ops.d = 2;
+
+        for (value, truncated, rounded) in [(2.9f32, 2, 3), (-2.9f32, -2, -3)] {
+            cpu.registers[1] = value.to_bits();
+            FTrunc.execute(&mut cpu, &mut memory, &ops);
+            assert_eq!(cpu.registers[2] as i32, truncated);
+            FpToInt.execute(&mut cpu, &mut memory, &ops);
+            assert_eq!(cpu.registers[2] as i32, rounded);
+        }
+
+        // Overflow and NaN are handled as by FpToInt
+        cpu.registers[1] = (2147483648.0f32).to_bits();
+        FTrunc.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 0x7FFFFFFF);
+        cpu.cr0 = 0;
+        cpu.registers[1] = f32::NAN.to_bits();
+        FTrunc.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2], 0);
         assert_ne!(cpu.cr0 & CPU::CR0_FP_INVALID, 0);
     }
@@ -397,16 +709,17 @@ mod tests {
     #[test]
     fn test_float_div_by_zero() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Set up division by zero
         cpu.registers[1] = f32::to_bits(1.0);
         cpu.registers[2] = f32::to_bits(0.0);
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        FDiv.execute(&mut cpu, &mut memory);
+        FDiv.execute(&mut cpu, &mut memory, &ops);
 
         // Result should be infinity
         assert!(f32::from_bits(cpu.registers[3]).is_infinite());
@@ -415,54 +728,57 @@ mod tests {
     #[test]
     fn test_float_invalid_operations() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test 0.0 / 0.0 (NaN)
         cpu.registers[1] = f32::to_bits(0.0);
         cpu.registers[2] = f32::to_bits(0.0);
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        FDiv.execute(&mut cpu, &mut memory);
+        FDiv.execute(&mut cpu, &mut memory, &ops);
         assert!(f32::from_bits(cpu.registers[3]).is_nan());
 
         // Test infinity - infinity (NaN)
         cpu.registers[1] = f32::to_bits(f32::INFINITY);
         cpu.registers[2] = f32::to_bits(f32::INFINITY);
-        FSub.execute(&mut cpu, &mut memory);
+        FSub.execute(&mut cpu, &mut memory, &ops);
         assert!(f32::from_bits(cpu.registers[3]).is_nan());
     }
 
     #[test]
     fn test_float_overflow() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test multiplication leading to overflow
         cpu.registers[1] = f32::to_bits(f32::MAX);
         cpu.registers[2] = f32::to_bits(2.0);
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        FMul.execute(&mut cpu, &mut memory);
+        FMul.execute(&mut cpu, &mut memory, &ops);
         assert!(f32::from_bits(cpu.registers[3]).is_infinite());
     }
 
     #[test]
     fn test_float_underflow() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test multiplication leading to underflow
         cpu.registers[1] = f32::to_bits(f32::MIN_POSITIVE);
         cpu.registers[2] = f32::to_bits(0.5);
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        FMul.execute(&mut cpu, &mut memory);
+        FMul.execute(&mut cpu, &mut memory, &ops);
 
         // Result should be denormalized or zero
         let result = f32::from_bits(cpu.registers[3]);
@@ -472,14 +788,15 @@ mod tests {
     #[test]
     fn test_float_rounding_modes() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test rounding of 1.5 to integer
         cpu.registers[1] = f32::to_bits(1.5);
-        cpu.d = 2;
-        cpu.s1 = 1;
+        ops.d = 2;
+        ops.s1 = 1;
 
-        FpToInt.execute(&mut cpu, &mut memory);
+        FpToInt.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2], 2); // Should round up
 
         // TODO: Add tests for other rounding modes when implemented
@@ -491,48 +808,311 @@ mod tests {
     #[test]
     fn test_float_compare_special_values() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test NaN comparisons
         cpu.registers[1] = f32::to_bits(f32::NAN);
         cpu.registers[2] = f32::to_bits(0.0);
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        FCmp.execute(&mut cpu, &mut memory);
+        FCmp.execute(&mut cpu, &mut memory, &ops);
         assert_ne!(cpu.cr0 & CPU::CR0_FP_UNORDERED, 0);
 
         // Test infinity comparisons
         cpu.registers[1] = f32::to_bits(f32::INFINITY);
         cpu.registers[2] = f32::to_bits(f32::MAX);
-        FCmp.execute(&mut cpu, &mut memory);
+        FCmp.execute(&mut cpu, &mut memory, &ops);
         assert_ne!(cpu.cr0 & CPU::CR0_FP_GREATER, 0);
 
         // Test -infinity comparisons
         cpu.registers[1] = f32::to_bits(f32::NEG_INFINITY);
         cpu.registers[2] = f32::to_bits(-f32::MAX);
-        FCmp.execute(&mut cpu, &mut memory);
+        FCmp.execute(&mut cpu, &mut memory, &ops);
         assert_ne!(cpu.cr0 & CPU::CR0_FP_LESS, 0);
     }
 
     #[test]
     fn test_float_denormal_handling() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Create a denormal number
         let denormal = f32::from_bits(1); // Smallest possible denormal
         cpu.registers[1] = f32::to_bits(denormal);
         cpu.registers[2] = f32::to_bits(2.0);
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        FMul.execute(&mut cpu, &mut memory);
+        FMul.execute(&mut cpu, &mut memory, &ops);
 
         // Result should still be denormal
         let result = f32::from_bits(cpu.registers[3]);
         assert!(result.is_subnormal());
     }
+
+    #[test]
+    fn test_fsqrt() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        cpu.registers[1] = 4.0f32.to_bits();
+        ops.d = 2;
+        ops.s1 = 1;
+
+        FSqrt.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(f32::from_bits(cpu.registers[2]), 2.0);
+        assert_eq!(cpu.cr0 & CPU::CR0_FP_INVALID, 0);
+
+        // Negative input produces NaN and the invalid flag
+        cpu.registers[1] = (-4.0f32).to_bits();
+        FSqrt.execute(&mut cpu, &mut memory, &ops);
+        assert!(f32::from_bits(cpu.registers[2]).is_nan());
+        assert_ne!(cpu.cr0 & CPU::CR0_FP_INVALID, 0);
+    }
+
+    #[test]
+    fn test_fsqrt_double() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        let bits = 2.25f64.to_bits();
+        cpu.registers[2] = (bits >> 32) as u32;
+        cpu.registers[3] = bits as u32;
+        ops.d = 4;
+        ops.s1 = 2;
+
+        FSqrtD.execute(&mut cpu, &mut memory, &ops);
+        let result = ((cpu.registers[4] as u64) << 32) | cpu.registers[5] as u64;
+        assert_eq!(f64::from_bits(result), 1.5);
+    }
+
+    #[test]
+    fn test_extended_register_moves() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        cpu.registers[3] = 1.5f32.to_bits();
+        ops.d = 7;
+        ops.s1 = 3;
+        MoveToX.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.x_reg(7), Ok(1.5f32.to_bits()));
+        assert_eq!(cpu.registers[7], 0);
+
+        cpu.set_x_reg(31, 0xDEAD_BEEF).unwrap();
+        ops.d = 4;
+        ops.s1 = 31;
+        MoveFromX.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[4], 0xDEAD_BEEF);
+        assert_eq!(cpu.x_registers[4], 0);
+
+        assert!(cpu.x_reg(32).is_err());
+        assert!(cpu.set_x_reg(32, 0).is_err());
+    }
+
+    #[test]
+    fn test_fabs_fneg() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        cpu.registers[1] = 0x4049_0FDB;
+        ops.d = 2;
+        ops.s1 = 1;
+
+        // FNeg flips only the sign bit
+        FNeg.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 0xC049_0FDB);
+
+        // FAbs clears the sign bit
+        cpu.registers[1] = 0xC049_0FDB;
+        FAbs.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 0x4049_0FDB);
+
+        // Neither touches the flags, even for NaN
+        cpu.registers[1] = 0xFFC0_0000;
+        FNeg.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 0x7FC0_0000);
+        assert_eq!(cpu.cr0, 0);
+    }
+
+    #[test]
+    fn test_fmadd_single_rounding() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        // (1 + 2^-12)^2 = 1 + 2^-11 + 2^-24; the 2^-24 term is lost when
+        // the product is rounded to single precision before the addition
+        let a = 1.0f32 + 2.0f32.powi(-12);
+        let c = 1.0f32 + 2.0f32.powi(-11);
+        cpu.registers[1] = a.to_bits();
+        cpu.registers[2] = a.to_bits();
+        cpu.registers[3] = (-c).to_bits();
+        ops.d = 4;
+        ops.s1 = 1;
+        ops.s2 = 2;
+        ops.s3 = 3;
+
+        FMAdd.execute(&mut cpu, &mut memory, &ops);
+        let fused = f32::from_bits(cpu.registers[4]);
+        assert_eq!(fused, 2.0f32.powi(-24));
+
+        // Separate multiply then add rounds twice
+        FMul.execute(&mut cpu, &mut memory, &ops);
+        ops.s1 = 4;
+        ops.s2 = 3;
+        FAdd.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(f32::from_bits(cpu.registers[4]), 0.0);
+    }
+
+    #[test]
+    #[cfg(feature = "softfloat")]
+    fn test_softfloat_exact_bits() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+        ops.d = 4;
+        ops.s1 = 1;
+        ops.s2 = 2;
+        ops.s3 = 3;
+
+        // Hosts propagate NaN payloads through fused multiply-add and
+        // produce their own default NaN for invalid operations
+        cpu.registers[1] = 0x7FC0_1234;
+        cpu.registers[2] = 1.0f32.to_bits();
+        cpu.registers[3] = 1.0f32.to_bits();
+        FMAdd.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[4], CANONICAL_NAN);
+
+        cpu.registers[1] = f32::INFINITY.to_bits();
+        cpu.registers[2] = 0;
+        FMSub.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[4], CANONICAL_NAN);
+
+        cpu.registers[1] = 0xFF80_0001;
+        FSqrt.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[4], CANONICAL_NAN);
+
+        // Correctly rounded results are unchanged
+        cpu.registers[1] = 2.0f32.to_bits();
+        FSqrt.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[4], 0x3FB5_04F3);
+
+        cpu.registers[1] = 0x0000_0003;
+        cpu.registers[2] = 0x3F00_0000;
+        FMul.execute(&mut cpu, &mut memory, &ops);
+        // 1.5 smallest subnormals rounds to even
+        assert_eq!(cpu.registers[4], 0x0000_0002);
+    }
+
+    #[test]
+    fn test_fmsub_single_rounding() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        let a = 1.0f32 + 2.0f32.powi(-12);
+        let c = 1.0f32 + 2.0f32.powi(-11);
+        cpu.registers[1] = a.to_bits();
+        cpu.registers[2] = a.to_bits();
+        cpu.registers[3] = c.to_bits();
+        ops.d = 4;
+        ops.s1 = 1;
+        ops.s2 = 2;
+        ops.s3 = 3;
+
+        FMSub.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(f32::from_bits(cpu.registers[4]), 2.0f32.powi(-24));
+        assert_eq!(a * a - c, 0.0);
+    }
+
+    #[test]
+    fn test_signaling_nan_operands() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        let signaling_nan = 0x7F80_0001;
+        cpu.registers[2] = 1.0f32.to_bits();
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
+
+        let instructions: [&dyn Instruction; 4] = [&FAdd, &FSub, &FMul, &FDiv];
+        for op in instructions {
+            cpu.cr0 = 0;
+            cpu.registers[1] = signaling_nan;
+            op.execute(&mut cpu, &mut memory, &ops);
+            assert_ne!(cpu.cr0 & CPU::CR0_FP_INVALID, 0);
+            assert_eq!(cpu.registers[3], CANONICAL_NAN);
+
+            // A quiet NaN propagates without raising invalid
+            cpu.cr0 = 0;
+            cpu.registers[1] = 0xFFC1_2345;
+            op.execute(&mut cpu, &mut memory, &ops);
+            assert_eq!(cpu.cr0 & CPU::CR0_FP_INVALID, 0);
+            assert_eq!(cpu.registers[3], CANONICAL_NAN);
+        }
+    }
+
+    #[test]
+    fn test_fp_inexact() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        cpu.registers[1] = 1.0f32.to_bits();
+        cpu.registers[2] = 3.0f32.to_bits();
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
+
+        // 1/3 is not representable
+        FDiv.execute(&mut cpu, &mut memory, &ops);
+        assert_ne!(cpu.cr0 & CPU::CR0_FP_INEXACT, 0);
+
+        // 1/2 is exact
+        cpu.cr0 = 0;
+        cpu.registers[2] = 2.0f32.to_bits();
+        FDiv.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.cr0 & CPU::CR0_FP_INEXACT, 0);
+
+        // Adding a value below half an ulp rounds it away
+        cpu.registers[2] = 1.0e-10f32.to_bits();
+        FAdd.execute(&mut cpu, &mut memory, &ops);
+        assert_ne!(cpu.cr0 & CPU::CR0_FP_INEXACT, 0);
+
+        // So does one too small to survive even a double precision sum
+        cpu.cr0 = 0;
+        cpu.registers[2] = 2.0f32.powi(-60).to_bits();
+        FAdd.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[3], 1.0f32.to_bits());
+        assert_ne!(cpu.cr0 & CPU::CR0_FP_INEXACT, 0);
+        cpu.cr0 = 0;
+        FSub.execute(&mut cpu, &mut memory, &ops);
+        assert_ne!(cpu.cr0 & CPU::CR0_FP_INEXACT, 0);
+
+        // Sums that fit exactly are not inexact
+        cpu.cr0 = 0;
+        cpu.registers[2] = 0.5f32.to_bits();
+        FAdd.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.cr0 & CPU::CR0_FP_INEXACT, 0);
+
+        // Conversions that drop a fraction are inexact
+        cpu.cr0 = 0;
+        cpu.registers[1] = 2.0f32.to_bits();
+        FpToInt.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.cr0 & CPU::CR0_FP_INEXACT, 0);
+        cpu.registers[1] = 2.5f32.to_bits();
+        FpToInt.execute(&mut cpu, &mut memory, &ops);
+        assert_ne!(cpu.cr0 & CPU::CR0_FP_INEXACT, 0);
+    }
 }
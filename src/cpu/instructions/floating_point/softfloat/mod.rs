@@ -0,0 +1,409 @@
+//! Software IEEE-754 arithmetic for the floating point instructions.
+//!
+//! Host floating point units agree on correctly rounded results but not on
+//! the NaN they produce, and some hosts lack a fused multiply-add. With the
+//! `softfloat` feature the floating point instructions compute through this
+//! module instead, so results are bit-identical on every host. Every
+//! operation rounds to nearest even, supports subnormals, and returns the
+//! canonical quiet NaN for any NaN result.
+//!
+//! The functions mirror the host arithmetic they replace and take and return
+//! `f32`/`f64` values.
+
+/// Layout of a binary floating point format.
+#[derive(Clone, Copy)]
+struct Format {
+    /// Number of stored fraction bits
+    frac_bits: u32,
+    /// Number of exponent bits
+    exp_bits: u32,
+}
+
+const SINGLE: Format = Format {
+    frac_bits: 23,
+    exp_bits: 8,
+};
+
+const DOUBLE: Format = Format {
+    frac_bits: 52,
+    exp_bits: 11,
+};
+
+/// Position of the leading bit that finite significands are normalized to.
+const NORMALIZED_TOP: u32 = 62;
+
+impl Format {
+    fn bias(self) -> i32 {
+        (1 << (self.exp_bits - 1)) - 1
+    }
+
+    fn max_biased_exp(self) -> u64 {
+        (1 << self.exp_bits) - 1
+    }
+
+    fn sign_bit(self) -> u64 {
+        1 << (self.frac_bits + self.exp_bits)
+    }
+
+    /// Exponent of the least significant bit of a subnormal.
+    fn min_exp(self) -> i32 {
+        1 - self.bias() - self.frac_bits as i32
+    }
+
+    fn quiet_nan(self) -> u64 {
+        (self.max_biased_exp() << self.frac_bits) | (1 << (self.frac_bits - 1))
+    }
+
+    fn infinity(self, sign: bool) -> u64 {
+        self.zero(sign) | (self.max_biased_exp() << self.frac_bits)
+    }
+
+    fn zero(self, sign: bool) -> u64 {
+        if sign {
+            self.sign_bit()
+        } else {
+            0
+        }
+    }
+
+    /// Splits a bit pattern into its sign and class.
+    fn unpack(self, bits: u64) -> (bool, Class) {
+        let sign = bits & self.sign_bit() != 0;
+        let biased = (bits >> self.frac_bits) & self.max_biased_exp();
+        let frac = bits & ((1 << self.frac_bits) - 1);
+        let class = match (biased, frac) {
+            (0, 0) => Class::Zero,
+            (0, _) => Class::Finite(normalize(frac as u128, self.min_exp())),
+            (max, 0) if max == self.max_biased_exp() => Class::Infinite,
+            (max, _) if max == self.max_biased_exp() => Class::Nan,
+            _ => Class::Finite(normalize(
+                (frac | (1 << self.frac_bits)) as u128,
+                biased as i32 - self.bias() - self.frac_bits as i32,
+            )),
+        };
+        (sign, class)
+    }
+
+    /// Rounds the exact value `sig * 2^exp` to nearest even and encodes it.
+    fn round_pack(self, sign: bool, sig: u128, exp: i32) -> u64 {
+        if sig == 0 {
+            return self.zero(sign);
+        }
+        let precision = self.frac_bits as i32 + 1;
+        let len = 128 - sig.leading_zeros() as i32;
+        let shift = (len - precision).max(self.min_exp() - exp);
+
+        let (mut mantissa, mut exp) = if shift <= 0 {
+            (sig << -shift, exp + shift)
+        } else if shift > len {
+            // Below half the smallest subnormal
+            (0, exp + shift)
+        } else {
+            let mantissa = sig.checked_shr(shift as u32).unwrap_or(0);
+            let rem = sig & (u128::MAX >> (128 - shift));
+            let half = 1 << (shift - 1);
+            let round_up = rem > half || (rem == half && mantissa & 1 == 1);
+            (mantissa + round_up as u128, exp + shift)
+        };
+        if mantissa == 1 << precision {
+            mantissa >>= 1;
+            exp += 1;
+        }
+
+        let mantissa = mantissa as u64;
+        if mantissa < 1 << self.frac_bits {
+            // Subnormal or zero
+            return self.zero(sign) | mantissa;
+        }
+        let biased = (exp + self.bias() + self.frac_bits as i32) as u64;
+        if biased >= self.max_biased_exp() {
+            return self.infinity(sign);
+        }
+        self.zero(sign) | (biased << self.frac_bits) | (mantissa & ((1 << self.frac_bits) - 1))
+    }
+}
+
+/// Classification of an unpacked value.
+#[derive(Clone, Copy)]
+enum Class {
+    Zero,
+    /// A nonzero finite value `sig * 2^exp`, with `sig` normalized
+    Finite((u128, i32)),
+    Infinite,
+    Nan,
+}
+
+/// Shifts a nonzero significand so its leading bit is at [`NORMALIZED_TOP`].
+fn normalize(sig: u128, exp: i32) -> (u128, i32) {
+    let shift = sig.leading_zeros() as i32 - (127 - NORMALIZED_TOP as i32);
+    if shift >= 0 {
+        (sig << shift, exp - shift)
+    } else {
+        // Only products are wider than the normalized width. Products of
+        // single precision values lose nothing; wider ones keep a sticky bit.
+        let lost = sig & ((1 << -shift) - 1) != 0;
+        ((sig >> -shift) | lost as u128, exp - shift)
+    }
+}
+
+/// Exactly adds two signed normalized values, jamming a far smaller addend.
+///
+/// Returns the sign and the sum as `sig * 2^exp`.
+fn add_exact(a: (bool, u128, i32), b: (bool, u128, i32)) -> (bool, u128, i32) {
+    let (large, small) = if (a.2, a.1) >= (b.2, b.1) {
+        (a, b)
+    } else {
+        (b, a)
+    };
+    let diff = (large.2 - small.2) as u32;
+    let (large_sig, small_sig) = if diff <= 64 {
+        (large.1 << diff, small.1)
+    } else {
+        // The small addend lies entirely below the rounding position and
+        // only contributes a sticky bit.
+        (large.1 << 64, 1)
+    };
+    let exp = large.2 - diff.min(64) as i32;
+    if large.0 == small.0 {
+        (large.0, large_sig + small_sig, exp)
+    } else {
+        (large.0, large_sig - small_sig, exp)
+    }
+}
+
+fn add_bits(format: Format, a: u64, b: u64) -> u64 {
+    match (format.unpack(a), format.unpack(b)) {
+        ((_, Class::Nan), _) | (_, (_, Class::Nan)) => format.quiet_nan(),
+        ((sa, Class::Infinite), (sb, Class::Infinite)) if sa != sb => format.quiet_nan(),
+        ((sa, Class::Infinite), _) => format.infinity(sa),
+        (_, (sb, Class::Infinite)) => format.infinity(sb),
+        ((sa, Class::Zero), (sb, Class::Zero)) => format.zero(sa && sb),
+        ((_, Class::Zero), _) => b,
+        (_, (_, Class::Zero)) => a,
+        ((sa, Class::Finite((siga, ea))), (sb, Class::Finite((sigb, eb)))) => {
+            let (sign, sig, exp) = add_exact((sa, siga, ea), (sb, sigb, eb));
+            // An exact cancellation is +0 when rounding to nearest
+            format.round_pack(sign && sig != 0, sig, exp)
+        }
+    }
+}
+
+fn mul_bits(format: Format, a: u64, b: u64) -> u64 {
+    let ((sa, ca), (sb, cb)) = (format.unpack(a), format.unpack(b));
+    let sign = sa != sb;
+    match (ca, cb) {
+        (Class::Nan, _) | (_, Class::Nan) => format.quiet_nan(),
+        (Class::Infinite, Class::Zero) | (Class::Zero, Class::Infinite) => format.quiet_nan(),
+        (Class::Infinite, _) | (_, Class::Infinite) => format.infinity(sign),
+        (Class::Zero, _) | (_, Class::Zero) => format.zero(sign),
+        (Class::Finite((siga, ea)), Class::Finite((sigb, eb))) => {
+            format.round_pack(sign, siga * sigb, ea + eb)
+        }
+    }
+}
+
+fn div_bits(format: Format, a: u64, b: u64) -> u64 {
+    let ((sa, ca), (sb, cb)) = (format.unpack(a), format.unpack(b));
+    let sign = sa != sb;
+    match (ca, cb) {
+        (Class::Nan, _) | (_, Class::Nan) => format.quiet_nan(),
+        (Class::Infinite, Class::Infinite) | (Class::Zero, Class::Zero) => format.quiet_nan(),
+        (Class::Infinite, _) | (_, Class::Zero) => format.infinity(sign),
+        (Class::Zero, _) | (_, Class::Infinite) => format.zero(sign),
+        (Class::Finite((siga, ea)), Class::Finite((sigb, eb))) => {
+            let dividend = siga << 64;
+            let quotient = dividend / sigb;
+            let sticky = dividend % sigb != 0;
+            format.round_pack(sign, (quotient << 1) | sticky as u128, ea - eb - 65)
+        }
+    }
+}
+
+fn sqrt_bits(format: Format, a: u64) -> u64 {
+    match format.unpack(a) {
+        (_, Class::Nan) | (true, Class::Infinite | Class::Finite(_)) => format.quiet_nan(),
+        (_, Class::Zero) => a,
+        (false, Class::Infinite) => a,
+        (false, Class::Finite((sig, exp))) => {
+            let (sig, exp) = if exp % 2 != 0 {
+                (sig << 1, exp - 1)
+            } else {
+                (sig, exp)
+            };
+            let radicand = sig << 64;
+            let root = radicand.isqrt();
+            let sticky = root * root != radicand;
+            format.round_pack(false, (root << 1) | sticky as u128, (exp - 64) / 2 - 1)
+        }
+    }
+}
+
+fn mul_add_bits(format: Format, a: u64, b: u64, c: u64) -> u64 {
+    let ((sa, ca), (sb, cb), (sc, cc)) = (format.unpack(a), format.unpack(b), format.unpack(c));
+    let sign = sa != sb;
+    match (ca, cb, cc) {
+        (Class::Nan, _, _) | (_, Class::Nan, _) | (_, _, Class::Nan) => format.quiet_nan(),
+        (Class::Infinite, Class::Zero, _) | (Class::Zero, Class::Infinite, _) => format.quiet_nan(),
+        (Class::Infinite, _, Class::Infinite) | (_, Class::Infinite, Class::Infinite)
+            if sign != sc =>
+        {
+            format.quiet_nan()
+        }
+        (Class::Infinite, _, _) | (_, Class::Infinite, _) => format.infinity(sign),
+        (_, _, Class::Infinite) => c,
+        (Class::Zero, _, Class::Zero) | (_, Class::Zero, Class::Zero) => format.zero(sign && sc),
+        (Class::Zero, _, _) | (_, Class::Zero, _) => c,
+        (Class::Finite((siga, ea)), Class::Finite((sigb, eb)), cc) => {
+            let product = normalize(siga * sigb, ea + eb);
+            let (sign, sig, exp) = match cc {
+                Class::Finite((sigc, ec)) => {
+                    add_exact((sign, product.0, product.1), (sc, sigc, ec))
+                }
+                _ => (sign, product.0, product.1),
+            };
+            format.round_pack(sign && sig != 0, sig, exp)
+        }
+    }
+}
+
+/// Returns `a + b`.
+pub fn add(a: f32, b: f32) -> f32 {
+    f32::from_bits(add_bits(SINGLE, a.to_bits() as u64, b.to_bits() as u64) as u32)
+}
+
+/// Returns `a - b`.
+pub fn sub(a: f32, b: f32) -> f32 {
+    add(a, -b)
+}
+
+/// Returns `a * b`.
+pub fn mul(a: f32, b: f32) -> f32 {
+    f32::from_bits(mul_bits(SINGLE, a.to_bits() as u64, b.to_bits() as u64) as u32)
+}
+
+/// Returns `a / b`.
+pub fn div(a: f32, b: f32) -> f32 {
+    f32::from_bits(div_bits(SINGLE, a.to_bits() as u64, b.to_bits() as u64) as u32)
+}
+
+/// Returns `a * b + c` with a single rounding.
+pub fn mul_add(a: f32, b: f32, c: f32) -> f32 {
+    let bits = mul_add_bits(
+        SINGLE,
+        a.to_bits() as u64,
+        b.to_bits() as u64,
+        c.to_bits() as u64,
+    );
+    f32::from_bits(bits as u32)
+}
+
+/// Returns the square root of `a`.
+pub fn sqrt(a: f32) -> f32 {
+    f32::from_bits(sqrt_bits(SINGLE, a.to_bits() as u64) as u32)
+}
+
+/// Returns the square root of the double precision `a`.
+pub fn sqrt_d(a: f64) -> f64 {
+    f64::from_bits(sqrt_bits(DOUBLE, a.to_bits()))
+}
+
+/// Converts an integer to the nearest single precision value.
+pub fn from_i32(value: i32) -> f32 {
+    let bits = SINGLE.round_pack(value < 0, value.unsigned_abs() as u128, 0);
+    f32::from_bits(bits as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic bit patterns covering zeros, subnormals, normals,
+    /// infinities and values near the rounding boundaries.
+    fn samples() -> Vec<f32> {
+        let mut values = vec![
+            0.0,
+            -0.0,
+            1.0,
+            -1.0,
+            1.5,
+            3.0,
+            0.1,
+            f32::MAX,
+            f32::MIN_POSITIVE,
+            f32::MIN_POSITIVE / 3.0,
+            f32::from_bits(1),
+            f32::from_bits(0x8000_0001),
+            f32::INFINITY,
+            f32::NEG_INFINITY,
+            1.0 + f32::EPSILON,
+            16_777_217.0,
+        ];
+        let mut state = 0x2545_F491_u32;
+        for _ in 0..200 {
+            // xorshift32
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            if !f32::from_bits(state).is_nan() {
+                values.push(f32::from_bits(state));
+            }
+            // Values of similar magnitude exercise cancellation
+            values.push(f32::from_bits(0x3F80_0000 | (state & 0x80FF_FFFF)));
+        }
+        values
+    }
+
+    /// Compares a soft result with the host's, treating all NaNs as equal.
+    fn assert_same(soft: f32, host: f32, what: &str) {
+        if host.is_nan() {
+            assert_eq!(soft.to_bits(), 0x7FC0_0000, "{}", what);
+        } else {
+            assert_eq!(soft.to_bits(), host.to_bits(), "{}", what);
+        }
+    }
+
+    #[test]
+    fn test_binary_ops_match_host() {
+        let values = samples();
+        for &a in &values {
+            for &b in &values {
+                assert_same(add(a, b), a + b, &format!("{:e} + {:e}", a, b));
+                assert_same(sub(a, b), a - b, &format!("{:e} - {:e}", a, b));
+                assert_same(mul(a, b), a * b, &format!("{:e} * {:e}", a, b));
+                assert_same(div(a, b), a / b, &format!("{:e} / {:e}", a, b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_add_matches_host() {
+        let values = samples();
+        for &a in values.iter().step_by(3) {
+            for &b in values.iter().step_by(5) {
+                for &c in values.iter().step_by(7) {
+                    let what = format!("{:e} * {:e} + {:e}", a, b, c);
+                    assert_same(mul_add(a, b, c), a.mul_add(b, c), &what);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sqrt_and_conversion_match_host() {
+        for a in samples() {
+            assert_same(sqrt(a), a.sqrt(), &format!("sqrt {:e}", a));
+            for d in [a as f64, a as f64 * 1.0e-300] {
+                let host = d.sqrt();
+                let expected = if host.is_nan() {
+                    0x7FF8_0000_0000_0000
+                } else {
+                    host.to_bits()
+                };
+                assert_eq!(sqrt_d(d).to_bits(), expected, "sqrt {:e}", d);
+            }
+            let i = a.to_bits() as i32;
+            assert_eq!(from_i32(i).to_bits(), (i as f32).to_bits(), "{}", i);
+        }
+        assert_eq!(sqrt_d(-1.0).to_bits(), 0x7FF8_0000_0000_0000);
+    }
+}
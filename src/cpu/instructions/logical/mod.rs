@@ -2,11 +2,12 @@
 //!
 //! This module contains implementations of all logical operations including:
 //! - Basic bitwise operations (AND, OR, XOR, NOT)
+//! - Register moves
 //! - Immediate variants of logical operations
 //! - Bit field operations (extract, insert, rotate)
 //! - Bit manipulation operations (clear, set, test)
 
-use crate::cpu::instructions::Instruction;
+use crate::cpu::instructions::{Instruction, Operands};
 use crate::cpu::CPU;
 use crate::memory::Memory;
 
@@ -14,17 +15,38 @@ use crate::memory::Memory;
 pub struct And;
 
 impl Instruction for And {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        cpu.registers[cpu.d] = cpu.registers[cpu.s1] & cpu.registers[cpu.s2];
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = cpu.registers[ops.s1] & cpu.registers[ops.s2];
     }
 }
 
-/// AND immediate instruction: rd = rs1 & immediate
+/// Zero-extends the 16-bit immediate of a logical instruction.
+///
+/// `and` and `and.u` instead fill the other halfword with ones, so that it
+/// passes through unchanged.
+fn logical_immediate(ops: &Operands) -> u32 {
+    ops.imm as u16 as u32
+}
+
+/// AND immediate instruction: rd = rs1 & (0xFFFF0000 | immediate)
+///
+/// The upper halfword of rs1 is left unchanged.
 pub struct AndImmediate;
 
 impl Instruction for AndImmediate {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        cpu.registers[cpu.d] = cpu.registers[cpu.s1] & (cpu.imm as u32);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = cpu.registers[ops.s1] & (0xFFFF_0000 | logical_immediate(ops));
+    }
+}
+
+/// AND upper immediate instruction (`and.u`): rd = rs1 & ((immediate << 16) | 0xFFFF)
+///
+/// The lower halfword of rs1 is left unchanged.
+pub struct AndUpper;
+
+impl Instruction for AndUpper {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = cpu.registers[ops.s1] & ((logical_immediate(ops) << 16) | 0xFFFF);
     }
 }
 
@@ -32,17 +54,26 @@ impl Instruction for AndImmediate {
 pub struct Or;
 
 impl Instruction for Or {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        cpu.registers[cpu.d] = cpu.registers[cpu.s1] | cpu.registers[cpu.s2];
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = cpu.registers[ops.s1] | cpu.registers[ops.s2];
     }
 }
 
-/// OR immediate instruction: rd = rs1 | immediate
+/// OR immediate instruction: rd = rs1 | zero-extended immediate
 pub struct OrImmediate;
 
 impl Instruction for OrImmediate {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        cpu.registers[cpu.d] = cpu.registers[cpu.s1] | (cpu.imm as u32);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = cpu.registers[ops.s1] | logical_immediate(ops);
+    }
+}
+
+/// OR upper immediate instruction (`or.u`): rd = rs1 | (immediate << 16)
+pub struct OrUpper;
+
+impl Instruction for OrUpper {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = cpu.registers[ops.s1] | (logical_immediate(ops) << 16);
     }
 }
 
@@ -50,17 +81,31 @@ impl Instruction for OrImmediate {
 pub struct Xor;
 
 impl Instruction for Xor {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        cpu.registers[cpu.d] = cpu.registers[cpu.s1] ^ cpu.registers[cpu.s2];
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = cpu.registers[ops.s1] ^ cpu.registers[ops.s2];
     }
 }
 
-/// XOR immediate instruction: rd = rs1 ^ immediate
+/// XOR immediate instruction: rd = rs1 ^ zero-extended immediate
 pub struct XorImmediate;
 
 impl Instruction for XorImmediate {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        cpu.registers[cpu.d] = cpu.registers[cpu.s1] ^ (cpu.imm as u32);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = cpu.registers[ops.s1] ^ logical_immediate(ops);
+    }
+}
+
+/// Move instruction: rd = rs1
+///
+/// A convenience for the `or rd, r0, rs` idiom; moving a register to itself
+/// or to r0 does nothing.
+pub struct Mov;
+
+impl Instruction for Mov {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        if ops.d != 0 && ops.d != ops.s1 {
+            cpu.registers[ops.d] = cpu.registers[ops.s1];
+        }
     }
 }
 
@@ -68,40 +113,117 @@ impl Instruction for XorImmediate {
 pub struct Not;
 
 impl Instruction for Not {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        cpu.registers[cpu.d] = !cpu.registers[cpu.s1];
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = !cpu.registers[ops.s1];
+    }
+}
+
+/// Splits a bit-field specifier into (width, offset).
+///
+/// As on the 88000, the width is in bits 9:5 and the offset in bits 4:0; a
+/// width of 0 selects all 32 bits.
+fn field_spec(spec: u32) -> (u32, u32) {
+    let width = (spec >> 5) & 0x1F;
+    (if width == 0 { 32 } else { width }, spec & 0x1F)
+}
+
+fn field_mask(width: u32) -> u32 {
+    if width >= 32 {
+        u32::MAX
+    } else {
+        (1u32 << width) - 1
     }
 }
 
-/// Clear bit instruction: rd = rs1 & ~(1 << rs2)
+fn clear_field(value: u32, spec: u32) -> u32 {
+    let (width, offset) = field_spec(spec);
+    value & !(field_mask(width) << offset)
+}
+
+fn set_field(value: u32, spec: u32) -> u32 {
+    let (width, offset) = field_spec(spec);
+    value | (field_mask(width) << offset)
+}
+
+fn extract_field(value: u32, spec: u32) -> u32 {
+    let (width, offset) = field_spec(spec);
+    (value >> offset) & field_mask(width)
+}
+
+fn extract_signed_field(value: u32, spec: u32) -> u32 {
+    let (width, offset) = field_spec(spec);
+    let field = (value as i32) >> offset;
+    // Shift the field's top bit up to bit 31 and back to sign-extend it
+    let unused = 32 - width;
+    ((field << unused) >> unused) as u32
+}
+
+fn make_field(value: u32, spec: u32) -> u32 {
+    let (width, offset) = field_spec(spec);
+    (value & field_mask(width)) << offset
+}
+
+fn rotate(value: u32, spec: u32) -> u32 {
+    let shift = spec & 0x1F; // Only use lower 5 bits for rotation
+    value.rotate_right(shift)
+}
+
+fn rotate_left(value: u32, spec: u32) -> u32 {
+    let shift = spec & 0x1F; // Only use lower 5 bits for rotation
+    value.rotate_left(shift)
+}
+
+/// Clear field instruction: clears the bit field of rs1 selected by rs2
 pub struct Clr;
 
 impl Instruction for Clr {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let bit = cpu.registers[cpu.s2] & 0x1F; // Only use lower 5 bits for bit position
-        cpu.registers[cpu.d] = cpu.registers[cpu.s1] & !(1 << bit);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = clear_field(cpu.registers[ops.s1], cpu.registers[ops.s2]);
     }
 }
 
-/// Set bit instruction: rd = rs1 | (1 << rs2)
+/// Clear field immediate instruction: width and offset come from the immediate
+pub struct ClrImm;
+
+impl Instruction for ClrImm {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = clear_field(cpu.registers[ops.s1], ops.imm as u32);
+    }
+}
+
+/// Set field instruction: sets the bit field of rs1 selected by rs2
 pub struct Set;
 
 impl Instruction for Set {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let bit = cpu.registers[cpu.s2] & 0x1F; // Only use lower 5 bits for bit position
-        cpu.registers[cpu.d] = cpu.registers[cpu.s1] | (1 << bit);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = set_field(cpu.registers[ops.s1], cpu.registers[ops.s2]);
+    }
+}
+
+/// Set field immediate instruction: width and offset come from the immediate
+pub struct SetImm;
+
+impl Instruction for SetImm {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = set_field(cpu.registers[ops.s1], ops.imm as u32);
     }
 }
 
-/// Extract field instruction: extracts a bit field from rs1
+/// Extract field instruction: extracts a sign-extended bit field from rs1
 pub struct Ext;
 
 impl Instruction for Ext {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let width = cpu.registers[cpu.s2] & 0x1F; // Only use lower 5 bits for width
-        let offset = (cpu.registers[cpu.s2] >> 5) & 0x1F; // Next 5 bits for offset
-        let mask = if width == 0 { 0 } else { (1u32 << width) - 1 };
-        cpu.registers[cpu.d] = (cpu.registers[cpu.s1] >> offset) & mask;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = extract_signed_field(cpu.registers[ops.s1], cpu.registers[ops.s2]);
+    }
+}
+
+/// Extract field immediate instruction: width and offset come from the immediate
+pub struct ExtImm;
+
+impl Instruction for ExtImm {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = extract_signed_field(cpu.registers[ops.s1], ops.imm as u32);
     }
 }
 
@@ -109,11 +231,17 @@ impl Instruction for Ext {
 pub struct ExtU;
 
 impl Instruction for ExtU {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let width = cpu.registers[cpu.s2] & 0x1F; // Only use lower 5 bits for width
-        let offset = (cpu.registers[cpu.s2] >> 5) & 0x1F; // Next 5 bits for offset
-        let mask = if width == 0 { 0 } else { (1u32 << width) - 1 };
-        cpu.registers[cpu.d] = (cpu.registers[cpu.s1] >> offset) & mask;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = extract_field(cpu.registers[ops.s1], cpu.registers[ops.s2]);
+    }
+}
+
+/// Extract unsigned field immediate instruction: width and offset come from the immediate
+pub struct ExtUImm;
+
+impl Instruction for ExtUImm {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = extract_field(cpu.registers[ops.s1], ops.imm as u32);
     }
 }
 
@@ -121,11 +249,17 @@ impl Instruction for ExtU {
 pub struct Mak;
 
 impl Instruction for Mak {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let width = cpu.registers[cpu.s2] & 0x1F; // Only use lower 5 bits for width
-        let offset = (cpu.registers[cpu.s2] >> 5) & 0x1F; // Next 5 bits for offset
-        let mask = if width == 0 { 0 } else { (1u32 << width) - 1 };
-        cpu.registers[cpu.d] = (cpu.registers[cpu.s1] & mask) << offset;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = make_field(cpu.registers[ops.s1], cpu.registers[ops.s2]);
+    }
+}
+
+/// Make field immediate instruction: width and offset come from the immediate
+pub struct MakImm;
+
+impl Instruction for MakImm {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = make_field(cpu.registers[ops.s1], ops.imm as u32);
     }
 }
 
@@ -133,9 +267,35 @@ impl Instruction for Mak {
 pub struct Rot;
 
 impl Instruction for Rot {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let shift = cpu.registers[cpu.s2] & 0x1F; // Only use lower 5 bits for rotation
-        cpu.registers[cpu.d] = cpu.registers[cpu.s1].rotate_right(shift);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = rotate(cpu.registers[ops.s1], cpu.registers[ops.s2]);
+    }
+}
+
+/// Rotate immediate instruction: rotates rs1 right by imm bits
+pub struct RotImm;
+
+impl Instruction for RotImm {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = rotate(cpu.registers[ops.s1], ops.imm as u32);
+    }
+}
+
+/// Rotate left instruction: rotates rs1 left by rs2 bits
+pub struct Rotl;
+
+impl Instruction for Rotl {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = rotate_left(cpu.registers[ops.s1], cpu.registers[ops.s2]);
+    }
+}
+
+/// Rotate left immediate instruction: rotates rs1 left by imm bits
+pub struct RotlImm;
+
+impl Instruction for RotlImm {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = rotate_left(cpu.registers[ops.s1], ops.imm as u32);
     }
 }
 
@@ -143,8 +303,8 @@ impl Instruction for Rot {
 pub struct ExtractUHalf;
 
 impl Instruction for ExtractUHalf {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        cpu.registers[cpu.d] = cpu.registers[cpu.s1] & 0xFFFF;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = cpu.registers[ops.s1] & 0xFFFF;
     }
 }
 
@@ -152,8 +312,8 @@ impl Instruction for ExtractUHalf {
 pub struct ExtractUByte;
 
 impl Instruction for ExtractUByte {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        cpu.registers[cpu.d] = cpu.registers[cpu.s1] & 0xFF;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = cpu.registers[ops.s1] & 0xFF;
     }
 }
 
@@ -161,9 +321,9 @@ impl Instruction for ExtractUByte {
 pub struct ExtractHalf;
 
 impl Instruction for ExtractHalf {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let value = (cpu.registers[cpu.s1] & 0xFFFF) as i16;
-        cpu.registers[cpu.d] = value as i32 as u32;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let value = (cpu.registers[ops.s1] & 0xFFFF) as i16;
+        cpu.registers[ops.d] = value as i32 as u32;
     }
 }
 
@@ -171,22 +331,40 @@ impl Instruction for ExtractHalf {
 pub struct ExtractByte;
 
 impl Instruction for ExtractByte {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let value = (cpu.registers[cpu.s1] & 0xFF) as i8;
-        cpu.registers[cpu.d] = value as i32 as u32;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let value = (cpu.registers[ops.s1] & 0xFF) as i8;
+        cpu.registers[ops.d] = value as i32 as u32;
     }
 }
 
 /// Make field N bits instruction: creates an N-bit field
+///
+/// Equivalent to [`Mak`]: the low N bits of rs1 are placed at the offset,
+/// with N and the offset taken from rs2.
 pub struct MakN;
 
 impl Instruction for MakN {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let n = cpu.registers[cpu.s2] & 0x1F; // Get width (0-31)
-        let offset = (cpu.registers[cpu.s2] >> 5) & 0x1F; // Get offset (0-31)
-        let mask = if n == 0 { 0 } else { (1u32 << n) - 1 };
-        let value = cpu.registers[cpu.s1] & mask;
-        cpu.registers[cpu.d] = value << offset;
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = make_field(cpu.registers[ops.s1], cpu.registers[ops.s2]);
+    }
+}
+
+/// Byte swap instruction: rd = rs1 with its byte order reversed
+pub struct ByteSwap;
+
+impl Instruction for ByteSwap {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        cpu.registers[ops.d] = cpu.registers[ops.s1].swap_bytes();
+    }
+}
+
+/// Halfword swap instruction: swaps the two bytes within each halfword of rs1
+pub struct HalfSwap;
+
+impl Instruction for HalfSwap {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let value = cpu.registers[ops.s1];
+        cpu.registers[ops.d] = ((value & 0x00FF_00FF) << 8) | ((value & 0xFF00_FF00) >> 8);
     }
 }
 
@@ -197,15 +375,16 @@ mod tests {
     #[test]
     fn test_and() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 0xFF00;
         cpu.registers[2] = 0x0FF0;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        And.execute(&mut cpu, &mut memory);
+        And.execute(&mut cpu, &mut memory, &ops);
 
         assert_eq!(cpu.registers[3], 0x0F00);
     }
@@ -213,109 +392,322 @@ mod tests {
     #[test]
     fn test_or() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 0xFF00;
         cpu.registers[2] = 0x0FF0;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        Or.execute(&mut cpu, &mut memory);
+        Or.execute(&mut cpu, &mut memory, &ops);
 
         assert_eq!(cpu.registers[3], 0xFFF0);
     }
 
+    #[test]
+    fn test_mov() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        cpu.registers[1] = 0xDEAD_BEEF;
+        ops.d = 2;
+        ops.s1 = 1;
+        Mov.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 0xDEAD_BEEF);
+        assert_eq!(cpu.registers[1], 0xDEAD_BEEF);
+
+        // Moving a register to itself leaves it unchanged
+        ops.d = 1;
+        Mov.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[1], 0xDEAD_BEEF);
+    }
+
+    #[test]
+    fn test_mov_to_r0_is_discarded() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        cpu.registers[1] = 0x1234;
+        let ops = Operands {
+            d: 0,
+            s1: 1,
+            ..Default::default()
+        };
+        Mov.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[0], 0);
+    }
+
     #[test]
     fn test_extu_half() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 0xFFFF1234;
-        cpu.d = 2;
-        cpu.s1 = 1;
+        ops.d = 2;
+        ops.s1 = 1;
 
-        ExtractUHalf.execute(&mut cpu, &mut memory);
+        ExtractUHalf.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2], 0x1234);
     }
 
     #[test]
     fn test_extu_byte() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 0xFFFFFF12;
-        cpu.d = 2;
-        cpu.s1 = 1;
+        ops.d = 2;
+        ops.s1 = 1;
 
-        ExtractUByte.execute(&mut cpu, &mut memory);
+        ExtractUByte.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2], 0x12);
     }
 
     #[test]
     fn test_ext_half() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test positive number
         cpu.registers[1] = 0x00001234;
-        cpu.d = 2;
-        cpu.s1 = 1;
+        ops.d = 2;
+        ops.s1 = 1;
 
-        ExtractHalf.execute(&mut cpu, &mut memory);
+        ExtractHalf.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2] as i32, 0x1234);
 
         // Test negative number
         cpu.registers[1] = 0x0000F234;
-        ExtractHalf.execute(&mut cpu, &mut memory);
+        ExtractHalf.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2] as i32, -3532); // 0xFFFFF234
     }
 
     #[test]
     fn test_ext_byte() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test positive number
         cpu.registers[1] = 0x00000012;
-        cpu.d = 2;
-        cpu.s1 = 1;
+        ops.d = 2;
+        ops.s1 = 1;
 
-        ExtractByte.execute(&mut cpu, &mut memory);
+        ExtractByte.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2] as i32, 0x12);
 
         // Test negative number
         cpu.registers[1] = 0x000000F2;
-        ExtractByte.execute(&mut cpu, &mut memory);
+        ExtractByte.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2] as i32, -14); // 0xFFFFFFF2
     }
 
     #[test]
     fn test_makn() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test making a 4-bit field at offset 8
         cpu.registers[1] = 0x0000000F; // Value
-        cpu.registers[2] = (8 << 5) | 4; // offset=8, width=4
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        cpu.registers[2] = (4 << 5) | 8; // width=4, offset=8
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        MakN.execute(&mut cpu, &mut memory);
+        MakN.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0x00000F00);
 
-        // Test with zero width
-        cpu.registers[2] = 0; // offset=0, width=0
-        MakN.execute(&mut cpu, &mut memory);
-        assert_eq!(cpu.registers[3], 0);
+        // A zero width selects the whole register
+        cpu.registers[1] = 0x1234_5678;
+        cpu.registers[2] = 0; // width=0, offset=0
+        MakN.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[3], 0x1234_5678);
 
-        // Test with maximum width
+        // Test with maximum explicit width
         cpu.registers[1] = 0xFFFFFFFF;
-        cpu.registers[2] = 31; // offset=0, width=31
-        MakN.execute(&mut cpu, &mut memory);
+        cpu.registers[2] = 31 << 5; // width=31, offset=0
+        MakN.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0x7FFFFFFF);
     }
 
+    #[test]
+    fn test_rot_immediate_matches_register_form() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        cpu.registers[1] = 0x1234_5678;
+        cpu.registers[2] = 8;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
+        ops.imm = 8;
+
+        Rot.execute(&mut cpu, &mut memory, &ops);
+        let register_result = cpu.registers[3];
+        RotImm.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[3], register_result);
+        assert_eq!(cpu.registers[3], 0x7812_3456);
+    }
+
+    #[test]
+    fn test_logical_immediates_zero_extend() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        cpu.registers[1] = 0;
+        ops.d = 2;
+        ops.s1 = 1;
+        ops.imm = 0xFFFFu16 as i16;
+
+        OrImmediate.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 0x0000_FFFF);
+        OrUpper.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 0xFFFF_0000);
+
+        cpu.registers[1] = 0x1234_5678;
+        XorImmediate.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 0x1234_A987);
+        // and and and.u leave the other halfword unchanged
+        AndImmediate.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 0x1234_5678);
+        AndUpper.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 0x1234_5678);
+
+        ops.imm = 0x00F0;
+        AndImmediate.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 0x1234_0070);
+        AndUpper.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 0x0030_5678);
+    }
+
+    #[test]
+    fn test_rotate_left() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        cpu.registers[1] = 0x1234_5678;
+        cpu.registers[2] = 8;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
+        ops.imm = 8;
+
+        Rotl.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[3], 0x3456_7812);
+        cpu.registers[3] = 0;
+        RotlImm.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[3], 0x3456_7812);
+    }
+
+    #[test]
+    fn test_rotate_by_zero_is_identity() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        cpu.registers[1] = 0x1234_5678;
+        cpu.registers[2] = 0;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
+        ops.imm = 0;
+
+        let rotates: [&dyn Instruction; 4] = [&Rot, &RotImm, &Rotl, &RotlImm];
+        for rotate in rotates {
+            cpu.registers[3] = 0;
+            rotate.execute(&mut cpu, &mut memory, &ops);
+            assert_eq!(cpu.registers[3], 0x1234_5678);
+        }
+    }
+
+    #[test]
+    fn test_field_immediates_match_register_forms() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        // 8-bit field at offset 4
+        let spec = (8 << 5) | 4;
+        cpu.registers[1] = 0x0000_ABCD;
+        cpu.registers[2] = spec;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
+        ops.imm = spec as i16;
+
+        let pairs: [(&dyn Instruction, &dyn Instruction); 5] = [
+            (&Clr, &ClrImm),
+            (&Set, &SetImm),
+            (&Ext, &ExtImm),
+            (&ExtU, &ExtUImm),
+            (&Mak, &MakImm),
+        ];
+        for (register_form, immediate_form) in pairs {
+            register_form.execute(&mut cpu, &mut memory, &ops);
+            let register_result = cpu.registers[3];
+            immediate_form.execute(&mut cpu, &mut memory, &ops);
+            assert_eq!(cpu.registers[3], register_result);
+        }
+
+        ExtUImm.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[3], 0xBC);
+        ExtImm.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[3], 0xFFFF_FFBC);
+    }
+
+    #[test]
+    fn test_bit_field_encodings() {
+        use crate::cpu::decoder::decode;
+
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+        cpu.registers[4] = 0x8765_4321;
+
+        // (instruction word, expected r3); the W5<O5> field is in bits 9:0
+        let cases = [
+            (0xF064_9910, 0x0000_0065), // extu r3, r4, 8<16>
+            (0xF064_9918, 0x0000_0087), // extu r3, r4, 8<24>
+            (0xF064_9118, 0xFFFF_FF87), // ext r3, r4, 8<24>
+            (0xF064_9004, 0xF876_5432), // ext r3, r4, 0<4>
+            (0xF064_A188, 0x0003_2100), // mak r3, r4, 12<8>
+            (0xF064_A808, 0x2187_6543), // rot r3, r4, <8>
+            (0xF064_8088, 0x8765_4021), // clr r3, r4, 4<8>
+            (0xF064_8888, 0x8765_4F21), // set r3, r4, 4<8>
+            (0xF064_8010, 0x0000_4321), // clr r3, r4, 0<16>
+        ];
+        for (word, expected) in cases {
+            let decoded = decode(word).unwrap();
+            decoded.execute(&mut cpu, &mut memory).unwrap();
+            assert_eq!(cpu.registers[3], expected, "{}", decoded.mnemonic);
+        }
+    }
+
+    #[test]
+    fn test_byte_swap() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        cpu.registers[1] = 0x12345678;
+        ops.d = 2;
+        ops.s1 = 1;
+
+        ByteSwap.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 0x78563412);
+
+        HalfSwap.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 0x34127856);
+    }
+
     // Add more tests following the same pattern...
 }
@@ -3,15 +3,17 @@
 //! This module contains implementations of Memory Management Unit (MMU) instructions,
 //! including page table management and address translation operations.
 
-use crate::cpu::instructions::system::PrivilegeLevel;
-use crate::cpu::instructions::Instruction;
+use crate::cpu::instructions::{Instruction, Operands};
 use crate::cpu::CPU;
-use crate::memory::Memory;
+use crate::memory::{AccessType, Memory};
 
-/// Load Page Table Base Register instruction
+/// Set page table base instruction: ptbr = rs
+///
+/// Only the base changes; translation is turned on or off separately by
+/// writing `MMU_ENABLE` to the MMU control register.
 #[derive(Debug)]
-pub struct PTBR {
-    pub rd: usize,
+pub struct SetPageTableBase {
+    pub rs: usize,
 }
 
 /// TLB Invalidate instruction
@@ -19,30 +21,37 @@ pub struct PTBR {
 pub struct TLBInvalidate;
 
 /// Translate Virtual Address instruction
+///
+/// On success the physical address is written to `rd`, the protection bits of
+/// the resolved page table entry to `rp`, and `CR0_EQUAL` is set. A failed
+/// translation clears `CR0_EQUAL` and raises a page fault.
 #[derive(Debug)]
 pub struct Translate {
     pub rd: usize,
     pub rs1: usize,
+    pub rp: usize,
 }
 
-impl Instruction for PTBR {
-    fn execute(&self, cpu: &mut CPU, memory: &mut Memory) {
-        if cpu.get_privilege_level() != PrivilegeLevel::Supervisor {
-            cpu.set_privilege_violation();
-            return;
-        }
-        memory.set_mmu_enabled(true);
-        memory.set_page_table_base(cpu.registers[self.rd]);
+impl Instruction for SetPageTableBase {
+    fn execute(&self, cpu: &mut CPU, memory: &mut Memory, _ops: &Operands) {
+        cpu.set_control_register(CPU::CR_PTBR, cpu.registers[self.rs]);
+        memory.set_page_table_base(cpu.ptbr);
+    }
+
+    fn is_privileged(&self) -> bool {
+        true
     }
 }
 
 impl Instruction for TLBInvalidate {
-    fn execute(&self, cpu: &mut CPU, memory: &mut Memory) {
-        if cpu.get_privilege_level() != PrivilegeLevel::Supervisor {
-            cpu.set_privilege_violation();
-            return;
-        }
+    fn execute(&self, cpu: &mut CPU, memory: &mut Memory, _ops: &Operands) {
+        cpu.set_mmu_enabled(false);
         memory.set_mmu_enabled(false);
+        cpu.sync_mmu(memory);
+    }
+
+    fn is_privileged(&self) -> bool {
+        true
     }
 }
 
@@ -50,18 +59,26 @@ impl Instruction for TLBInvalidate {
 pub struct TLBLoad;
 
 impl Instruction for TLBLoad {
-    fn execute(&self, cpu: &mut CPU, memory: &mut Memory) {
+    fn execute(&self, cpu: &mut CPU, memory: &mut Memory, _ops: &Operands) {
         // Load a TLB entry - in our implementation this is handled automatically
         // by the memory subsystem during address translation
-        memory.mmu_enabled = cpu.mmu_enabled();
+        cpu.sync_mmu(memory);
     }
 }
 
 impl Instruction for Translate {
-    fn execute(&self, cpu: &mut CPU, memory: &mut Memory) {
-        match memory.translate_address(cpu.registers[self.rs1]) {
-            Ok(physical_addr) => cpu.registers[self.rd] = physical_addr as u32,
-            Err(_) => cpu.set_page_fault(),
+    fn execute(&self, cpu: &mut CPU, memory: &mut Memory, _ops: &Operands) {
+        let virtual_addr = cpu.registers[self.rs1];
+        match memory.lookup_page(virtual_addr, AccessType::Read) {
+            Ok(pte) => {
+                cpu.registers[self.rd] = pte.physical_page | (virtual_addr & 0xFFF);
+                cpu.registers[self.rp] = pte.protection();
+                cpu.cr0 |= CPU::CR0_EQUAL;
+            }
+            Err(err) => {
+                cpu.cr0 &= !CPU::CR0_EQUAL;
+                cpu.set_page_fault(err.address());
+            }
         }
     }
 }
@@ -69,57 +86,140 @@ impl Instruction for Translate {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cpu::instructions::system::PrivilegeLevel;
+    use crate::cpu::instructions::ExecError;
+    use crate::memory::PageTableEntry;
 
     #[test]
-    fn test_ptbr_privilege() {
+    fn test_set_page_table_base_privilege() {
         let mut cpu = CPU::new();
+        let ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test in user mode (should fail)
         cpu.set_privilege_level(PrivilegeLevel::User);
-        let ptbr = PTBR { rd: 1 };
-        ptbr.execute(&mut cpu, &mut memory);
+        cpu.registers[1] = 0x1000;
+        let set_base = SetPageTableBase { rs: 1 };
+        assert_eq!(
+            set_base.try_execute(&mut cpu, &mut memory, &ops),
+            Err(ExecError::PrivilegeViolation)
+        );
         assert!(cpu.has_privilege_violation());
-        assert!(!memory.is_mmu_enabled());
+        assert_eq!(cpu.ptbr, 0);
 
         // Test in supervisor mode (should succeed)
         cpu.set_privilege_level(PrivilegeLevel::Supervisor);
         cpu.clear_privilege_violation();
-        cpu.registers[1] = 0x1000;
-        ptbr.execute(&mut cpu, &mut memory);
-        assert!(!cpu.has_privilege_violation());
-        assert!(memory.is_mmu_enabled());
+        assert_eq!(set_base.try_execute(&mut cpu, &mut memory, &ops), Ok(()));
+        assert_eq!(cpu.ptbr, 0x1000);
+    }
+
+    #[test]
+    fn test_set_page_table_base_reads_source() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+        cpu.set_privilege_level(PrivilegeLevel::Supervisor);
+        memory.install_identity_mapping(0x4000, 4).unwrap();
+
+        // The base comes from rs; the decoded destination is ignored
+        cpu.registers[3] = 0x4000;
+        ops.d = 1;
+        cpu.registers[1] = 0x9000;
+        SetPageTableBase { rs: 3 }.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.ptbr, 0x4000);
+        assert_eq!(cpu.registers[1], 0x9000);
+        assert_eq!(cpu.registers[3], 0x4000);
+
+        // Setting the base does not turn translation on
+        assert!(!cpu.mmu_enabled());
+        assert!(!memory.is_mmu_enabled());
+
+        // Once enabled, translation walks the table at the new base
+        memory.set_mmu_enabled(true);
+        assert_eq!(
+            memory.translate_address(0x2ABC, AccessType::Read).unwrap(),
+            0x2ABC
+        );
+        assert!(memory.translate_address(0x5000, AccessType::Read).is_err());
     }
 
     #[test]
     fn test_tlb_invalidate() {
         let mut cpu = CPU::new();
+        let ops = Operands::default();
         let mut memory = Memory::new();
         cpu.set_privilege_level(PrivilegeLevel::Supervisor);
         memory.set_mmu_enabled(true);
 
         // Test TLB invalidate
-        TLBInvalidate.execute(&mut cpu, &mut memory);
+        TLBInvalidate.execute(&mut cpu, &mut memory, &ops);
         assert!(!memory.is_mmu_enabled());
     }
 
     #[test]
     fn test_translate() {
         let mut cpu = CPU::new();
+        let ops = Operands::default();
         let mut memory = Memory::new();
 
         // Set up virtual address in rs1
         cpu.registers[1] = 0x1000;
 
         // Test translation with MMU disabled
-        let translate = Translate { rd: 2, rs1: 1 };
-        translate.execute(&mut cpu, &mut memory);
+        let translate = Translate {
+            rd: 2,
+            rs1: 1,
+            rp: 3,
+        };
+        translate.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2], 0x1000);
+        assert!(cpu.cr0 & CPU::CR0_EQUAL != 0);
         assert!(!cpu.cr0 & CPU::CR0_PAGE_FAULT != 0);
 
         // Test translation with MMU enabled but no page table
         memory.set_mmu_enabled(true);
-        translate.execute(&mut cpu, &mut memory);
+        translate.execute(&mut cpu, &mut memory, &ops);
+        assert!(cpu.cr0 & CPU::CR0_PAGE_FAULT != 0);
+        assert!(cpu.cr0 & CPU::CR0_EQUAL == 0);
+    }
+
+    #[test]
+    fn test_translate_reports_protection() {
+        let mut cpu = CPU::new();
+        let ops = Operands::default();
+        let mut memory = Memory::new();
+        memory.set_page_table_base(0x1000);
+        memory.set_mmu_enabled(true);
+
+        // Virtual page 2 -> read-only supervisor page at 0x5000
+        let mut pte = PageTableEntry::new(0x5000);
+        pte.writable = false;
+        pte.supervisor = true;
+        memory.write_physical_u32(0x1008, pte.to_u32()).unwrap();
+        cpu.set_privilege_level(PrivilegeLevel::Supervisor);
+        memory.set_privilege_level(PrivilegeLevel::Supervisor);
+
+        let translate = Translate {
+            rd: 2,
+            rs1: 1,
+            rp: 3,
+        };
+        cpu.registers[1] = 0x2ABC;
+        translate.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 0x5ABC);
+        assert_eq!(cpu.registers[3], pte.protection());
+        assert_eq!(cpu.registers[3], 0b101);
+        assert!(cpu.cr0 & CPU::CR0_EQUAL != 0);
+        assert!(cpu.cr0 & CPU::CR0_PAGE_FAULT == 0);
+
+        // Virtual page 3 is unmapped
+        cpu.registers[1] = 0x3000;
+        cpu.registers[3] = 0xDEAD;
+        translate.execute(&mut cpu, &mut memory, &ops);
+        assert!(cpu.cr0 & CPU::CR0_EQUAL == 0);
         assert!(cpu.cr0 & CPU::CR0_PAGE_FAULT != 0);
+        assert_eq!(cpu.fault_addr, 0x3000);
+        assert_eq!(cpu.registers[3], 0xDEAD);
     }
 }
@@ -9,18 +9,116 @@
 //! - Vector instructions
 //! - System instructions
 //! - MMU instructions
+//! - Block copy pseudo-instruction (with the `block-copy` feature)
+//!
+//! The [`opcode`] module provides enum-based dispatch over the decodable instructions.
 
 pub mod arithmetic;
+#[cfg(feature = "block-copy")]
+pub mod block_copy;
 pub mod control;
 pub mod floating_point;
 pub mod logical;
 pub mod memory_access;
 pub mod mmu;
+pub mod opcode;
 pub mod system;
 pub mod vector;
 
+use crate::cpu::instructions::system::PrivilegeLevel;
 use crate::cpu::CPU;
 use crate::memory::Memory;
+use std::fmt;
+
+/// Ways an instruction can fail to complete normally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecError {
+    /// A data access to the address was not mapped
+    PageFault(u32),
+    /// A store to the address hit a write-protected page
+    WriteProtect(u32),
+    /// The instruction requires supervisor mode
+    PrivilegeViolation,
+    /// A bounds check failed
+    BoundsCheck,
+    /// An operand is not valid for the instruction
+    IllegalOperand,
+    /// Integer division by zero
+    DivideByZero,
+    /// Integer overflow trap
+    Overflow,
+    /// The instruction word does not decode
+    UnimplementedOpcode,
+    /// A trap or other exception through the given vector
+    Trap(u8),
+}
+
+impl ExecError {
+    /// Returns the exception vector the error is delivered through.
+    pub fn vector(&self) -> u8 {
+        match self {
+            ExecError::PageFault(_) | ExecError::WriteProtect(_) => CPU::VECTOR_DATA_ACCESS,
+            ExecError::PrivilegeViolation => CPU::VECTOR_PRIVILEGE_VIOLATION,
+            ExecError::BoundsCheck => CPU::VECTOR_BOUNDS_CHECK,
+            ExecError::IllegalOperand => CPU::VECTOR_ILLEGAL_OPERAND,
+            ExecError::DivideByZero => CPU::VECTOR_INTEGER_DIVIDE,
+            ExecError::Overflow => CPU::VECTOR_INTEGER_OVERFLOW,
+            ExecError::UnimplementedOpcode => CPU::VECTOR_UNIMPLEMENTED_OPCODE,
+            ExecError::Trap(vector) => *vector,
+        }
+    }
+
+    fn from_vector(vector: u8) -> Self {
+        match vector {
+            CPU::VECTOR_PRIVILEGE_VIOLATION => ExecError::PrivilegeViolation,
+            CPU::VECTOR_BOUNDS_CHECK => ExecError::BoundsCheck,
+            CPU::VECTOR_ILLEGAL_OPERAND => ExecError::IllegalOperand,
+            CPU::VECTOR_INTEGER_DIVIDE => ExecError::DivideByZero,
+            CPU::VECTOR_INTEGER_OVERFLOW => ExecError::Overflow,
+            CPU::VECTOR_UNIMPLEMENTED_OPCODE => ExecError::UnimplementedOpcode,
+            vector => ExecError::Trap(vector),
+        }
+    }
+}
+
+impl fmt::Display for ExecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecError::PageFault(addr) => write!(f, "page fault at {:#010x}", addr),
+            ExecError::WriteProtect(addr) => write!(f, "write protection fault at {:#010x}", addr),
+            ExecError::PrivilegeViolation => write!(f, "privilege violation"),
+            ExecError::BoundsCheck => write!(f, "bounds check violation"),
+            ExecError::IllegalOperand => write!(f, "illegal operand"),
+            ExecError::DivideByZero => write!(f, "integer divide by zero"),
+            ExecError::Overflow => write!(f, "integer overflow"),
+            ExecError::UnimplementedOpcode => write!(f, "unimplemented opcode"),
+            ExecError::Trap(vector) => write!(f, "trap through vector {}", vector),
+        }
+    }
+}
+
+/// Operand fields of the instruction being executed.
+///
+/// The decoder builds a fresh set from each instruction word and passes it to
+/// [`Instruction::execute`], so an instruction never sees fields left over
+/// from the one before it. Fields the encoding does not have are zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Operands {
+    /// Destination register
+    pub d: usize,
+    /// First source register
+    pub s1: usize,
+    /// Second source register
+    pub s2: usize,
+    /// Third source register (fused multiply-add)
+    pub s3: usize,
+    /// Immediate value
+    pub imm: i16,
+    /// Memory displacement
+    pub offset: i16,
+    /// 26-bit branch displacement, in words
+    pub disp: i32,
+}
 
 /// Trait defining the interface for all CPU instructions.
 ///
@@ -34,5 +132,51 @@ pub trait Instruction {
     ///
     /// * `cpu` - Mutable reference to the CPU state
     /// * `memory` - Mutable reference to the system memory
-    fn execute(&self, cpu: &mut CPU, memory: &mut Memory);
+    /// * `ops` - Operand fields of the instruction word
+    fn execute(&self, cpu: &mut CPU, memory: &mut Memory, ops: &Operands);
+
+    /// Returns whether the instruction may only execute in supervisor mode.
+    ///
+    /// Privileged instructions do not check the privilege level themselves:
+    /// [`Instruction::try_execute`] raises the privilege violation before
+    /// `execute` runs.
+    fn is_privileged(&self) -> bool {
+        false
+    }
+
+    /// Executes the instruction and reports how it failed, if it did.
+    ///
+    /// The cr0 flags are set exactly as by [`Instruction::execute`]. An
+    /// exception the instruction vectored itself (a trap, divide by zero, ...)
+    /// has already been taken when its error is returned; a data access fault
+    /// or privilege violation is only flagged in cr0 and left for the caller
+    /// to deliver through [`ExecError::vector`]. Faults are reported by the
+    /// instruction that raised them, so a flag left set in cr0 by an earlier
+    /// fault neither hides nor causes one.
+    ///
+    /// # Arguments
+    ///
+    /// * `cpu` - Mutable reference to the CPU state
+    /// * `memory` - Mutable reference to the system memory
+    /// * `ops` - Operand fields of the instruction word
+    fn try_execute(
+        &self,
+        cpu: &mut CPU,
+        memory: &mut Memory,
+        ops: &Operands,
+    ) -> Result<(), ExecError> {
+        cpu.exception_taken = None;
+        cpu.data_fault = None;
+        if self.is_privileged() && cpu.get_privilege_level() != PrivilegeLevel::Supervisor {
+            cpu.set_privilege_violation();
+            return Err(ExecError::PrivilegeViolation);
+        }
+        self.execute(cpu, memory, ops);
+
+        let error = match cpu.exception_taken {
+            Some(vector) => Some(ExecError::from_vector(vector)),
+            None => cpu.data_fault.take(),
+        };
+        error.map_or(Ok(()), Err)
+    }
 }
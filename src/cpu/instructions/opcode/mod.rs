@@ -0,0 +1,308 @@
+//! Enum-based instruction dispatch for the Motorola 88000.
+//!
+//! The decoder produces an [`Opcode`] rather than a boxed trait object, so the
+//! step loop executes instructions through a single `match` without heap
+//! allocation or dynamic dispatch. Each variant forwards to the corresponding
+//! [`Instruction`] implementation, which remains the single definition of the
+//! instruction's behavior.
+
+use crate::cpu::instructions::arithmetic::{
+    Add, AddImmediate, AddU, AddUImmediate, Cmp, CmpUImmediate, Div, DivU, Mul, Sub, SubImmediate,
+    SubU, SubUImmediate, FF0, FF1,
+};
+use crate::cpu::instructions::control::{Br, BrN, Jal, Jr, Ldcr, Rte, Stcr, Tbnd, TbndImmediate};
+use crate::cpu::instructions::floating_point::{
+    FAdd, FCmp, FDiv, FMul, FSub, FTrunc, FpToInt, IntToFp,
+};
+use crate::cpu::instructions::logical::{
+    And, AndImmediate, AndUpper, Clr, ClrImm, Ext, ExtImm, ExtU, ExtUImm, Mak, MakImm, Or,
+    OrImmediate, OrUpper, Rot, RotImm, Set, SetImm, Xor, XorImmediate,
+};
+use crate::cpu::instructions::memory_access::{
+    Exchange, Load, LoadByte, LoadByteU, LoadDouble, LoadHalf, LoadHalfU, Store, StoreByte,
+    StoreDouble, StoreHalf,
+};
+use crate::cpu::instructions::system::{Halt, Nop, PipelineSync};
+use crate::cpu::instructions::{Instruction, Operands};
+use crate::cpu::CPU;
+use crate::memory::Memory;
+
+/// A decodable instruction, one variant per instruction implementation.
+#[derive(Debug)]
+pub enum Opcode {
+    // Memory access
+    Exchange,
+    Load,
+    LoadByte,
+    LoadByteU,
+    LoadDouble,
+    LoadHalf,
+    LoadHalfU,
+    Store,
+    StoreByte,
+    StoreDouble,
+    StoreHalf,
+    // Logical
+    And,
+    AndImmediate,
+    AndUpper,
+    Or,
+    OrImmediate,
+    OrUpper,
+    Xor,
+    XorImmediate,
+    Clr,
+    ClrImm,
+    Set,
+    SetImm,
+    Ext,
+    ExtImm,
+    ExtU,
+    ExtUImm,
+    Mak,
+    MakImm,
+    Rot,
+    RotImm,
+    // Integer arithmetic
+    Add,
+    AddImmediate,
+    AddU,
+    AddUImmediate,
+    Sub,
+    SubImmediate,
+    SubU,
+    SubUImmediate,
+    Mul,
+    Div,
+    DivU,
+    Cmp,
+    CmpUImmediate,
+    FF0,
+    FF1,
+    // Control flow
+    Br,
+    BrN,
+    Jr,
+    Jal,
+    Ldcr(Ldcr),
+    Stcr(Stcr),
+    Rte,
+    Tbnd,
+    TbndImmediate,
+    // Floating point
+    FAdd,
+    FSub,
+    FMul,
+    FDiv,
+    FCmp,
+    IntToFp,
+    FpToInt,
+    FTrunc,
+    // System
+    Nop,
+    PipelineSync,
+    Halt,
+}
+
+impl Opcode {
+    /// Executes the instruction.
+    ///
+    /// # Arguments
+    ///
+    /// * `cpu` - Mutable reference to the CPU state
+    /// * `memory` - Mutable reference to the system memory
+    /// * `ops` - Operand fields of the instruction word
+    #[inline]
+    pub fn execute(&self, cpu: &mut CPU, memory: &mut Memory, ops: &Operands) {
+        match self {
+            Opcode::Exchange => Exchange.execute(cpu, memory, ops),
+            Opcode::Load => Load.execute(cpu, memory, ops),
+            Opcode::LoadByte => LoadByte.execute(cpu, memory, ops),
+            Opcode::LoadByteU => LoadByteU.execute(cpu, memory, ops),
+            Opcode::LoadDouble => LoadDouble.execute(cpu, memory, ops),
+            Opcode::LoadHalf => LoadHalf.execute(cpu, memory, ops),
+            Opcode::LoadHalfU => LoadHalfU.execute(cpu, memory, ops),
+            Opcode::Store => Store.execute(cpu, memory, ops),
+            Opcode::StoreByte => StoreByte.execute(cpu, memory, ops),
+            Opcode::StoreDouble => StoreDouble.execute(cpu, memory, ops),
+            Opcode::StoreHalf => StoreHalf.execute(cpu, memory, ops),
+            Opcode::And => And.execute(cpu, memory, ops),
+            Opcode::AndImmediate => AndImmediate.execute(cpu, memory, ops),
+            Opcode::AndUpper => AndUpper.execute(cpu, memory, ops),
+            Opcode::Or => Or.execute(cpu, memory, ops),
+            Opcode::OrImmediate => OrImmediate.execute(cpu, memory, ops),
+            Opcode::OrUpper => OrUpper.execute(cpu, memory, ops),
+            Opcode::Xor => Xor.execute(cpu, memory, ops),
+            Opcode::XorImmediate => XorImmediate.execute(cpu, memory, ops),
+            Opcode::Clr => Clr.execute(cpu, memory, ops),
+            Opcode::ClrImm => ClrImm.execute(cpu, memory, ops),
+            Opcode::Set => Set.execute(cpu, memory, ops),
+            Opcode::SetImm => SetImm.execute(cpu, memory, ops),
+            Opcode::Ext => Ext.execute(cpu, memory, ops),
+            Opcode::ExtImm => ExtImm.execute(cpu, memory, ops),
+            Opcode::ExtU => ExtU.execute(cpu, memory, ops),
+            Opcode::ExtUImm => ExtUImm.execute(cpu, memory, ops),
+            Opcode::Mak => Mak.execute(cpu, memory, ops),
+            Opcode::MakImm => MakImm.execute(cpu, memory, ops),
+            Opcode::Rot => Rot.execute(cpu, memory, ops),
+            Opcode::RotImm => RotImm.execute(cpu, memory, ops),
+            Opcode::Add => Add.execute(cpu, memory, ops),
+            Opcode::AddImmediate => AddImmediate.execute(cpu, memory, ops),
+            Opcode::AddU => AddU.execute(cpu, memory, ops),
+            Opcode::AddUImmediate => AddUImmediate.execute(cpu, memory, ops),
+            Opcode::Sub => Sub.execute(cpu, memory, ops),
+            Opcode::SubImmediate => SubImmediate.execute(cpu, memory, ops),
+            Opcode::SubU => SubU.execute(cpu, memory, ops),
+            Opcode::SubUImmediate => SubUImmediate.execute(cpu, memory, ops),
+            Opcode::Mul => Mul.execute(cpu, memory, ops),
+            Opcode::Div => Div.execute(cpu, memory, ops),
+            Opcode::DivU => DivU.execute(cpu, memory, ops),
+            Opcode::Cmp => Cmp.execute(cpu, memory, ops),
+            Opcode::CmpUImmediate => CmpUImmediate.execute(cpu, memory, ops),
+            Opcode::FF0 => FF0.execute(cpu, memory, ops),
+            Opcode::FF1 => FF1.execute(cpu, memory, ops),
+            Opcode::Br => Br.execute(cpu, memory, ops),
+            Opcode::BrN => BrN.execute(cpu, memory, ops),
+            Opcode::Jr => Jr.execute(cpu, memory, ops),
+            Opcode::Jal => Jal.execute(cpu, memory, ops),
+            Opcode::Ldcr(ldcr) => ldcr.execute(cpu, memory, ops),
+            Opcode::Stcr(stcr) => stcr.execute(cpu, memory, ops),
+            Opcode::Rte => Rte.execute(cpu, memory, ops),
+            Opcode::Tbnd => Tbnd.execute(cpu, memory, ops),
+            Opcode::TbndImmediate => TbndImmediate.execute(cpu, memory, ops),
+            Opcode::FAdd => FAdd.execute(cpu, memory, ops),
+            Opcode::FSub => FSub.execute(cpu, memory, ops),
+            Opcode::FMul => FMul.execute(cpu, memory, ops),
+            Opcode::FDiv => FDiv.execute(cpu, memory, ops),
+            Opcode::FCmp => FCmp.execute(cpu, memory, ops),
+            Opcode::IntToFp => IntToFp.execute(cpu, memory, ops),
+            Opcode::FpToInt => FpToInt.execute(cpu, memory, ops),
+            Opcode::FTrunc => FTrunc.execute(cpu, memory, ops),
+            Opcode::Nop => Nop.execute(cpu, memory, ops),
+            Opcode::PipelineSync => PipelineSync.execute(cpu, memory, ops),
+            Opcode::Halt => Halt.execute(cpu, memory, ops),
+        }
+    }
+
+    /// Returns whether the instruction may only execute in supervisor mode.
+    #[inline]
+    pub fn is_privileged(&self) -> bool {
+        match self {
+            Opcode::Exchange => Exchange.is_privileged(),
+            Opcode::Load => Load.is_privileged(),
+            Opcode::LoadByte => LoadByte.is_privileged(),
+            Opcode::LoadByteU => LoadByteU.is_privileged(),
+            Opcode::LoadDouble => LoadDouble.is_privileged(),
+            Opcode::LoadHalf => LoadHalf.is_privileged(),
+            Opcode::LoadHalfU => LoadHalfU.is_privileged(),
+            Opcode::Store => Store.is_privileged(),
+            Opcode::StoreByte => StoreByte.is_privileged(),
+            Opcode::StoreDouble => StoreDouble.is_privileged(),
+            Opcode::StoreHalf => StoreHalf.is_privileged(),
+            Opcode::And => And.is_privileged(),
+            Opcode::AndImmediate => AndImmediate.is_privileged(),
+            Opcode::AndUpper => AndUpper.is_privileged(),
+            Opcode::Or => Or.is_privileged(),
+            Opcode::OrImmediate => OrImmediate.is_privileged(),
+            Opcode::OrUpper => OrUpper.is_privileged(),
+            Opcode::Xor => Xor.is_privileged(),
+            Opcode::XorImmediate => XorImmediate.is_privileged(),
+            Opcode::Clr => Clr.is_privileged(),
+            Opcode::ClrImm => ClrImm.is_privileged(),
+            Opcode::Set => Set.is_privileged(),
+            Opcode::SetImm => SetImm.is_privileged(),
+            Opcode::Ext => Ext.is_privileged(),
+            Opcode::ExtImm => ExtImm.is_privileged(),
+            Opcode::ExtU => ExtU.is_privileged(),
+            Opcode::ExtUImm => ExtUImm.is_privileged(),
+            Opcode::Mak => Mak.is_privileged(),
+            Opcode::MakImm => MakImm.is_privileged(),
+            Opcode::Rot => Rot.is_privileged(),
+            Opcode::RotImm => RotImm.is_privileged(),
+            Opcode::Add => Add.is_privileged(),
+            Opcode::AddImmediate => AddImmediate.is_privileged(),
+            Opcode::AddU => AddU.is_privileged(),
+            Opcode::AddUImmediate => AddUImmediate.is_privileged(),
+            Opcode::Sub => Sub.is_privileged(),
+            Opcode::SubImmediate => SubImmediate.is_privileged(),
+            Opcode::SubU => SubU.is_privileged(),
+            Opcode::SubUImmediate => SubUImmediate.is_privileged(),
+            Opcode::Mul => Mul.is_privileged(),
+            Opcode::Div => Div.is_privileged(),
+            Opcode::DivU => DivU.is_privileged(),
+            Opcode::Cmp => Cmp.is_privileged(),
+            Opcode::CmpUImmediate => CmpUImmediate.is_privileged(),
+            Opcode::FF0 => FF0.is_privileged(),
+            Opcode::FF1 => FF1.is_privileged(),
+            Opcode::Br => Br.is_privileged(),
+            Opcode::BrN => BrN.is_privileged(),
+            Opcode::Jr => Jr.is_privileged(),
+            Opcode::Jal => Jal.is_privileged(),
+            Opcode::Ldcr(ldcr) => ldcr.is_privileged(),
+            Opcode::Stcr(stcr) => stcr.is_privileged(),
+            Opcode::Rte => Rte.is_privileged(),
+            Opcode::Tbnd => Tbnd.is_privileged(),
+            Opcode::TbndImmediate => TbndImmediate.is_privileged(),
+            Opcode::FAdd => FAdd.is_privileged(),
+            Opcode::FSub => FSub.is_privileged(),
+            Opcode::FMul => FMul.is_privileged(),
+            Opcode::FDiv => FDiv.is_privileged(),
+            Opcode::FCmp => FCmp.is_privileged(),
+            Opcode::IntToFp => IntToFp.is_privileged(),
+            Opcode::FpToInt => FpToInt.is_privileged(),
+            Opcode::FTrunc => FTrunc.is_privileged(),
+            Opcode::Nop => Nop.is_privileged(),
+            Opcode::PipelineSync => PipelineSync.is_privileged(),
+            Opcode::Halt => Halt.is_privileged(),
+        }
+    }
+}
+
+impl Instruction for Opcode {
+    fn execute(&self, cpu: &mut CPU, memory: &mut Memory, ops: &Operands) {
+        Opcode::execute(self, cpu, memory, ops);
+    }
+
+    fn is_privileged(&self) -> bool {
+        Opcode::is_privileged(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::decoder::decode;
+
+    #[test]
+    fn test_enum_dispatch_matches_trait_objects() {
+        // (instruction word, equivalent trait object)
+        let samples: Vec<(u32, Box<dyn Instruction>)> = vec![
+            (0x6062_1234, Box::new(AddUImmediate)), // addu r3, r2, 0x1234
+            (0xF485_6406, Box::new(SubU)),          // subu r4, r5, r6
+            (0xF485_7806, Box::new(Div)),           // div r4, r5, r6
+            (0xF485_7C06, Box::new(Cmp)),           // cmp r4, r5, r6
+            (0xF485_5806, Box::new(Or)),            // or r4, r5, r6
+            (0xF485_A806, Box::new(Rot)),           // rot r4, r5, r6
+            (0x8485_2806, Box::new(FAdd)),          // fadd r4, r5, r6
+        ];
+
+        for (word, boxed) in samples {
+            let decoded = decode(word).unwrap();
+            let ops = decoded.operands();
+            let mut memory = Memory::new();
+
+            let mut enum_cpu = CPU::new();
+            let mut trait_cpu = CPU::new();
+            for cpu in [&mut enum_cpu, &mut trait_cpu] {
+                cpu.registers[2] = 0x4048_0000;
+                cpu.registers[5] = 0x3FC0_0000;
+                cpu.registers[6] = 7;
+            }
+
+            decoded.opcode.execute(&mut enum_cpu, &mut memory, &ops);
+            boxed.execute(&mut trait_cpu, &mut memory, &ops);
+            assert_eq!(enum_cpu.registers, trait_cpu.registers, "{:08x}", word);
+            assert_eq!(enum_cpu.cr0, trait_cpu.cr0, "{:08x}", word);
+        }
+    }
+}
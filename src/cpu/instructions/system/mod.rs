@@ -6,7 +6,7 @@
 //! - Privileged operations
 //! - System maintenance functions
 
-use crate::cpu::instructions::Instruction;
+use crate::cpu::instructions::{Instruction, Operands};
 use crate::cpu::CPU;
 use crate::memory::Memory;
 
@@ -72,88 +72,198 @@ impl DCache {
 }
 
 impl Instruction for ICache {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        // Check privilege level
-        if cpu.get_privilege_level() != PrivilegeLevel::Supervisor {
-            cpu.set_privilege_violation();
-        }
+    fn execute(&self, _cpu: &mut CPU, _memory: &mut Memory, _ops: &Operands) {
         // Cache operations are no-ops in this emulator
     }
+
+    fn is_privileged(&self) -> bool {
+        true
+    }
 }
 
 impl Instruction for DCache {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        // Check privilege level
-        if cpu.get_privilege_level() != PrivilegeLevel::Supervisor {
-            cpu.set_privilege_violation();
-        }
+    fn execute(&self, _cpu: &mut CPU, _memory: &mut Memory, _ops: &Operands) {
         // Cache operations are no-ops in this emulator
     }
+
+    fn is_privileged(&self) -> bool {
+        true
+    }
 }
 
 impl Instruction for FlushCache {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        // Check privilege level
-        if cpu.get_privilege_level() != PrivilegeLevel::Supervisor {
-            cpu.set_privilege_violation();
-        }
+    fn execute(&self, _cpu: &mut CPU, _memory: &mut Memory, _ops: &Operands) {
         // Cache flush operations are no-ops in this emulator
     }
+
+    fn is_privileged(&self) -> bool {
+        true
+    }
 }
 
 impl Instruction for CacheInvalidate {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        // Check privilege level
-        if cpu.get_privilege_level() != PrivilegeLevel::Supervisor {
-            cpu.set_privilege_violation();
-        }
+    fn execute(&self, _cpu: &mut CPU, _memory: &mut Memory, _ops: &Operands) {
         // Cache invalidate operations are no-ops in this emulator
     }
+
+    fn is_privileged(&self) -> bool {
+        true
+    }
 }
 
 impl Instruction for CacheFlush {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        // Check privilege level
-        if cpu.get_privilege_level() != PrivilegeLevel::Supervisor {
-            cpu.set_privilege_violation();
-        }
+    fn execute(&self, _cpu: &mut CPU, _memory: &mut Memory, _ops: &Operands) {
         // Cache flush operations are no-ops in this emulator
     }
+
+    fn is_privileged(&self) -> bool {
+        true
+    }
 }
 
 impl Instruction for CachePrefetch {
-    fn execute(&self, _cpu: &mut CPU, _memory: &mut Memory) {
+    fn execute(&self, _cpu: &mut CPU, _memory: &mut Memory, _ops: &Operands) {
         // Cache prefetch operations are allowed in user mode
         // but are no-ops in this emulator
     }
 }
 
+/// Halt instruction: stops the processor (supervisor only)
+///
+/// Guest programs reach it through the reserved
+/// [`HALT`](crate::cpu::decoder::HALT) encoding.
+pub struct Halt;
+
+impl Instruction for Halt {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, _ops: &Operands) {
+        cpu.halted = true;
+    }
+
+    fn is_privileged(&self) -> bool {
+        true
+    }
+}
+
+/// No operation instruction: advances the program counter only
+///
+/// The canonical encoding is `or r0, r0, r0`.
+pub struct Nop;
+
+impl Instruction for Nop {
+    fn execute(&self, _cpu: &mut CPU, _memory: &mut Memory, _ops: &Operands) {}
+}
+
+/// Pipeline synchronization instruction: a barrier for outstanding memory accesses
+///
+/// Encoded as the `tb1 0, r0, 255` idiom, which never traps. Memory accesses
+/// complete immediately in this emulator, so there is nothing to wait for.
+pub struct PipelineSync;
+
+impl Instruction for PipelineSync {
+    fn execute(&self, _cpu: &mut CPU, _memory: &mut Memory, _ops: &Operands) {
+        // All memory accesses have completed by the time an instruction retires
+    }
+}
+
+/// Read performance counter instruction: rd = counter\[imm\]
+///
+/// Available in user mode. Every instruction is modelled as taking a single
+/// cycle, so the cycle counter reads the low word of the retired instruction
+/// count. An unknown selector is an illegal operand.
+pub struct ReadPerfCounter;
+
+impl ReadPerfCounter {
+    /// Counter selector: low word of the retired instruction count
+    pub const INSTRET_LOW: i16 = 0;
+    /// Counter selector: high word of the retired instruction count
+    pub const INSTRET_HIGH: i16 = 1;
+    /// Counter selector: elapsed cycles
+    pub const CYCLES: i16 = 2;
+}
+
+impl Instruction for ReadPerfCounter {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let value = match ops.imm {
+            Self::INSTRET_LOW | Self::CYCLES => cpu.instret as u32,
+            Self::INSTRET_HIGH => (cpu.instret >> 32) as u32,
+            _ => {
+                cpu.raise_exception(CPU::VECTOR_ILLEGAL_OPERAND);
+                return;
+            }
+        };
+        cpu.registers[ops.d] = value;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cpu::instructions::ExecError;
+
+    #[test]
+    fn test_read_perf_counter() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        // Five nops retired in user mode
+        for i in 0..5 {
+            memory
+                .write_word(0x1000 + i * 4, crate::cpu::decoder::NOP)
+                .unwrap();
+        }
+        cpu.pc = 0x1000;
+        for _ in 0..5 {
+            cpu.step(&mut memory).unwrap();
+        }
+
+        ops.d = 2;
+        ops.imm = ReadPerfCounter::INSTRET_LOW;
+        assert_eq!(
+            ReadPerfCounter.try_execute(&mut cpu, &mut memory, &ops),
+            Ok(())
+        );
+        assert_eq!(cpu.registers[2], 5);
+        ops.imm = ReadPerfCounter::CYCLES;
+        ReadPerfCounter.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 5);
+
+        cpu.instret = 0x0000_0003_0000_0001;
+        ops.imm = ReadPerfCounter::INSTRET_HIGH;
+        ReadPerfCounter.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[2], 3);
+
+        ops.imm = 3;
+        assert_eq!(
+            ReadPerfCounter.try_execute(&mut cpu, &mut memory, &ops),
+            Err(ExecError::IllegalOperand)
+        );
+    }
 
     #[test]
     fn test_icache_privilege_levels() {
         let mut cpu = CPU::new();
+        let ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test in user mode (should fail)
         cpu.set_privilege_level(PrivilegeLevel::User);
         let icache = ICache::new(CacheOperation::Invalidate);
         let initial_state = cpu.cr0;
-        icache.execute(&mut cpu, &mut memory);
+        let _ = icache.try_execute(&mut cpu, &mut memory, &ops);
         assert_ne!(cpu.cr0, initial_state); // Should have privilege violation flag set
 
         // Test in supervisor mode (should succeed)
         cpu.set_privilege_level(PrivilegeLevel::Supervisor);
         cpu.cr0 = initial_state;
-        icache.execute(&mut cpu, &mut memory);
+        icache.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.cr0, initial_state); // Should not change state
     }
 
     #[test]
     fn test_dcache_operations() {
         let mut cpu = CPU::new();
+        let ops = Operands::default();
         let mut memory = Memory::new();
         cpu.set_privilege_level(PrivilegeLevel::Supervisor);
 
@@ -168,9 +278,9 @@ mod tests {
         ];
 
         for op in operations {
-            let dcache = DCache::new(op.clone());
+            let dcache = DCache::new(op);
             let initial_state = cpu.cr0;
-            dcache.execute(&mut cpu, &mut memory);
+            dcache.execute(&mut cpu, &mut memory, &ops);
             assert_eq!(
                 cpu.cr0, initial_state,
                 "Cache operation {:?} modified CPU state",
@@ -182,6 +292,7 @@ mod tests {
     #[test]
     fn test_cache_flush_sequence() {
         let mut cpu = CPU::new();
+        let ops = Operands::default();
         let mut memory = Memory::new();
         cpu.set_privilege_level(PrivilegeLevel::Supervisor);
 
@@ -190,14 +301,14 @@ mod tests {
 
         // 1. Invalidate instruction cache
         let icache = ICache::new(CacheOperation::Invalidate);
-        icache.execute(&mut cpu, &mut memory);
+        icache.execute(&mut cpu, &mut memory, &ops);
 
         // 2. Flush data cache
         let dcache = DCache::new(CacheOperation::Flush);
-        dcache.execute(&mut cpu, &mut memory);
+        dcache.execute(&mut cpu, &mut memory, &ops);
 
         // 3. Final flush
-        FlushCache.execute(&mut cpu, &mut memory);
+        FlushCache.execute(&mut cpu, &mut memory, &ops);
 
         assert_eq!(
             cpu.cr0, initial_state,
@@ -208,6 +319,7 @@ mod tests {
     #[test]
     fn test_cache_lock_operations() {
         let mut cpu = CPU::new();
+        let ops = Operands::default();
         let mut memory = Memory::new();
         cpu.set_privilege_level(PrivilegeLevel::Supervisor);
 
@@ -216,15 +328,15 @@ mod tests {
 
         // 1. Load lock
         let dcache_load = DCache::new(CacheOperation::LoadLock);
-        dcache_load.execute(&mut cpu, &mut memory);
+        dcache_load.execute(&mut cpu, &mut memory, &ops);
 
         // 2. Store lock
         let dcache_store = DCache::new(CacheOperation::StoreLock);
-        dcache_store.execute(&mut cpu, &mut memory);
+        dcache_store.execute(&mut cpu, &mut memory, &ops);
 
         // 3. Clear lock
         let dcache_clear = DCache::new(CacheOperation::ClearLock);
-        dcache_clear.execute(&mut cpu, &mut memory);
+        dcache_clear.execute(&mut cpu, &mut memory, &ops);
 
         assert_eq!(
             cpu.cr0, initial_state,
@@ -235,12 +347,13 @@ mod tests {
     #[test]
     fn test_cache_prefetch_user_mode() {
         let mut cpu = CPU::new();
+        let ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test prefetch in user mode (should be allowed)
         cpu.set_privilege_level(PrivilegeLevel::User);
         let initial_state = cpu.cr0;
-        CachePrefetch.execute(&mut cpu, &mut memory);
+        CachePrefetch.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(
             cpu.cr0, initial_state,
             "Prefetch in user mode modified CPU state"
@@ -250,11 +363,12 @@ mod tests {
     #[test]
     fn test_cache_invalidate_error_handling() {
         let mut cpu = CPU::new();
+        let ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test invalid privilege level
         cpu.set_privilege_level(PrivilegeLevel::User);
-        CacheInvalidate.execute(&mut cpu, &mut memory);
+        let _ = CacheInvalidate.try_execute(&mut cpu, &mut memory, &ops);
         assert!(
             cpu.has_privilege_violation(),
             "Privilege violation not detected"
@@ -263,7 +377,7 @@ mod tests {
         // Test supervisor mode
         cpu.set_privilege_level(PrivilegeLevel::Supervisor);
         cpu.clear_privilege_violation();
-        CacheInvalidate.execute(&mut cpu, &mut memory);
+        CacheInvalidate.execute(&mut cpu, &mut memory, &ops);
         assert!(
             !cpu.has_privilege_violation(),
             "False privilege violation detected"
@@ -273,11 +387,12 @@ mod tests {
     #[test]
     fn test_cache_flush_error_handling() {
         let mut cpu = CPU::new();
+        let ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test invalid privilege level
         cpu.set_privilege_level(PrivilegeLevel::User);
-        CacheFlush.execute(&mut cpu, &mut memory);
+        let _ = CacheFlush.try_execute(&mut cpu, &mut memory, &ops);
         assert!(
             cpu.has_privilege_violation(),
             "Privilege violation not detected"
@@ -286,7 +401,7 @@ mod tests {
         // Test supervisor mode
         cpu.set_privilege_level(PrivilegeLevel::Supervisor);
         cpu.clear_privilege_violation();
-        CacheFlush.execute(&mut cpu, &mut memory);
+        CacheFlush.execute(&mut cpu, &mut memory, &ops);
         assert!(
             !cpu.has_privilege_violation(),
             "False privilege violation detected"
@@ -296,6 +411,7 @@ mod tests {
     #[test]
     fn test_cache_operations_state_preservation() {
         let mut cpu = CPU::new();
+        let ops = Operands::default();
         let mut memory = Memory::new();
         cpu.set_privilege_level(PrivilegeLevel::Supervisor);
 
@@ -305,12 +421,12 @@ mod tests {
         let initial_registers = cpu.registers;
 
         // Execute all cache operations
-        ICache::new(CacheOperation::Invalidate).execute(&mut cpu, &mut memory);
-        DCache::new(CacheOperation::Flush).execute(&mut cpu, &mut memory);
-        FlushCache.execute(&mut cpu, &mut memory);
-        CacheInvalidate.execute(&mut cpu, &mut memory);
-        CacheFlush.execute(&mut cpu, &mut memory);
-        CachePrefetch.execute(&mut cpu, &mut memory);
+        ICache::new(CacheOperation::Invalidate).execute(&mut cpu, &mut memory, &ops);
+        DCache::new(CacheOperation::Flush).execute(&mut cpu, &mut memory, &ops);
+        FlushCache.execute(&mut cpu, &mut memory, &ops);
+        CacheInvalidate.execute(&mut cpu, &mut memory, &ops);
+        CacheFlush.execute(&mut cpu, &mut memory, &ops);
+        CachePrefetch.execute(&mut cpu, &mut memory, &ops);
 
         // Verify register state is preserved
         assert_eq!(
@@ -318,4 +434,26 @@ mod tests {
             "Cache operations modified register state"
         );
     }
+
+    #[test]
+    fn test_halt_privilege() {
+        let mut cpu = CPU::new();
+        let ops = Operands::default();
+        let mut memory = Memory::new();
+
+        // User mode raises a privilege violation instead of halting
+        cpu.set_privilege_level(PrivilegeLevel::User);
+        assert_eq!(
+            Halt.try_execute(&mut cpu, &mut memory, &ops),
+            Err(ExecError::PrivilegeViolation)
+        );
+        assert!(cpu.has_privilege_violation());
+        assert!(!cpu.halted);
+
+        cpu.clear_privilege_violation();
+        cpu.set_privilege_level(PrivilegeLevel::Supervisor);
+        Halt.execute(&mut cpu, &mut memory, &ops);
+        assert!(!cpu.has_privilege_violation());
+        assert!(cpu.halted);
+    }
 }
@@ -1,4 +1,5 @@
-use crate::cpu::instructions::Instruction;
+use crate::cpu::instructions::floating_point::arith;
+use crate::cpu::instructions::{Instruction, Operands};
 use crate::cpu::CPU;
 use crate::memory::Memory;
 
@@ -32,13 +33,38 @@ pub struct VMax;
 // Vector Minimum instruction
 pub struct VMin;
 
+// Vector Float Maximum instruction
+//
+// Follows the fmax convention for NaN: a NaN in one lane yields the other
+// operand. A lane where both operands are NaN yields NaN and sets the
+// invalid operation flag.
+pub struct VFMax;
+
+// Vector Float Minimum instruction
+//
+// NaN handling matches VFMax.
+pub struct VFMin;
+
 // Vector Shuffle instruction
 pub struct VShuffle;
 
 // Vector Interleave High instruction
+//
+// Byte lanes are numbered from the most significant byte, as the 88000
+// addresses them in memory. The high halves of rs1 (a) and rs2 (b) are
+// interleaved, a lane first:
+//
+//   rs1 = a0 a1 a2 a3, rs2 = b0 b1 b2 b3  =>  rd = a0 b0 a1 b1
+//
+// With rs1 = 0 this zero-extends the high bytes of rs2 to halfwords, the
+// 88110 graphics unpack of the first two pixels.
 pub struct VInterleaveHigh;
 
 // Vector Interleave Low instruction
+//
+// The low-half counterpart of VInterleaveHigh:
+//
+//   rs1 = a0 a1 a2 a3, rs2 = b0 b1 b2 b3  =>  rd = a2 b2 a3 b3
 pub struct VInterleaveLow;
 
 // Vector Extract Byte instruction
@@ -59,18 +85,24 @@ pub struct VUnpackBytesToHalfwords;
 // Vector Unpack Halfwords to Word instruction
 pub struct VUnpackHalfwordsToWord;
 
+// Vector Add Signed Bytes (saturating) instruction
+pub struct VAddSB;
+
+// Vector Subtract Signed Bytes (saturating) instruction
+pub struct VSubSB;
+
 const VECTOR_SIZE: usize = 4;
 
 impl Instruction for VAdd {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
         for i in 0..VECTOR_SIZE {
-            let base_s1 = cpu.s1 + i;
-            let base_s2 = cpu.s2 + i;
-            let base_d = cpu.d + i;
+            let base_s1 = ops.s1 + i;
+            let base_s2 = ops.s2 + i;
+            let base_d = ops.d + i;
 
             let a = f32::from_bits(cpu.registers[base_s1]);
             let b = f32::from_bits(cpu.registers[base_s2]);
-            let result = a + b;
+            let result = arith::add(a, b);
 
             if result.is_nan() {
                 cpu.cr0 |= CPU::CR0_FP_INVALID;
@@ -82,15 +114,15 @@ impl Instruction for VAdd {
 }
 
 impl Instruction for VSub {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
         for i in 0..VECTOR_SIZE {
-            let base_s1 = cpu.s1 + i;
-            let base_s2 = cpu.s2 + i;
-            let base_d = cpu.d + i;
+            let base_s1 = ops.s1 + i;
+            let base_s2 = ops.s2 + i;
+            let base_d = ops.d + i;
 
             let a = f32::from_bits(cpu.registers[base_s1]);
             let b = f32::from_bits(cpu.registers[base_s2]);
-            let result = a - b;
+            let result = arith::sub(a, b);
 
             if result.is_nan() {
                 cpu.cr0 |= CPU::CR0_FP_INVALID;
@@ -102,15 +134,15 @@ impl Instruction for VSub {
 }
 
 impl Instruction for VMul {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
         for i in 0..VECTOR_SIZE {
-            let base_s1 = cpu.s1 + i;
-            let base_s2 = cpu.s2 + i;
-            let base_d = cpu.d + i;
+            let base_s1 = ops.s1 + i;
+            let base_s2 = ops.s2 + i;
+            let base_d = ops.d + i;
 
             let a = f32::from_bits(cpu.registers[base_s1]);
             let b = f32::from_bits(cpu.registers[base_s2]);
-            let result = a * b;
+            let result = arith::mul(a, b);
 
             if result.is_nan() {
                 cpu.cr0 |= CPU::CR0_FP_INVALID;
@@ -122,11 +154,11 @@ impl Instruction for VMul {
 }
 
 impl Instruction for VDiv {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
         for i in 0..VECTOR_SIZE {
-            let base_s1 = cpu.s1 + i;
-            let base_s2 = cpu.s2 + i;
-            let base_d = cpu.d + i;
+            let base_s1 = ops.s1 + i;
+            let base_s2 = ops.s2 + i;
+            let base_d = ops.d + i;
 
             let a = f32::from_bits(cpu.registers[base_s1]);
             let b = f32::from_bits(cpu.registers[base_s2]);
@@ -137,7 +169,7 @@ impl Instruction for VDiv {
                 continue;
             }
 
-            let result = a / b;
+            let result = arith::div(a, b);
             if result.is_nan() {
                 cpu.cr0 |= CPU::CR0_FP_INVALID;
             }
@@ -148,19 +180,19 @@ impl Instruction for VDiv {
 }
 
 impl Instruction for VMove {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
         for i in 0..VECTOR_SIZE {
-            let base_s1 = cpu.s1 + i;
-            let base_d = cpu.d + i;
+            let base_s1 = ops.s1 + i;
+            let base_d = ops.d + i;
             cpu.registers[base_d] = cpu.registers[base_s1];
         }
     }
 }
 
 impl Instruction for VEq {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = cpu.registers[cpu.s1];
-        let b = cpu.registers[cpu.s2];
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1];
+        let b = cpu.registers[ops.s2];
 
         // Compare each byte and set result bits
         let mut result = 0u32;
@@ -172,14 +204,14 @@ impl Instruction for VEq {
                 result |= 0xFF << shift;
             }
         }
-        cpu.registers[cpu.d] = result;
+        cpu.registers[ops.d] = result;
     }
 }
 
 impl Instruction for VGt {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = cpu.registers[cpu.s1];
-        let b = cpu.registers[cpu.s2];
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1];
+        let b = cpu.registers[ops.s2];
 
         // Compare each byte and set result bits
         let mut result = 0u32;
@@ -191,14 +223,14 @@ impl Instruction for VGt {
                 result |= 0xFF << shift;
             }
         }
-        cpu.registers[cpu.d] = result;
+        cpu.registers[ops.d] = result;
     }
 }
 
 impl Instruction for VLt {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = cpu.registers[cpu.s1];
-        let b = cpu.registers[cpu.s2];
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1];
+        let b = cpu.registers[ops.s2];
 
         // Compare each byte and set result bits
         let mut result = 0u32;
@@ -210,14 +242,14 @@ impl Instruction for VLt {
                 result |= 0xFF << shift;
             }
         }
-        cpu.registers[cpu.d] = result;
+        cpu.registers[ops.d] = result;
     }
 }
 
 impl Instruction for VMax {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = cpu.registers[cpu.s1];
-        let b = cpu.registers[cpu.s2];
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1];
+        let b = cpu.registers[ops.s2];
 
         // Find maximum of each byte
         let mut result = 0u32;
@@ -227,14 +259,14 @@ impl Instruction for VMax {
             let max = if byte_a > byte_b { byte_a } else { byte_b };
             result |= max << (24 - i * 8);
         }
-        cpu.registers[cpu.d] = result;
+        cpu.registers[ops.d] = result;
     }
 }
 
 impl Instruction for VMin {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = cpu.registers[cpu.s1];
-        let b = cpu.registers[cpu.s2];
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1];
+        let b = cpu.registers[ops.s2];
 
         // Find minimum of each byte
         let mut result = 0u32;
@@ -244,14 +276,38 @@ impl Instruction for VMin {
             let min = if byte_a < byte_b { byte_a } else { byte_b };
             result |= min << (24 - i * 8);
         }
-        cpu.registers[cpu.d] = result;
+        cpu.registers[ops.d] = result;
+    }
+}
+
+/// Combines the four f32 lanes of s1 and s2 into d, flagging lanes where both are NaN
+fn float_lanes(cpu: &mut CPU, ops: &Operands, op: fn(f32, f32) -> f32) {
+    for i in 0..VECTOR_SIZE {
+        let a = f32::from_bits(cpu.registers[ops.s1 + i]);
+        let b = f32::from_bits(cpu.registers[ops.s2 + i]);
+        if a.is_nan() && b.is_nan() {
+            cpu.cr0 |= CPU::CR0_FP_INVALID;
+        }
+        cpu.registers[ops.d + i] = op(a, b).to_bits();
+    }
+}
+
+impl Instruction for VFMax {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        float_lanes(cpu, ops, f32::max);
+    }
+}
+
+impl Instruction for VFMin {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        float_lanes(cpu, ops, f32::min);
     }
 }
 
 impl Instruction for VShuffle {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = cpu.registers[cpu.s1];
-        let pattern = cpu.registers[cpu.s2];
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1];
+        let pattern = cpu.registers[ops.s2];
 
         let mut result = 0u32;
         for i in 0..4 {
@@ -261,65 +317,53 @@ impl Instruction for VShuffle {
             let byte = (a >> (byte_select * 8)) & 0xFF;
             result |= byte << shift;
         }
-        cpu.registers[cpu.d] = result;
+        cpu.registers[ops.d] = result;
     }
 }
 
 impl Instruction for VInterleaveHigh {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = cpu.registers[cpu.s1];
-        let b = cpu.registers[cpu.s2];
-
-        // Interleave high bytes: a[0],b[0],a[1],b[1]
-        let result = (a & 0xFF000000)
-            | ((b & 0xFF000000) >> 8)
-            | ((a & 0x00FF0000) >> 8)
-            | ((b & 0x00FF0000) >> 16);
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1].to_be_bytes();
+        let b = cpu.registers[ops.s2].to_be_bytes();
 
-        cpu.registers[cpu.d] = result;
+        cpu.registers[ops.d] = u32::from_be_bytes([a[0], b[0], a[1], b[1]]);
     }
 }
 
 impl Instruction for VInterleaveLow {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = cpu.registers[cpu.s1];
-        let b = cpu.registers[cpu.s2];
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1].to_be_bytes();
+        let b = cpu.registers[ops.s2].to_be_bytes();
 
-        // Interleave low bytes: a[2],b[2],a[3],b[3]
-        let result = ((a & 0x0000FF00) << 16)
-            | ((b & 0x0000FF00) << 8)
-            | ((a & 0x000000FF) << 8)
-            | (b & 0x000000FF);
-
-        cpu.registers[cpu.d] = result;
+        cpu.registers[ops.d] = u32::from_be_bytes([a[2], b[2], a[3], b[3]]);
     }
 }
 
 impl Instruction for VExtractByte {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = cpu.registers[cpu.s1];
-        let pos = cpu.registers[cpu.s2] & 0x3; // Only use bottom 2 bits for position
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1];
+        let pos = cpu.registers[ops.s2] & 0x3; // Only use bottom 2 bits for position
         let byte = (a >> ((3 - pos) * 8)) & 0xFF;
-        cpu.registers[cpu.d] = byte;
+        cpu.registers[ops.d] = byte;
     }
 }
 
 impl Instruction for VInsertByte {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = cpu.registers[cpu.s1]; // Vector
-        let b = cpu.registers[cpu.s2]; // Byte to insert
-        let pos = cpu.imm as u32 & 0x3; // Position from immediate value
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1]; // Vector
+        let b = cpu.registers[ops.s2]; // Byte to insert
+        let pos = ops.imm as u32 & 0x3; // Position from immediate value
         let shift = (3 - pos) * 8;
         let mask = !(0xFF << shift);
         let result = (a & mask) | ((b & 0xFF) << shift);
-        cpu.registers[cpu.d] = result;
+        cpu.registers[ops.d] = result;
     }
 }
 
 impl Instruction for VPackBytesToHalfwords {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = cpu.registers[cpu.s1];
-        let b = cpu.registers[cpu.s2];
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1];
+        let b = cpu.registers[ops.s2];
 
         // Pack bytes into halfwords with saturation
         let mut result = 0u32;
@@ -329,47 +373,70 @@ impl Instruction for VPackBytesToHalfwords {
             let halfword = (byte_a << 8) | byte_b;
             result |= (halfword as u32) << ((1 - i) * 16);
         }
-        cpu.registers[cpu.d] = result;
+        cpu.registers[ops.d] = result;
     }
 }
 
 impl Instruction for VPackHalfwordsToWord {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = cpu.registers[cpu.s1];
-        let b = cpu.registers[cpu.s2];
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1];
+        let b = cpu.registers[ops.s2];
 
         // Pack halfwords into word with saturation
         let high = a & 0xFFFF;
         let low = b & 0xFFFF;
         let result = (high << 16) | low;
-        cpu.registers[cpu.d] = result;
+        cpu.registers[ops.d] = result;
     }
 }
 
 impl Instruction for VUnpackBytesToHalfwords {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = cpu.registers[cpu.s1];
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1];
 
-        // Unpack bytes to halfwords
-        let mut result = 0u32;
-        for i in 0..2 {
-            let byte = (a >> ((3 - i) * 8)) & 0xFF;
-            let halfword = byte << ((1 - i) * 16);
-            result |= halfword;
-        }
-        cpu.registers[cpu.d] = result;
+        // Unpack the four bytes to halfwords: the high two bytes go to d,
+        // the low two bytes to d+1
+        let [b0, b1, b2, b3] = a.to_be_bytes().map(u64::from);
+        cpu.write_register_pair(ops.d, (b0 << 48) | (b1 << 32) | (b2 << 16) | b3);
     }
 }
 
 impl Instruction for VUnpackHalfwordsToWord {
-    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory) {
-        let a = cpu.registers[cpu.s1];
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1];
 
         // Unpack halfwords to word
-        let high = (a >> 16) & 0xFFFF;
-        let low = a & 0xFFFF;
-        cpu.registers[cpu.d] = high;
-        cpu.registers[cpu.d + 1] = low;
+        let high = (a >> 16) as u64;
+        let low = (a & 0xFFFF) as u64;
+        cpu.write_register_pair(ops.d, (high << 32) | low);
+    }
+}
+
+impl Instruction for VAddSB {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1].to_be_bytes();
+        let b = cpu.registers[ops.s2].to_be_bytes();
+
+        // Add each signed byte, saturating to -128..127
+        let mut result = [0u8; 4];
+        for i in 0..4 {
+            result[i] = (a[i] as i8).saturating_add(b[i] as i8) as u8;
+        }
+        cpu.registers[ops.d] = u32::from_be_bytes(result);
+    }
+}
+
+impl Instruction for VSubSB {
+    fn execute(&self, cpu: &mut CPU, _memory: &mut Memory, ops: &Operands) {
+        let a = cpu.registers[ops.s1].to_be_bytes();
+        let b = cpu.registers[ops.s2].to_be_bytes();
+
+        // Subtract each signed byte, saturating to -128..127
+        let mut result = [0u8; 4];
+        for i in 0..4 {
+            result[i] = (a[i] as i8).saturating_sub(b[i] as i8) as u8;
+        }
+        cpu.registers[ops.d] = u32::from_be_bytes(result);
     }
 }
 
@@ -380,11 +447,12 @@ mod tests {
     #[test]
     fn test_vadd() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
-        cpu.s1 = 1;
-        cpu.s2 = 5;
-        cpu.d = 9;
+        ops.s1 = 1;
+        ops.s2 = 5;
+        ops.d = 9;
 
         // Initialize source vectors
         for i in 0..VECTOR_SIZE {
@@ -392,7 +460,7 @@ mod tests {
             cpu.registers[5 + i] = ((i + 1) as f32).to_bits();
         }
 
-        VAdd.execute(&mut cpu, &mut memory);
+        VAdd.execute(&mut cpu, &mut memory, &ops);
 
         // Check results
         for i in 0..VECTOR_SIZE {
@@ -404,11 +472,12 @@ mod tests {
     #[test]
     fn test_vsub() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
-        cpu.s1 = 1;
-        cpu.s2 = 5;
-        cpu.d = 9;
+        ops.s1 = 1;
+        ops.s2 = 5;
+        ops.d = 9;
 
         // Initialize source vectors
         for i in 0..VECTOR_SIZE {
@@ -416,7 +485,7 @@ mod tests {
             cpu.registers[5 + i] = ((i + 1) as f32).to_bits();
         }
 
-        VSub.execute(&mut cpu, &mut memory);
+        VSub.execute(&mut cpu, &mut memory, &ops);
 
         // Check results
         for i in 0..VECTOR_SIZE {
@@ -428,11 +497,12 @@ mod tests {
     #[test]
     fn test_vmul() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
-        cpu.s1 = 1;
-        cpu.s2 = 5;
-        cpu.d = 9;
+        ops.s1 = 1;
+        ops.s2 = 5;
+        ops.d = 9;
 
         // Initialize source vectors
         for i in 0..VECTOR_SIZE {
@@ -440,7 +510,7 @@ mod tests {
             cpu.registers[5 + i] = ((i + 1) as f32).to_bits();
         }
 
-        VMul.execute(&mut cpu, &mut memory);
+        VMul.execute(&mut cpu, &mut memory, &ops);
 
         // Check results
         for i in 0..VECTOR_SIZE {
@@ -452,11 +522,12 @@ mod tests {
     #[test]
     fn test_vdiv() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
-        cpu.s1 = 1;
-        cpu.s2 = 5;
-        cpu.d = 9;
+        ops.s1 = 1;
+        ops.s2 = 5;
+        ops.d = 9;
 
         // Initialize source vectors
         for i in 0..VECTOR_SIZE {
@@ -464,7 +535,7 @@ mod tests {
             cpu.registers[5 + i] = (2.0f32).to_bits();
         }
 
-        VDiv.execute(&mut cpu, &mut memory);
+        VDiv.execute(&mut cpu, &mut memory, &ops);
 
         // Check results
         for i in 0..VECTOR_SIZE {
@@ -474,7 +545,7 @@ mod tests {
 
         // Test division by zero
         cpu.registers[5] = (0.0f32).to_bits();
-        VDiv.execute(&mut cpu, &mut memory);
+        VDiv.execute(&mut cpu, &mut memory, &ops);
         assert_ne!(cpu.cr0 & CPU::CR0_FP_DIVZERO, 0);
         assert!(f32::from_bits(cpu.registers[9]).is_nan());
     }
@@ -482,17 +553,18 @@ mod tests {
     #[test]
     fn test_vmove() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
-        cpu.s1 = 1;
-        cpu.d = 5;
+        ops.s1 = 1;
+        ops.d = 5;
 
         // Initialize source vector
         for i in 0..VECTOR_SIZE {
             cpu.registers[1 + i] = (i as f32).to_bits();
         }
 
-        VMove.execute(&mut cpu, &mut memory);
+        VMove.execute(&mut cpu, &mut memory, &ops);
 
         // Check results
         for i in 0..VECTOR_SIZE {
@@ -503,6 +575,7 @@ mod tests {
     #[test]
     fn test_veq() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test with 0x12345678 vs 0x12FF5678
@@ -510,17 +583,18 @@ mod tests {
         // Match:   yes   no    yes   yes
         cpu.registers[1] = 0x12345678;
         cpu.registers[2] = 0x12FF5678;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        VEq.execute(&mut cpu, &mut memory);
+        VEq.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0xFF00FFFF);
     }
 
     #[test]
     fn test_vgt() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test with 0x12345678 vs 0x11335577
@@ -528,17 +602,18 @@ mod tests {
         // GT:      yes   yes   yes   yes
         cpu.registers[1] = 0x12345678;
         cpu.registers[2] = 0x11335577;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        VGt.execute(&mut cpu, &mut memory);
+        VGt.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0xFFFFFFFF);
     }
 
     #[test]
     fn test_vlt() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test with 0x11335577 vs 0x12345678
@@ -546,185 +621,335 @@ mod tests {
         // LT:      yes   yes   yes   yes
         cpu.registers[1] = 0x11335577;
         cpu.registers[2] = 0x12345678;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        VLt.execute(&mut cpu, &mut memory);
+        VLt.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0xFFFFFFFF);
     }
 
     #[test]
     fn test_vmax() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 0x12345678;
         cpu.registers[2] = 0x11335577;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        VMax.execute(&mut cpu, &mut memory);
+        VMax.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0x12345678);
     }
 
     #[test]
     fn test_vmin() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 0x12345678;
         cpu.registers[2] = 0x11335577;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        VMin.execute(&mut cpu, &mut memory);
+        VMin.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0x11335577);
     }
 
+    #[test]
+    fn test_vfmax_vfmin() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        ops.s1 = 1;
+        ops.s2 = 5;
+        ops.d = 9;
+
+        let a = [1.0f32, -2.0, 3.0, -0.5];
+        let b = [2.0f32, -3.0, 1.0, 4.0];
+        for i in 0..VECTOR_SIZE {
+            cpu.registers[1 + i] = a[i].to_bits();
+            cpu.registers[5 + i] = b[i].to_bits();
+        }
+
+        VFMax.execute(&mut cpu, &mut memory, &ops);
+        for (i, expected) in [2.0f32, -2.0, 3.0, 4.0].into_iter().enumerate() {
+            assert_eq!(f32::from_bits(cpu.registers[9 + i]), expected);
+        }
+
+        VFMin.execute(&mut cpu, &mut memory, &ops);
+        for (i, expected) in [1.0f32, -3.0, 1.0, -0.5].into_iter().enumerate() {
+            assert_eq!(f32::from_bits(cpu.registers[9 + i]), expected);
+        }
+        assert_eq!(cpu.cr0 & CPU::CR0_FP_INVALID, 0);
+    }
+
+    #[test]
+    fn test_vfmax_vfmin_nan() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        ops.s1 = 1;
+        ops.s2 = 5;
+        ops.d = 9;
+
+        // NaN in one operand of lanes 0 and 1 yields the other operand
+        cpu.registers[1] = f32::NAN.to_bits();
+        cpu.registers[5] = 7.0f32.to_bits();
+        cpu.registers[2] = (-1.0f32).to_bits();
+        cpu.registers[6] = f32::NAN.to_bits();
+        cpu.registers[3] = 1.0f32.to_bits();
+        cpu.registers[7] = 2.0f32.to_bits();
+        cpu.registers[4] = 3.0f32.to_bits();
+        cpu.registers[8] = 4.0f32.to_bits();
+
+        for instruction in [&VFMax as &dyn Instruction, &VFMin] {
+            instruction.execute(&mut cpu, &mut memory, &ops);
+            assert_eq!(f32::from_bits(cpu.registers[9]), 7.0);
+            assert_eq!(f32::from_bits(cpu.registers[10]), -1.0);
+            assert_eq!(cpu.cr0 & CPU::CR0_FP_INVALID, 0);
+        }
+
+        // NaN in both operands yields NaN and flags an invalid operation
+        cpu.registers[7] = f32::NAN.to_bits();
+        cpu.registers[3] = f32::NAN.to_bits();
+        VFMin.execute(&mut cpu, &mut memory, &ops);
+        assert!(f32::from_bits(cpu.registers[11]).is_nan());
+        assert_eq!(f32::from_bits(cpu.registers[12]), 3.0);
+        assert_ne!(cpu.cr0 & CPU::CR0_FP_INVALID, 0);
+
+        cpu.cr0 = 0;
+        VFMax.execute(&mut cpu, &mut memory, &ops);
+        assert!(f32::from_bits(cpu.registers[11]).is_nan());
+        assert_eq!(f32::from_bits(cpu.registers[12]), 4.0);
+        assert_ne!(cpu.cr0 & CPU::CR0_FP_INVALID, 0);
+    }
+
     #[test]
     fn test_vshuffle() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         // Test data: 0x12345678
         // Pattern: 0b00011011 (3,2,1,0)
         cpu.registers[1] = 0x12345678;
         cpu.registers[2] = 0b00011011;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        VShuffle.execute(&mut cpu, &mut memory);
+        VShuffle.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0x78563412);
     }
 
     #[test]
     fn test_vinterleave_high() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 0x12345678;
         cpu.registers[2] = 0xABCDEFFF;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        VInterleaveHigh.execute(&mut cpu, &mut memory);
+        VInterleaveHigh.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0x12AB34CD);
     }
 
     #[test]
     fn test_vinterleave_low() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 0x12345678;
         cpu.registers[2] = 0xABCDEFFF;
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        VInterleaveLow.execute(&mut cpu, &mut memory);
+        VInterleaveLow.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0x56EF78FF);
     }
 
+    #[test]
+    fn test_vinterleave_lane_mapping() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        // Each byte names its register and lane
+        cpu.registers[1] = 0xA0A1_A2A3;
+        cpu.registers[2] = 0xB0B1_B2B3;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
+
+        VInterleaveHigh.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[3], 0xA0B0_A1B1);
+        VInterleaveLow.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[3], 0xA2B2_A3B3);
+
+        // Interleaving with zero unpacks bytes to halfwords
+        cpu.registers[1] = 0;
+        ops.s2 = 2;
+        VInterleaveHigh.execute(&mut cpu, &mut memory, &ops);
+        let high = cpu.registers[3];
+        VInterleaveLow.execute(&mut cpu, &mut memory, &ops);
+        let low = cpu.registers[3];
+
+        ops.s1 = 2;
+        ops.d = 4;
+        VUnpackBytesToHalfwords.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!((high, low), (0x00B0_00B1, 0x00B2_00B3));
+        assert_eq!((cpu.registers[4], cpu.registers[5]), (high, low));
+    }
+
     #[test]
     fn test_vextract_byte() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 0x12345678;
         cpu.registers[2] = 0; // Extract first byte
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        VExtractByte.execute(&mut cpu, &mut memory);
+        VExtractByte.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0x12);
 
         cpu.registers[2] = 3; // Extract last byte
-        VExtractByte.execute(&mut cpu, &mut memory);
+        VExtractByte.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0x78);
     }
 
     #[test]
     fn test_vinsert_byte() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 0x12345678; // Original vector
         cpu.registers[2] = 0xFF; // Byte to insert
-        cpu.imm = 0; // Insert at first position
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.imm = 0; // Insert at first position
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        VInsertByte.execute(&mut cpu, &mut memory);
+        VInsertByte.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0xFF345678);
 
         cpu.registers[1] = 0x12345678;
-        cpu.imm = 3; // Insert at last position
-        VInsertByte.execute(&mut cpu, &mut memory);
+        ops.imm = 3; // Insert at last position
+        VInsertByte.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0x123456FF);
     }
 
     #[test]
     fn test_vpack_bytes_to_halfwords() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 0x12345678; // First vector
         cpu.registers[2] = 0xABCDEFFF; // Second vector
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        VPackBytesToHalfwords.execute(&mut cpu, &mut memory);
+        VPackBytesToHalfwords.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0x12AB34CD);
     }
 
     #[test]
     fn test_vpack_halfwords_to_word() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 0x00001234; // First halfword
         cpu.registers[2] = 0x00005678; // Second halfword
-        cpu.d = 3;
-        cpu.s1 = 1;
-        cpu.s2 = 2;
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
 
-        VPackHalfwordsToWord.execute(&mut cpu, &mut memory);
+        VPackHalfwordsToWord.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[3], 0x12345678);
     }
 
     #[test]
     fn test_vunpack_bytes_to_halfwords() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 0x12345678;
-        cpu.d = 2;
-        cpu.s1 = 1;
+        ops.d = 2;
+        ops.s1 = 1;
 
-        VUnpackBytesToHalfwords.execute(&mut cpu, &mut memory);
+        VUnpackBytesToHalfwords.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2], 0x00120034);
+        assert_eq!(cpu.registers[3], 0x00560078);
     }
 
     #[test]
     fn test_vunpack_halfwords_to_word() {
         let mut cpu = CPU::new();
+        let mut ops = Operands::default();
         let mut memory = Memory::new();
 
         cpu.registers[1] = 0x12345678;
-        cpu.d = 2;
-        cpu.s1 = 1;
+        ops.d = 2;
+        ops.s1 = 1;
 
-        VUnpackHalfwordsToWord.execute(&mut cpu, &mut memory);
+        VUnpackHalfwordsToWord.execute(&mut cpu, &mut memory, &ops);
         assert_eq!(cpu.registers[2], 0x00001234);
         assert_eq!(cpu.registers[3], 0x00005678);
     }
+
+    #[test]
+    fn test_vaddsb_saturates() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        // Lanes: 100 + 50, -100 + -50, 1 + 2, -1 + 1
+        cpu.registers[1] = u32::from_be_bytes([100, (-100i8) as u8, 1, 0xFF]);
+        cpu.registers[2] = u32::from_be_bytes([50, (-50i8) as u8, 2, 1]);
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
+
+        VAddSB.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[3], u32::from_be_bytes([127, 0x80, 3, 0]));
+    }
+
+    #[test]
+    fn test_vsubsb_saturates() {
+        let mut cpu = CPU::new();
+        let mut ops = Operands::default();
+        let mut memory = Memory::new();
+
+        // Lanes: 100 - -50, -100 - 50, 5 - 7, 0 - 0
+        cpu.registers[1] = u32::from_be_bytes([100, (-100i8) as u8, 5, 0]);
+        cpu.registers[2] = u32::from_be_bytes([(-50i8) as u8, 50, 7, 0]);
+        ops.d = 3;
+        ops.s1 = 1;
+        ops.s2 = 2;
+
+        VSubSB.execute(&mut cpu, &mut memory, &ops);
+        assert_eq!(cpu.registers[3], u32::from_be_bytes([127, 0x80, 0xFE, 0]));
+    }
 }
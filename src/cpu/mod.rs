@@ -1,11 +1,484 @@
 //! CPU module for the Motorola 88000 emulator.
 //!
 //! This module implements the core CPU functionality including register management,
-//! control flags, and MMU support.
+//! control flags, MMU support, exception vectoring, and the fetch/decode/execute loop.
 
+pub mod decoder;
 pub mod instructions;
 
+use crate::memory::{ByteWrite, Memory};
 use instructions::system::PrivilegeLevel;
+use instructions::{ExecError, Instruction};
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+
+/// Information about an instruction that is about to execute.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceEvent {
+    /// Address of the instruction
+    pub pc: u32,
+    /// Raw instruction word
+    pub word: u32,
+    /// Decoded assembler mnemonic
+    pub mnemonic: &'static str,
+}
+
+/// A register changed by a traced step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegisterDelta {
+    /// Register number
+    pub reg: usize,
+    /// Value before the step
+    pub old: u32,
+    /// Value after the step
+    pub new: u32,
+}
+
+/// Record of a single step, as collected by [`CPU::trace_steps`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepRecord {
+    /// Address of the instruction
+    pub pc: u32,
+    /// Decoded assembler mnemonic, or `None` if the word does not decode
+    pub mnemonic: Option<&'static str>,
+    /// General purpose registers changed by the step, in register order
+    pub deltas: Vec<RegisterDelta>,
+}
+
+/// Computes the registers that differ between two register files, in register order.
+fn register_deltas(before: &[u32; 32], after: &[u32; 32]) -> Vec<RegisterDelta> {
+    before
+        .iter()
+        .zip(after.iter())
+        .enumerate()
+        .filter(|(_, (old, new))| old != new)
+        .map(|(reg, (&old, &new))| RegisterDelta { reg, old, new })
+        .collect()
+}
+
+/// Processor state other than the register files, saved before a journaled step.
+#[derive(Debug, Clone, Copy)]
+struct ControlState {
+    pc: u32,
+    cr0: u32,
+    sxip: u32,
+    snip: u32,
+    sfip: u32,
+    shadow_privilege_level: PrivilegeLevel,
+    nip: u32,
+    fip: u32,
+    vector: u8,
+    trap_vector: u8,
+    fault_addr: u32,
+    halted: bool,
+    instret: u64,
+    ptbr: u32,
+    mmu_control: u32,
+    vbr: u32,
+    interrupt_pending: u32,
+    interrupt_mask: u32,
+    interrupt_line: u8,
+    privilege_level: PrivilegeLevel,
+    branch_taken: bool,
+    delayed_branch: Option<u32>,
+    exception_taken: Option<u8>,
+    data_fault: Option<ExecError>,
+    mmu_synced: bool,
+}
+
+impl ControlState {
+    fn capture(cpu: &CPU) -> Self {
+        ControlState {
+            pc: cpu.pc,
+            cr0: cpu.cr0,
+            sxip: cpu.sxip,
+            snip: cpu.snip,
+            sfip: cpu.sfip,
+            shadow_privilege_level: cpu.shadow_privilege_level,
+            nip: cpu.nip,
+            fip: cpu.fip,
+            vector: cpu.vector,
+            trap_vector: cpu.trap_vector,
+            fault_addr: cpu.fault_addr,
+            halted: cpu.halted,
+            instret: cpu.instret,
+            ptbr: cpu.ptbr,
+            mmu_control: cpu.mmu_control,
+            vbr: cpu.vbr,
+            interrupt_pending: cpu.interrupt_pending,
+            interrupt_mask: cpu.interrupt_mask,
+            interrupt_line: cpu.interrupt_line,
+            privilege_level: cpu.privilege_level,
+            branch_taken: cpu.branch_taken,
+            delayed_branch: cpu.delayed_branch,
+            exception_taken: cpu.exception_taken,
+            data_fault: cpu.data_fault,
+            mmu_synced: cpu.mmu_synced,
+        }
+    }
+
+    fn restore(self, cpu: &mut CPU) {
+        cpu.pc = self.pc;
+        cpu.cr0 = self.cr0;
+        cpu.sxip = self.sxip;
+        cpu.snip = self.snip;
+        cpu.sfip = self.sfip;
+        cpu.shadow_privilege_level = self.shadow_privilege_level;
+        cpu.nip = self.nip;
+        cpu.fip = self.fip;
+        cpu.vector = self.vector;
+        cpu.trap_vector = self.trap_vector;
+        cpu.fault_addr = self.fault_addr;
+        cpu.halted = self.halted;
+        cpu.instret = self.instret;
+        cpu.ptbr = self.ptbr;
+        cpu.mmu_control = self.mmu_control;
+        cpu.vbr = self.vbr;
+        cpu.interrupt_pending = self.interrupt_pending;
+        cpu.interrupt_mask = self.interrupt_mask;
+        cpu.interrupt_line = self.interrupt_line;
+        cpu.privilege_level = self.privilege_level;
+        cpu.branch_taken = self.branch_taken;
+        cpu.delayed_branch = self.delayed_branch;
+        cpu.exception_taken = self.exception_taken;
+        cpu.data_fault = self.data_fault;
+        cpu.mmu_synced = self.mmu_synced;
+    }
+}
+
+/// Everything a journaled step changed, as reverted by [`CPU::step_back`].
+#[derive(Debug)]
+struct UndoRecord {
+    /// General purpose registers changed by the step
+    registers: Vec<RegisterDelta>,
+    /// Extended registers changed by the step
+    x_registers: Vec<RegisterDelta>,
+    /// Control state before the step
+    control: ControlState,
+    /// Memory translation state before the step
+    mmu_enabled: bool,
+    /// Memory page table base before the step
+    page_table_base: u32,
+    /// Memory bytes overwritten by the step
+    memory: Vec<ByteWrite>,
+}
+
+/// Reason the run loop stopped executing instructions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// The program counter reached a breakpoint (the instruction has not executed)
+    Breakpoint(u32),
+    /// An instruction wrote to a watched memory range
+    Watchpoint {
+        /// First address of the watched range
+        addr: u32,
+        /// Value of the range before the write
+        old: u64,
+        /// Value of the range after the write
+        new: u64,
+    },
+    /// The requested number of instructions was executed
+    StepLimit,
+    /// The processor executed a halt instruction
+    Halted,
+}
+
+/// Result of executing a single step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepOutcome {
+    /// The instruction completed and execution continues sequentially
+    Retired,
+    /// The instruction (or a completed delay slot) transferred control to the address
+    BranchTaken(u32),
+    /// An exception was raised and control transferred to its handler
+    Exception {
+        /// The exception vector number
+        vector: u8,
+        /// The faulting address, where one applies
+        address: Option<u32>,
+    },
+    /// The processor is halted
+    Halted,
+}
+
+/// How the step loop handles an instruction word that does not decode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IllegalAction {
+    /// Treat the word as a no-op and continue with the next instruction
+    Skip,
+    /// Raise the unimplemented opcode exception
+    Trap,
+    /// Halt the processor at the instruction
+    Halt,
+}
+
+/// Errors that prevent the emulator from executing a step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmulationError {
+    /// The program counter is not word aligned
+    MisalignedPc(u32),
+}
+
+/// Errors returned by the CPU state accessors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuError {
+    /// The register index is not in the range r0-r31
+    InvalidRegister(usize),
+}
+
+/// Exception conditions recorded in cr0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExceptionKind {
+    /// Bounds check violation
+    BoundsCheck,
+    /// Software trap
+    Trap,
+    /// Data access page fault
+    PageFault,
+    /// Write protection violation
+    WriteProtect,
+    /// Privilege violation
+    PrivilegeViolation,
+    /// Integer division by zero
+    DivideZero,
+    /// Signed integer overflow
+    Overflow,
+    /// Instruction fetch fault
+    InstructionFault,
+}
+
+impl ExceptionKind {
+    /// All exception kinds, in cr0 bit order.
+    pub const ALL: [ExceptionKind; 8] = [
+        ExceptionKind::BoundsCheck,
+        ExceptionKind::Trap,
+        ExceptionKind::PageFault,
+        ExceptionKind::WriteProtect,
+        ExceptionKind::PrivilegeViolation,
+        ExceptionKind::DivideZero,
+        ExceptionKind::Overflow,
+        ExceptionKind::InstructionFault,
+    ];
+
+    /// Returns the cr0 flag recording this exception.
+    pub fn flag(self) -> u32 {
+        match self {
+            ExceptionKind::BoundsCheck => CPU::CR0_BOUNDS_CHECK,
+            ExceptionKind::Trap => CPU::CR0_TRAP,
+            ExceptionKind::PageFault => CPU::CR0_PAGE_FAULT,
+            ExceptionKind::WriteProtect => CPU::CR0_WRITE_PROTECT,
+            ExceptionKind::PrivilegeViolation => CPU::CR0_PRIVILEGE_VIOLATION,
+            ExceptionKind::DivideZero => CPU::CR0_DIVIDE_ZERO,
+            ExceptionKind::Overflow => CPU::CR0_OVERFLOW,
+            ExceptionKind::InstructionFault => CPU::CR0_INSTR_FAULT,
+        }
+    }
+}
+
+/// Result of the most recent floating point comparison, as recorded in cr0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FpCompare {
+    /// The operands compared equal
+    Equal,
+    /// The first operand was less than the second
+    Less,
+    /// The first operand was greater than the second
+    Greater,
+    /// At least one operand was a NaN
+    Unordered,
+}
+
+impl FpCompare {
+    /// Returns the cr0 flag recording this result.
+    pub fn flag(self) -> u32 {
+        match self {
+            FpCompare::Equal => CPU::CR0_FP_EQUAL,
+            FpCompare::Less => CPU::CR0_FP_LESS,
+            FpCompare::Greater => CPU::CR0_FP_GREATER,
+            FpCompare::Unordered => CPU::CR0_FP_UNORDERED,
+        }
+    }
+
+    /// Reads the result recorded in a cr0 value.
+    ///
+    /// If several result flags are set, unordered takes precedence, then
+    /// equal, less and greater.
+    pub fn from_cr0(cr0: u32) -> Option<Self> {
+        [
+            FpCompare::Unordered,
+            FpCompare::Equal,
+            FpCompare::Less,
+            FpCompare::Greater,
+        ]
+        .into_iter()
+        .find(|result| cr0 & result.flag() != 0)
+    }
+}
+
+/// Typed view of the processor status register (cr0).
+///
+/// [`CPU::psr`] unpacks cr0 into this struct and [`CPU::set_psr`] packs it
+/// back, so flags can be manipulated without masking bits by hand.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProcessorStatus {
+    /// Integer compare: equal
+    pub equal: bool,
+    /// Integer compare: less than
+    pub less: bool,
+    /// Integer compare: greater than
+    pub greater: bool,
+    /// Integer compare: unordered
+    pub unordered: bool,
+    /// Floating point divide by zero
+    pub fp_divide_zero: bool,
+    /// Floating point inexact result
+    pub fp_inexact: bool,
+    /// Floating point invalid operation
+    pub fp_invalid: bool,
+    /// Floating point overflow
+    pub fp_overflow: bool,
+    /// Floating point underflow
+    pub fp_underflow: bool,
+    /// Floating point comparison result, if one is recorded
+    pub fp_compare: Option<FpCompare>,
+    /// Bounds check violation
+    pub bounds_check: bool,
+    /// Software trap
+    pub trap: bool,
+    /// Data access page fault
+    pub page_fault: bool,
+    /// Write protection violation
+    pub write_protect: bool,
+    /// Privilege violation
+    pub privilege_violation: bool,
+    /// Integer division by zero
+    pub divide_zero: bool,
+    /// Signed integer overflow
+    pub overflow: bool,
+    /// Instruction fetch fault
+    pub instruction_fault: bool,
+    /// External interrupts are masked
+    pub interrupt_disable: bool,
+}
+
+impl ProcessorStatus {
+    /// The cr0 flag and field of every boolean in the struct.
+    fn flags(&mut self) -> [(u32, &mut bool); 18] {
+        [
+            (CPU::CR0_EQUAL, &mut self.equal),
+            (CPU::CR0_LESS, &mut self.less),
+            (CPU::CR0_GREATER, &mut self.greater),
+            (CPU::CR0_UNORDERED, &mut self.unordered),
+            (CPU::CR0_FP_DIVZERO, &mut self.fp_divide_zero),
+            (CPU::CR0_FP_INEXACT, &mut self.fp_inexact),
+            (CPU::CR0_FP_INVALID, &mut self.fp_invalid),
+            (CPU::CR0_FP_OVERFLOW, &mut self.fp_overflow),
+            (CPU::CR0_FP_UNDERFLOW, &mut self.fp_underflow),
+            (CPU::CR0_BOUNDS_CHECK, &mut self.bounds_check),
+            (CPU::CR0_TRAP, &mut self.trap),
+            (CPU::CR0_PAGE_FAULT, &mut self.page_fault),
+            (CPU::CR0_WRITE_PROTECT, &mut self.write_protect),
+            (CPU::CR0_PRIVILEGE_VIOLATION, &mut self.privilege_violation),
+            (CPU::CR0_DIVIDE_ZERO, &mut self.divide_zero),
+            (CPU::CR0_OVERFLOW, &mut self.overflow),
+            (CPU::CR0_INSTR_FAULT, &mut self.instruction_fault),
+            (CPU::CR0_INTERRUPT_DISABLE, &mut self.interrupt_disable),
+        ]
+    }
+
+    /// Unpacks a cr0 value.
+    pub fn from_cr0(cr0: u32) -> Self {
+        let mut status = ProcessorStatus {
+            fp_compare: FpCompare::from_cr0(cr0),
+            ..Default::default()
+        };
+        for (flag, field) in status.flags() {
+            *field = cr0 & flag != 0;
+        }
+        status
+    }
+
+    /// Packs the status into a cr0 value.
+    pub fn to_cr0(mut self) -> u32 {
+        let fp_compare = self.fp_compare.map_or(0, FpCompare::flag);
+        self.flags()
+            .into_iter()
+            .filter(|(_, field)| **field)
+            .fold(fp_compare, |cr0, (flag, _)| cr0 | flag)
+    }
+}
+
+/// Decoded snapshot of the CPU state, for diagnostics.
+///
+/// The `Display` implementation prints a one-screen summary with the
+/// register file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpuStatus {
+    /// Program counter
+    pub pc: u32,
+    /// Raw control register 0
+    pub cr0: u32,
+    /// Current privilege level
+    pub privilege_level: PrivilegeLevel,
+    /// Whether address translation is enabled
+    pub mmu_enabled: bool,
+    /// Exceptions flagged in cr0
+    pub exceptions: Vec<ExceptionKind>,
+    /// Floating point comparison result, if one is recorded
+    pub fp_compare: Option<FpCompare>,
+    /// General purpose registers
+    pub registers: [u32; 32],
+}
+
+impl fmt::Display for CpuStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&format_registers(&self.registers, self.pc, self.cr0))?;
+        writeln!(
+            f,
+            "privilege = {:?}  mmu = {}",
+            self.privilege_level,
+            if self.mmu_enabled { "on" } else { "off" }
+        )?;
+        if self.exceptions.is_empty() {
+            writeln!(f, "exceptions = none")?;
+        } else {
+            writeln!(f, "exceptions = {:?}", self.exceptions)?;
+        }
+        match self.fp_compare {
+            Some(result) => writeln!(f, "fp compare = {:?}", result),
+            None => writeln!(f, "fp compare = none"),
+        }
+    }
+}
+
+/// Lays out the register file four registers per line, then `pc` and `cr0`.
+fn format_registers(registers: &[u32; 32], pc: u32, cr0: u32) -> String {
+    let mut dump = String::new();
+    for (idx, value) in registers.iter().enumerate() {
+        let separator = if idx % 4 == 3 { "\n" } else { "  " };
+        dump.push_str(&format!("r{:<2} = {:08x}{}", idx, value, separator));
+    }
+    dump.push_str(&format!("pc  = {:08x}  cr0 = {:08x}\n", pc, cr0));
+    dump
+}
+
+/// Callback invoked by the step loop for every executed instruction.
+struct TraceHook(Box<dyn FnMut(&TraceEvent)>);
+
+impl fmt::Debug for TraceHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TraceHook")
+    }
+}
+
+/// Callback deciding how an undecodable instruction word is handled.
+struct IllegalInstructionHandler(Box<dyn FnMut(u32) -> IllegalAction>);
+
+impl fmt::Debug for IllegalInstructionHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("IllegalInstructionHandler")
+    }
+}
 
 /// CPU state for the Motorola 88000.
 ///
@@ -14,27 +487,27 @@ use instructions::system::PrivilegeLevel;
 #[derive(Debug, Default)]
 pub struct CPU {
     /// General purpose registers (r0-r31)
+    ///
+    /// Prefer [`CPU::reg`] and [`CPU::set_reg`] when the index is not
+    /// known to be valid, as indexing out of range panics.
     pub registers: [u32; 32],
+    /// Extended registers (x0-x31) of the 88110 register file
+    ///
+    /// Prefer [`CPU::x_reg`] and [`CPU::set_x_reg`] when the index is not
+    /// known to be valid, as indexing out of range panics.
+    pub x_registers: [u32; 32],
     /// Program counter
     pub pc: u32,
     /// Control register 0 (Processor Status Register)
     pub cr0: u32,
-    /// Current instruction's destination register
-    pub d: usize,
-    /// Current instruction's first source register
-    pub s1: usize,
-    /// Current instruction's second source register
-    pub s2: usize,
-    /// Current instruction's immediate value
-    pub imm: i16,
-    /// Current instruction's branch offset
-    pub offset: i16,
     /// Shadow execution instruction pointer
     pub sxip: u32,
     /// Shadow next instruction pointer
     pub snip: u32,
     /// Shadow fetch instruction pointer
     pub sfip: u32,
+    /// Privilege level saved on exception entry, restored by `Rte`
+    pub shadow_privilege_level: PrivilegeLevel,
     /// Next instruction pointer
     pub nip: u32,
     /// Fetch instruction pointer
@@ -43,12 +516,46 @@ pub struct CPU {
     pub vector: u8,
     /// Current trap vector number
     pub trap_vector: u8,
+    /// Virtual address of the most recent data access fault
+    pub fault_addr: u32,
+    /// The processor has stopped executing instructions
+    pub halted: bool,
+    /// Number of instructions retired by `step`
+    pub instret: u64,
     /// Page Table Base Register
     pub ptbr: u32,
     /// MMU Control Register
     pub mmu_control: u32,
+    /// Vector Base Register: address of the exception vector table
+    pub vbr: u32,
+    /// Pending external interrupt lines (one bit per line)
+    pub interrupt_pending: u32,
+    /// Enabled external interrupt lines (one bit per line)
+    pub interrupt_mask: u32,
+    /// Line number of the most recently delivered interrupt
+    pub interrupt_line: u8,
     /// Current privilege level
     privilege_level: PrivilegeLevel,
+    /// Set when the executing instruction redirected the program counter
+    branch_taken: bool,
+    /// Target of a delayed branch, taken once the delay slot retires
+    delayed_branch: Option<u32>,
+    /// Vector of the exception raised by the executing instruction
+    exception_taken: Option<u8>,
+    /// Data access fault flagged by the executing instruction
+    data_fault: Option<ExecError>,
+    /// Observer called before each instruction executes
+    trace_hook: Option<TraceHook>,
+    /// Handler consulted for instruction words that do not decode
+    illegal_instruction_handler: Option<IllegalInstructionHandler>,
+    /// Instruction addresses that stop the run loop
+    breakpoints: HashSet<u32>,
+    /// MMU enable state last agreed between the CPU and memory
+    mmu_synced: bool,
+    /// Record an undo record for every step
+    journaling: bool,
+    /// Undo records of the most recent steps, oldest first
+    journal: VecDeque<UndoRecord>,
 }
 
 impl CPU {
@@ -93,6 +600,75 @@ impl CPU {
     pub const CR0_WRITE_PROTECT: u32 = 1 << 16;
     /// Exception flag: Privilege Violation
     pub const CR0_PRIVILEGE_VIOLATION: u32 = 1 << 17;
+    /// Exception flag: Integer Division by Zero
+    pub const CR0_DIVIDE_ZERO: u32 = 1 << 19;
+    /// Exception flag: Signed Integer Overflow
+    pub const CR0_OVERFLOW: u32 = 1 << 20;
+    /// Exception flag: Instruction Fetch Fault
+    pub const CR0_INSTR_FAULT: u32 = 1 << 21;
+    /// All exception flags
+    pub const CR0_EXCEPTION_MASK: u32 = Self::CR0_BOUNDS_CHECK
+        | Self::CR0_TRAP
+        | Self::CR0_PAGE_FAULT
+        | Self::CR0_WRITE_PROTECT
+        | Self::CR0_PRIVILEGE_VIOLATION
+        | Self::CR0_DIVIDE_ZERO
+        | Self::CR0_OVERFLOW
+        | Self::CR0_INSTR_FAULT;
+
+    /// Control register number: processor status (cr0)
+    pub const CR_PSR: u32 = 0;
+    /// Control register number: shadow execution instruction pointer
+    pub const CR_SXIP: u32 = 1;
+    /// Control register number: shadow next instruction pointer
+    pub const CR_SNIP: u32 = 2;
+    /// Control register number: shadow fetch instruction pointer
+    pub const CR_SFIP: u32 = 3;
+    /// Control register number: page table base
+    pub const CR_PTBR: u32 = 4;
+    /// Control register number: data fault address
+    pub const CR_FAULT_ADDRESS: u32 = 5;
+    /// Control register number: MMU control
+    pub const CR_MMU_CONTROL: u32 = 6;
+    /// Control register number: vector base
+    pub const CR_VBR: u32 = 7;
+    /// Number of modelled control registers
+    pub const CONTROL_REGISTERS: u32 = 8;
+
+    /// Interrupt control: Disable all external interrupts
+    pub const CR0_INTERRUPT_DISABLE: u32 = 1 << 18;
+
+    /// Number of external interrupt lines
+    pub const INTERRUPT_LINES: u8 = 32;
+
+    /// Number of steps kept by the undo journal
+    pub const JOURNAL_CAPACITY: usize = 1024;
+
+    /// Exception vector: Reset
+    pub const VECTOR_RESET: u8 = 0;
+    /// Exception vector: External Interrupt
+    pub const VECTOR_INTERRUPT: u8 = 1;
+    /// Exception vector: Instruction Access Exception
+    pub const VECTOR_INSTRUCTION_ACCESS: u8 = 2;
+    /// Exception vector: Data Access Exception
+    pub const VECTOR_DATA_ACCESS: u8 = 3;
+    /// Exception vector: Misaligned Access
+    pub const VECTOR_MISALIGNED_ACCESS: u8 = 4;
+    /// Exception vector: Unimplemented Opcode
+    pub const VECTOR_UNIMPLEMENTED_OPCODE: u8 = 5;
+    /// Exception vector: Privilege Violation
+    pub const VECTOR_PRIVILEGE_VIOLATION: u8 = 6;
+    /// Exception vector: Bounds Check Violation
+    pub const VECTOR_BOUNDS_CHECK: u8 = 7;
+    /// Exception vector: Illegal Integer Divide
+    pub const VECTOR_INTEGER_DIVIDE: u8 = 8;
+    /// Exception vector: Integer Overflow
+    pub const VECTOR_INTEGER_OVERFLOW: u8 = 9;
+    /// Exception vector: Illegal Operand (e.g. a misaligned register pair)
+    pub const VECTOR_ILLEGAL_OPERAND: u8 = 10;
+
+    /// Size in bytes of each exception vector table slot (two instructions)
+    pub const VECTOR_SLOT_SIZE: u32 = 8;
 
     /// MMU control bit: Enable MMU
     #[allow(dead_code)]
@@ -116,6 +692,31 @@ impl CPU {
         }
     }
 
+    /// Restores the architectural reset state.
+    ///
+    /// All registers and control registers are cleared except that the
+    /// processor enters supervisor mode with external interrupts disabled,
+    /// the MMU is turned off (memory follows on the next `step`), and `pc`
+    /// is set to the reset vector. The trace hook, illegal instruction
+    /// handler and breakpoints are kept.
+    pub fn reset(&mut self) {
+        let trace_hook = self.trace_hook.take();
+        let illegal_instruction_handler = self.illegal_instruction_handler.take();
+        let breakpoints = std::mem::take(&mut self.breakpoints);
+        *self = Self {
+            cr0: Self::CR0_INTERRUPT_DISABLE,
+            privilege_level: PrivilegeLevel::Supervisor,
+            trace_hook,
+            illegal_instruction_handler,
+            breakpoints,
+            journaling: self.journaling,
+            // Forces the disabled MMU state onto memory at the next sync
+            mmu_synced: true,
+            ..Default::default()
+        };
+        self.pc = self.exception_handler_address(Self::VECTOR_RESET);
+    }
+
     /// Sets a floating point flag in CR0.
     ///
     /// # Arguments
@@ -134,14 +735,96 @@ impl CPU {
         self.cr0 &= !flag;
     }
 
-    /// Sets the page fault flag in CR0.
-    pub fn set_page_fault(&mut self) {
+    /// Sets the page fault flag in CR0 and records the faulting address.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - The virtual address that faulted
+    pub fn set_page_fault(&mut self, addr: u32) {
         self.cr0 |= Self::CR0_PAGE_FAULT;
+        self.fault_addr = addr;
+        self.data_fault = Some(ExecError::PageFault(addr));
+    }
+
+    /// Sets the instruction fetch fault flag in CR0.
+    pub fn set_instruction_fault(&mut self) {
+        self.cr0 |= Self::CR0_INSTR_FAULT;
     }
 
-    /// Sets the write protection fault flag in CR0.
-    pub fn set_write_protect_fault(&mut self) {
+    /// Sets the write protection fault flag in CR0 and records the faulting address.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - The virtual address that faulted
+    pub fn set_write_protect_fault(&mut self, addr: u32) {
         self.cr0 |= Self::CR0_WRITE_PROTECT;
+        self.fault_addr = addr;
+        self.data_fault = Some(ExecError::WriteProtect(addr));
+    }
+
+    /// Reads a control register by number.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The control register number
+    ///
+    /// # Returns
+    ///
+    /// The register value, or `None` if the control register is not modelled.
+    pub fn control_register(&self, index: u32) -> Option<u32> {
+        match index {
+            Self::CR_PSR => Some(self.cr0),
+            Self::CR_SXIP => Some(self.sxip),
+            Self::CR_SNIP => Some(self.snip),
+            Self::CR_SFIP => Some(self.sfip),
+            Self::CR_PTBR => Some(self.ptbr),
+            Self::CR_FAULT_ADDRESS => Some(self.fault_addr),
+            Self::CR_MMU_CONTROL => Some(self.mmu_control),
+            Self::CR_VBR => Some(self.vbr),
+            _ => None,
+        }
+    }
+
+    /// Writes a control register by number.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The control register number
+    /// * `value` - The value to write
+    ///
+    /// # Returns
+    ///
+    /// `false` if the control register is not modelled.
+    pub fn set_control_register(&mut self, index: u32, value: u32) -> bool {
+        let register = match index {
+            Self::CR_PSR => &mut self.cr0,
+            Self::CR_SXIP => &mut self.sxip,
+            Self::CR_SNIP => &mut self.snip,
+            Self::CR_SFIP => &mut self.sfip,
+            Self::CR_PTBR => &mut self.ptbr,
+            Self::CR_FAULT_ADDRESS => &mut self.fault_addr,
+            Self::CR_MMU_CONTROL => &mut self.mmu_control,
+            Self::CR_VBR => &mut self.vbr,
+            _ => return false,
+        };
+        *register = value;
+        true
+    }
+
+    /// Checks whether a control register is only accessible in supervisor mode.
+    ///
+    /// The processor status and fault address registers are available to user
+    /// code; all other control registers are protected.
+    pub fn control_register_protected(index: u32) -> bool {
+        !matches!(index, Self::CR_PSR | Self::CR_FAULT_ADDRESS)
+    }
+
+    /// Checks whether a control register may only be written in supervisor mode.
+    ///
+    /// The processor status register holds the interrupt disable bit and the
+    /// exception flags, so user code may read it but not write it.
+    pub fn control_register_write_protected(index: u32) -> bool {
+        index == Self::CR_PSR || Self::control_register_protected(index)
     }
 
     /// Sets the privilege violation flag in CR0.
@@ -159,6 +842,262 @@ impl CPU {
         (self.cr0 & Self::CR0_PRIVILEGE_VIOLATION) != 0
     }
 
+    /// Clears all exception flags in CR0.
+    ///
+    /// Condition codes, floating point flags and the interrupt disable bit
+    /// are preserved.
+    pub fn clear_exceptions(&mut self) {
+        self.cr0 &= !Self::CR0_EXCEPTION_MASK;
+    }
+
+    /// Lists the exceptions currently flagged in CR0.
+    pub fn pending_exceptions(&self) -> Vec<ExceptionKind> {
+        ExceptionKind::ALL
+            .into_iter()
+            .filter(|kind| self.cr0 & kind.flag() != 0)
+            .collect()
+    }
+
+    /// Reads a general purpose register.
+    ///
+    /// # Arguments
+    ///
+    /// * `idx` - The register number (0-31)
+    ///
+    /// # Returns
+    ///
+    /// The register value, or `CpuError::InvalidRegister` if `idx` is out of range.
+    pub fn reg(&self, idx: usize) -> Result<u32, CpuError> {
+        self.registers
+            .get(idx)
+            .copied()
+            .ok_or(CpuError::InvalidRegister(idx))
+    }
+
+    /// Writes a general purpose register.
+    ///
+    /// # Arguments
+    ///
+    /// * `idx` - The register number (0-31)
+    /// * `val` - The value to write
+    ///
+    /// # Returns
+    ///
+    /// `CpuError::InvalidRegister` if `idx` is out of range.
+    pub fn set_reg(&mut self, idx: usize, val: u32) -> Result<(), CpuError> {
+        let reg = self
+            .registers
+            .get_mut(idx)
+            .ok_or(CpuError::InvalidRegister(idx))?;
+        *reg = val;
+        Ok(())
+    }
+
+    /// Reads an extended register.
+    ///
+    /// # Arguments
+    ///
+    /// * `idx` - The extended register number (0-31)
+    ///
+    /// # Returns
+    ///
+    /// The register value, or `CpuError::InvalidRegister` if `idx` is out of range.
+    pub fn x_reg(&self, idx: usize) -> Result<u32, CpuError> {
+        self.x_registers
+            .get(idx)
+            .copied()
+            .ok_or(CpuError::InvalidRegister(idx))
+    }
+
+    /// Writes an extended register.
+    ///
+    /// # Arguments
+    ///
+    /// * `idx` - The extended register number (0-31)
+    /// * `val` - The value to write
+    ///
+    /// # Returns
+    ///
+    /// `CpuError::InvalidRegister` if `idx` is out of range.
+    pub fn set_x_reg(&mut self, idx: usize, val: u32) -> Result<(), CpuError> {
+        let reg = self
+            .x_registers
+            .get_mut(idx)
+            .ok_or(CpuError::InvalidRegister(idx))?;
+        *reg = val;
+        Ok(())
+    }
+
+    /// Reads a general purpose register as a single precision float.
+    ///
+    /// # Arguments
+    ///
+    /// * `idx` - The register number (0-31)
+    pub fn freg_s(&self, idx: usize) -> f32 {
+        f32::from_bits(self.registers[idx])
+    }
+
+    /// Writes a single precision float to a general purpose register.
+    ///
+    /// # Arguments
+    ///
+    /// * `idx` - The register number (0-31)
+    /// * `value` - The value to write
+    pub fn set_freg_s(&mut self, idx: usize, value: f32) {
+        self.registers[idx] = value.to_bits();
+    }
+
+    /// Reads the register pair `idx:idx+1` as a double precision float.
+    ///
+    /// The high word of the value is held in `idx` and the low word in `idx+1`.
+    ///
+    /// # Arguments
+    ///
+    /// * `idx` - The register number of the high word (0-30)
+    pub fn freg_d(&self, idx: usize) -> f64 {
+        let bits = ((self.registers[idx] as u64) << 32) | self.registers[idx + 1] as u64;
+        f64::from_bits(bits)
+    }
+
+    /// Writes a double precision float to the register pair `idx:idx+1`.
+    ///
+    /// The high word of the value is written to `idx` and the low word to `idx+1`.
+    ///
+    /// # Arguments
+    ///
+    /// * `idx` - The register number of the high word (0-30)
+    /// * `value` - The value to write
+    pub fn set_freg_d(&mut self, idx: usize, value: f64) {
+        let bits = value.to_bits();
+        self.registers[idx] = (bits >> 32) as u32;
+        self.registers[idx + 1] = bits as u32;
+    }
+
+    /// Checks that `reg` names the high register of a register pair.
+    ///
+    /// Doubleword operands occupy an even register and its odd partner. An
+    /// odd or out-of-range register takes the illegal operand exception.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the pair `reg:reg+1` may be accessed.
+    pub fn check_register_pair(&mut self, reg: usize) -> bool {
+        if reg & 1 != 0 || reg + 1 >= self.registers.len() {
+            self.raise_exception(Self::VECTOR_ILLEGAL_OPERAND);
+            return false;
+        }
+        true
+    }
+
+    /// Writes a 64-bit value to the register pair `reg:reg+1`, high word in `reg`.
+    ///
+    /// The pair is checked with [`CPU::check_register_pair`] first, and the
+    /// half destined for r0 is discarded.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the pair is invalid and the illegal operand exception was taken.
+    pub fn write_register_pair(&mut self, reg: usize, value: u64) -> bool {
+        if !self.check_register_pair(reg) {
+            return false;
+        }
+        if reg != 0 {
+            self.registers[reg] = (value >> 32) as u32;
+        }
+        self.registers[reg + 1] = value as u32;
+        true
+    }
+
+    /// Computes a register-plus-displacement effective address.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - The base register number
+    /// * `offset` - The signed displacement
+    pub fn effective_address(&self, base: usize, offset: i16) -> u32 {
+        self.overflowing_effective_address(base, offset).0
+    }
+
+    /// Computes a register-plus-displacement effective address, also
+    /// returning whether it wrapped past either end of the address space.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - The base register number
+    /// * `offset` - The signed displacement
+    pub fn overflowing_effective_address(&self, base: usize, offset: i16) -> (u32, bool) {
+        self.registers[base].overflowing_add_signed(offset as i32)
+    }
+
+    /// Computes a scaled register-indexed effective address: base + index * scale.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - The base register number
+    /// * `index` - The index register number
+    /// * `scale` - The access size in bytes the index is scaled by
+    pub fn indexed_address(&self, base: usize, index: usize, scale: u32) -> u32 {
+        self.registers[base].wrapping_add(self.registers[index].wrapping_mul(scale))
+    }
+
+    /// Computes a scaled register-indexed effective address, also returning
+    /// whether scaling the index or adding it wrapped past 32 bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - The base register number
+    /// * `index` - The index register number
+    /// * `scale` - The access size in bytes the index is scaled by
+    pub fn overflowing_indexed_address(
+        &self,
+        base: usize,
+        index: usize,
+        scale: u32,
+    ) -> (u32, bool) {
+        let (scaled, scale_wrapped) = self.registers[index].overflowing_mul(scale);
+        let (addr, add_wrapped) = self.registers[base].overflowing_add(scaled);
+        (addr, scale_wrapped || add_wrapped)
+    }
+
+    /// Iterates over the general purpose registers as `(index, value)` pairs.
+    pub fn registers_iter(&self) -> impl Iterator<Item = (usize, u32)> + '_ {
+        self.registers.iter().copied().enumerate()
+    }
+
+    /// Formats the register file as a hex dump.
+    ///
+    /// The general purpose registers are laid out four per line, followed by
+    /// a line with `pc` and `cr0`.
+    pub fn dump_registers(&self) -> String {
+        format_registers(&self.registers, self.pc, self.cr0)
+    }
+
+    /// Returns a decoded snapshot of the CPU state.
+    pub fn status(&self) -> CpuStatus {
+        CpuStatus {
+            pc: self.pc,
+            cr0: self.cr0,
+            privilege_level: self.privilege_level,
+            mmu_enabled: self.mmu_enabled(),
+            exceptions: self.pending_exceptions(),
+            fp_compare: FpCompare::from_cr0(self.cr0),
+            registers: self.registers,
+        }
+    }
+
+    /// Returns a typed view of the processor status register (cr0).
+    pub fn psr(&self) -> ProcessorStatus {
+        ProcessorStatus::from_cr0(self.cr0)
+    }
+
+    /// Replaces the processor status register (cr0) with a typed status.
+    ///
+    /// Every cr0 flag is written, so fields left at their default clear the
+    /// corresponding bits.
+    pub fn set_psr(&mut self, status: ProcessorStatus) {
+        self.cr0 = status.to_cr0();
+    }
+
     /// Gets the current privilege level.
     pub fn get_privilege_level(&self) -> PrivilegeLevel {
         self.privilege_level
@@ -190,26 +1129,780 @@ impl CPU {
             self.mmu_control &= !Self::MMU_ENABLE;
         }
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn test_cpu_initialization() {
-        let cpu = CPU::new();
-        assert_eq!(cpu.registers[0], 0);
-        assert_eq!(cpu.pc, 0);
-        assert_eq!(cpu.cr0, 0);
-        assert_eq!(cpu.ptbr, 0);
-        assert_eq!(cpu.mmu_control, 0);
-        assert_eq!(cpu.get_privilege_level(), PrivilegeLevel::User);
+    /// Reconciles the MMU enable state of the CPU and memory.
+    ///
+    /// Whichever side changed since the last synchronization wins: setting
+    /// `MMU_ENABLE` in `mmu_control` turns translation on or off in memory,
+    /// and enabling the MMU through memory is reflected in `mmu_control`.
+    /// The step loop synchronizes before every instruction.
+    ///
+    /// # Arguments
+    ///
+    /// * `memory` - The memory whose translation state is synchronized
+    pub fn sync_mmu(&mut self, memory: &mut Memory) {
+        if self.mmu_enabled() != self.mmu_synced {
+            memory.set_mmu_enabled(self.mmu_enabled());
+        } else if memory.is_mmu_enabled() != self.mmu_synced {
+            self.set_mmu_enabled(memory.is_mmu_enabled());
+        }
+        self.mmu_synced = self.mmu_enabled();
     }
 
-    #[test]
-    fn test_set_fp_flag() {
-        let mut cpu = CPU::new();
+    /// Asserts an external interrupt line.
+    ///
+    /// The interrupt is delivered at the start of the next `step` if the line
+    /// is enabled in `interrupt_mask` and interrupts are not globally disabled.
+    /// Lines outside `0..INTERRUPT_LINES` are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The interrupt line to assert
+    pub fn assert_interrupt(&mut self, line: u8) {
+        if line < Self::INTERRUPT_LINES {
+            self.interrupt_pending |= 1 << line;
+        }
+    }
+
+    /// Deasserts an external interrupt line without delivering it.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The interrupt line to deassert
+    pub fn deassert_interrupt(&mut self, line: u8) {
+        if line < Self::INTERRUPT_LINES {
+            self.interrupt_pending &= !(1 << line);
+        }
+    }
+
+    /// Enables or disables delivery of an external interrupt line.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The interrupt line to configure
+    /// * `enabled` - Whether the line may interrupt the processor
+    pub fn set_interrupt_enabled(&mut self, line: u8, enabled: bool) {
+        if line >= Self::INTERRUPT_LINES {
+            return;
+        }
+        if enabled {
+            self.interrupt_mask |= 1 << line;
+        } else {
+            self.interrupt_mask &= !(1 << line);
+        }
+    }
+
+    /// Returns the lowest-numbered interrupt line that is pending and enabled.
+    fn deliverable_interrupt(&self) -> Option<u8> {
+        if self.cr0 & Self::CR0_INTERRUPT_DISABLE != 0 {
+            return None;
+        }
+        let ready = self.interrupt_pending & self.interrupt_mask;
+        if ready == 0 {
+            None
+        } else {
+            Some(ready.trailing_zeros() as u8)
+        }
+    }
+
+    /// Installs a hook that is called before each instruction executes.
+    ///
+    /// Replaces any previously installed hook.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - The callback receiving a `TraceEvent` per instruction
+    pub fn set_trace_hook(&mut self, hook: Box<dyn FnMut(&TraceEvent)>) {
+        self.trace_hook = Some(TraceHook(hook));
+    }
+
+    /// Removes the installed trace hook, if any.
+    pub fn clear_trace_hook(&mut self) {
+        self.trace_hook = None;
+    }
+
+    /// Installs a handler for instruction words that do not decode.
+    ///
+    /// The handler receives the raw word and chooses an [`IllegalAction`],
+    /// which is useful for stubbing out missing opcodes during bring-up.
+    /// Without a handler the unimplemented opcode exception is raised.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The callback choosing how to handle the word
+    pub fn set_illegal_instruction_handler(
+        &mut self,
+        handler: Box<dyn FnMut(u32) -> IllegalAction>,
+    ) {
+        self.illegal_instruction_handler = Some(IllegalInstructionHandler(handler));
+    }
+
+    /// Removes the installed illegal instruction handler, if any.
+    pub fn clear_illegal_instruction_handler(&mut self) {
+        self.illegal_instruction_handler = None;
+    }
+
+    /// Adds a breakpoint at an instruction address.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - The instruction address to stop at
+    pub fn add_breakpoint(&mut self, addr: u32) {
+        self.breakpoints.insert(addr);
+    }
+
+    /// Removes a breakpoint from an instruction address.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - The instruction address to clear
+    pub fn remove_breakpoint(&mut self, addr: u32) {
+        self.breakpoints.remove(&addr);
+    }
+
+    /// Checks if a breakpoint is set at an instruction address.
+    pub fn has_breakpoint(&self, addr: u32) -> bool {
+        self.breakpoints.contains(&addr)
+    }
+
+    /// Transfers control to a new program counter value.
+    ///
+    /// Instructions that change the flow of control use this so that the step
+    /// loop does not advance past the new target.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The address of the next instruction to execute
+    pub fn branch_to(&mut self, target: u32) {
+        self.pc = target;
+        self.branch_taken = true;
+    }
+
+    /// Schedules a delayed branch.
+    ///
+    /// The instruction following the branch (the delay slot) executes first,
+    /// then control transfers to `target`.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The address to branch to once the delay slot retires
+    pub fn branch_delayed(&mut self, target: u32) {
+        self.delayed_branch = Some(target);
+    }
+
+    /// Checks if the next instruction to execute sits in a delay slot.
+    pub fn in_delay_slot(&self) -> bool {
+        self.delayed_branch.is_some()
+    }
+
+    /// Returns the address of the exception handler for a vector.
+    ///
+    /// Each vector owns a two-instruction slot in the table at `vbr`.
+    ///
+    /// # Arguments
+    ///
+    /// * `vector` - The exception vector number
+    pub fn exception_handler_address(&self, vector: u8) -> u32 {
+        self.vbr
+            .wrapping_add(vector as u32 * Self::VECTOR_SLOT_SIZE)
+    }
+
+    /// Raises an exception, vectoring through the exception table.
+    ///
+    /// The shadow instruction pointers are loaded so that `Rte` resumes at the
+    /// instruction at the current program counter, the current privilege level
+    /// is saved, the processor enters supervisor mode, and control transfers to
+    /// the handler for `vector`.
+    ///
+    /// # Arguments
+    ///
+    /// * `vector` - The exception vector number
+    pub fn raise_exception(&mut self, vector: u8) {
+        self.enter_exception(vector, self.pc);
+    }
+
+    /// Raises a software trap, vectoring through the exception table.
+    ///
+    /// Unlike [`CPU::raise_exception`], the shadow instruction pointers are
+    /// loaded so that `Rte` resumes at the instruction following the trap.
+    ///
+    /// # Arguments
+    ///
+    /// * `vector` - The trap vector number
+    pub fn raise_trap(&mut self, vector: u8) {
+        self.enter_exception(vector, self.pc.wrapping_add(4));
+    }
+
+    fn enter_exception(&mut self, vector: u8, resume: u32) {
+        self.sxip = resume;
+        self.snip = resume.wrapping_add(4);
+        self.sfip = resume.wrapping_add(8);
+        self.shadow_privilege_level = self.privilege_level;
+        self.vector = vector;
+        self.delayed_branch = None;
+        self.privilege_level = PrivilegeLevel::Supervisor;
+        self.exception_taken = Some(vector);
+        let handler = self.exception_handler_address(vector);
+        self.branch_to(handler);
+    }
+
+    /// Executes a single instruction.
+    ///
+    /// Delivers any pending, enabled interrupt before fetching (unless the next
+    /// instruction is in a delay slot), then fetches, decodes and executes the
+    /// instruction at `pc` and advances to the next instruction. Delivering an
+    /// interrupt takes the place of executing an instruction for this step.
+    /// Unknown encodings raise the unimplemented opcode exception (unless an
+    /// illegal instruction handler chooses otherwise), and faults flagged in
+    /// cr0 by the instruction vector through the exception table. A faulting
+    /// instruction commits no register writes and does not retire, so `Rte`
+    /// from the handler restarts it. A halted processor executes nothing and reports `StepOutcome::Halted`.
+    ///
+    /// # Arguments
+    ///
+    /// * `memory` - The system memory to fetch from and operate on
+    ///
+    /// # Returns
+    ///
+    /// How the step completed, or an error if the CPU state cannot be executed.
+    pub fn step(&mut self, memory: &mut Memory) -> Result<StepOutcome, EmulationError> {
+        if !self.journaling {
+            return self.execute_step(memory);
+        }
+
+        let control = ControlState::capture(self);
+        let (registers, x_registers) = (self.registers, self.x_registers);
+        let mmu_enabled = memory.is_mmu_enabled();
+        let page_table_base = memory.page_table_base();
+        memory.begin_write_journal();
+        let outcome = self.execute_step(memory);
+        let record = UndoRecord {
+            registers: register_deltas(&registers, &self.registers),
+            x_registers: register_deltas(&x_registers, &self.x_registers),
+            control,
+            mmu_enabled,
+            page_table_base,
+            memory: memory.end_write_journal(),
+        };
+        if self.journal.len() == Self::JOURNAL_CAPACITY {
+            self.journal.pop_front();
+        }
+        self.journal.push_back(record);
+        outcome
+    }
+
+    /// Enables or disables the undo journal.
+    ///
+    /// While enabled, every step records the registers, control state and
+    /// memory bytes it changed, up to the most recent
+    /// [`CPU::JOURNAL_CAPACITY`] steps, so that [`CPU::step_back`] can revert
+    /// them. Changing the setting clears the journal.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether steps are journaled
+    pub fn set_journaling(&mut self, enabled: bool) {
+        self.journaling = enabled;
+        self.journal.clear();
+    }
+
+    /// Reverts the most recent journaled step.
+    ///
+    /// Restores the registers, the control state, the memory translation
+    /// state and the memory bytes the step wrote, including page table
+    /// entries whose accessed and dirty bits it set; stepping again
+    /// re-executes the instruction. Other memory
+    /// state, such as reservations, watchpoint hits and the access log, is
+    /// not reverted.
+    ///
+    /// # Arguments
+    ///
+    /// * `memory` - The memory the step operated on
+    ///
+    /// # Returns
+    ///
+    /// `false` if the journal holds no step to revert.
+    pub fn step_back(&mut self, memory: &mut Memory) -> bool {
+        let Some(record) = self.journal.pop_back() else {
+            return false;
+        };
+        memory.undo_writes(&record.memory);
+        memory.set_mmu_enabled(record.mmu_enabled);
+        memory.set_page_table_base(record.page_table_base);
+        for delta in &record.registers {
+            self.registers[delta.reg] = delta.old;
+        }
+        for delta in &record.x_registers {
+            self.x_registers[delta.reg] = delta.old;
+        }
+        record.control.restore(self);
+        true
+    }
+
+    /// Executes a single instruction; see [`CPU::step`].
+    fn execute_step(&mut self, memory: &mut Memory) -> Result<StepOutcome, EmulationError> {
+        if self.halted {
+            return Ok(StepOutcome::Halted);
+        }
+        if self.pc & 0x3 != 0 {
+            return Err(EmulationError::MisalignedPc(self.pc));
+        }
+
+        memory.set_privilege_level(self.privilege_level);
+        self.sync_mmu(memory);
+        let slot_target = self.delayed_branch.take();
+
+        if slot_target.is_none() {
+            if let Some(line) = self.deliverable_interrupt() {
+                self.interrupt_pending &= !(1 << line);
+                self.interrupt_line = line;
+                self.raise_exception(Self::VECTOR_INTERRUPT);
+                return Ok(StepOutcome::Exception {
+                    vector: Self::VECTOR_INTERRUPT,
+                    address: None,
+                });
+            }
+        }
+
+        let fetch_addr = self.pc;
+        let word = match memory.fetch_word(fetch_addr) {
+            Ok(word) => word,
+            Err(_) => {
+                self.set_instruction_fault();
+                self.raise_exception(Self::VECTOR_INSTRUCTION_ACCESS);
+                return Ok(StepOutcome::Exception {
+                    vector: Self::VECTOR_INSTRUCTION_ACCESS,
+                    address: Some(fetch_addr),
+                });
+            }
+        };
+
+        let decoded = match decoder::decode(word) {
+            Some(decoded) => decoded,
+            None => {
+                let action = match self.illegal_instruction_handler.as_mut() {
+                    Some(handler) => (handler.0)(word),
+                    None => IllegalAction::Trap,
+                };
+                return Ok(match action {
+                    IllegalAction::Skip => match slot_target {
+                        Some(target) => {
+                            self.pc = target;
+                            StepOutcome::BranchTaken(target)
+                        }
+                        None => {
+                            self.pc = self.pc.wrapping_add(4);
+                            StepOutcome::Retired
+                        }
+                    },
+                    IllegalAction::Trap => {
+                        self.raise_exception(Self::VECTOR_UNIMPLEMENTED_OPCODE);
+                        StepOutcome::Exception {
+                            vector: Self::VECTOR_UNIMPLEMENTED_OPCODE,
+                            address: None,
+                        }
+                    }
+                    IllegalAction::Halt => {
+                        self.halted = true;
+                        StepOutcome::Halted
+                    }
+                });
+            }
+        };
+
+        if let Some(hook) = self.trace_hook.as_mut() {
+            (hook.0)(&TraceEvent {
+                pc: self.pc,
+                word,
+                mnemonic: decoded.mnemonic,
+            });
+        }
+
+        self.branch_taken = false;
+        memory.set_current_pc(Some(self.pc));
+        let result = decoded
+            .opcode
+            .try_execute(self, memory, &decoded.operands());
+        // Accesses made outside a step are not attributed to this instruction
+        memory.set_current_pc(None);
+        if let Err(error) = result {
+            let vector = error.vector();
+            if self.exception_taken.take().is_none() {
+                self.raise_exception(vector);
+            }
+            self.branch_taken = false;
+            self.exception_taken = None;
+            let address = (vector == Self::VECTOR_DATA_ACCESS).then_some(self.fault_addr);
+            return Ok(StepOutcome::Exception { vector, address });
+        }
+
+        self.instret = self.instret.wrapping_add(1);
+        if self.halted {
+            self.pc = slot_target.unwrap_or(self.pc.wrapping_add(4));
+            return Ok(StepOutcome::Halted);
+        }
+
+        if self.branch_taken {
+            self.branch_taken = false;
+            Ok(StepOutcome::BranchTaken(self.pc))
+        } else if let Some(target) = slot_target {
+            self.pc = target;
+            Ok(StepOutcome::BranchTaken(target))
+        } else {
+            self.pc = self.pc.wrapping_add(4);
+            Ok(StepOutcome::Retired)
+        }
+    }
+
+    /// Executes a single raw instruction word outside the step loop.
+    ///
+    /// The word is decoded and run through
+    /// [`Instruction::try_execute`]. `pc` is left unchanged, so a branch has
+    /// no effect on the program counter and nothing is fetched from memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `word` - The instruction word to execute
+    /// * `memory` - The system memory the instruction operates on
+    ///
+    /// # Returns
+    ///
+    /// How the instruction failed, if it did. A word that does not decode
+    /// reports `ExecError::UnimplementedOpcode` without changing any state.
+    /// An exception is reported but not taken: the privilege level, shadow
+    /// registers and other exception state are left as they were, and only
+    /// the cr0 flags and fault address record it.
+    pub fn execute_word(&mut self, word: u32, memory: &mut Memory) -> Result<(), ExecError> {
+        let decoded = decoder::decode(word).ok_or(ExecError::UnimplementedOpcode)?;
+        let saved = ControlState::capture(self);
+        let result = decoded
+            .opcode
+            .try_execute(self, memory, &decoded.operands());
+        if self.exception_taken.is_some() {
+            // Undo the exception entry, keeping only the flags reporting it
+            let (cr0, fault_addr) = (self.cr0, self.fault_addr);
+            saved.restore(self);
+            self.cr0 = cr0;
+            self.fault_addr = fault_addr;
+        } else {
+            self.pc = saved.pc;
+            self.branch_taken = false;
+            self.delayed_branch = saved.delayed_branch;
+        }
+        result
+    }
+
+    /// Executes up to `n` steps, recording each one.
+    ///
+    /// Stops early if the processor halts or a step fails. Breakpoints and
+    /// watchpoints are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `memory` - The system memory to fetch from and operate on
+    /// * `n` - The number of steps to execute
+    ///
+    /// # Returns
+    ///
+    /// One record per executed step, in execution order.
+    pub fn trace_steps(&mut self, memory: &mut Memory, n: usize) -> Vec<StepRecord> {
+        let mut records = Vec::with_capacity(n);
+        for _ in 0..n {
+            let pc = self.pc;
+            let mnemonic = memory
+                .peek_word(pc)
+                .ok()
+                .and_then(decoder::decode)
+                .map(|decoded| decoded.mnemonic);
+            let before = self.registers;
+
+            let outcome = self.step(memory);
+            records.push(StepRecord {
+                pc,
+                mnemonic,
+                deltas: register_deltas(&before, &self.registers),
+            });
+
+            if !matches!(
+                outcome,
+                Ok(StepOutcome::Retired
+                    | StepOutcome::BranchTaken(_)
+                    | StepOutcome::Exception { .. })
+            ) {
+                break;
+            }
+        }
+        records
+    }
+
+    /// Executes instructions until a stop condition is reached.
+    ///
+    /// Stops before executing an instruction at a breakpoint, after an
+    /// instruction writes to a watched memory address, when the processor
+    /// halts, or once `max_steps` steps have run. The instruction at the starting `pc` always executes,
+    /// so calling `run` again after a breakpoint resumes past it.
+    ///
+    /// # Arguments
+    ///
+    /// * `memory` - The system memory to fetch from and operate on
+    /// * `max_steps` - The maximum number of steps to execute
+    ///
+    /// # Returns
+    ///
+    /// The reason execution stopped, or the error that aborted a step.
+    pub fn run(
+        &mut self,
+        memory: &mut Memory,
+        max_steps: u64,
+    ) -> Result<StopReason, EmulationError> {
+        for executed in 0..max_steps {
+            if executed > 0 && self.breakpoints.contains(&self.pc) {
+                return Ok(StopReason::Breakpoint(self.pc));
+            }
+            if self.step(memory)? == StepOutcome::Halted {
+                return Ok(StopReason::Halted);
+            }
+            if let Some(hit) = memory.take_watchpoint_hit() {
+                return Ok(StopReason::Watchpoint {
+                    addr: hit.addr,
+                    old: hit.old,
+                    new: hit.new,
+                });
+            }
+        }
+        Ok(StopReason::StepLimit)
+    }
+}
+
+/// Builder for a CPU in a specific initial state.
+///
+/// # Example
+///
+/// ```
+/// use motorola88k::cpu::CpuBuilder;
+///
+/// let cpu = CpuBuilder::new().supervisor().pc(0x1000).register(2, 42).build();
+/// assert_eq!(cpu.pc, 0x1000);
+/// assert_eq!(cpu.registers[2], 42);
+/// ```
+#[derive(Debug, Default)]
+pub struct CpuBuilder {
+    cpu: CPU,
+}
+
+impl CpuBuilder {
+    /// Creates a builder starting from the reset state of [`CPU::new`].
+    pub fn new() -> Self {
+        Self { cpu: CPU::new() }
+    }
+
+    /// Sets the privilege level.
+    pub fn privilege(mut self, level: PrivilegeLevel) -> Self {
+        self.cpu.set_privilege_level(level);
+        self
+    }
+
+    /// Starts the CPU in supervisor mode.
+    pub fn supervisor(self) -> Self {
+        self.privilege(PrivilegeLevel::Supervisor)
+    }
+
+    /// Sets the initial program counter.
+    pub fn pc(mut self, addr: u32) -> Self {
+        self.cpu.pc = addr;
+        self
+    }
+
+    /// Sets the initial value of a general purpose register.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is not a register number (0-31).
+    pub fn register(mut self, idx: usize, val: u32) -> Self {
+        self.cpu.registers[idx] = val;
+        self
+    }
+
+    /// Returns the configured CPU.
+    pub fn build(self) -> CPU {
+        self.cpu
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::PageTableEntry;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_cpu_initialization() {
+        let cpu = CPU::new();
+        assert_eq!(cpu.registers[0], 0);
+        assert_eq!(cpu.pc, 0);
+        assert_eq!(cpu.cr0, 0);
+        assert_eq!(cpu.ptbr, 0);
+        assert_eq!(cpu.mmu_control, 0);
+        assert_eq!(cpu.get_privilege_level(), PrivilegeLevel::User);
+    }
+
+    #[test]
+    fn test_cpu_builder() {
+        let cpu = CpuBuilder::new()
+            .supervisor()
+            .pc(0x1000)
+            .register(2, 42)
+            .build();
+
+        assert_eq!(cpu.get_privilege_level(), PrivilegeLevel::Supervisor);
+        assert_eq!(cpu.pc, 0x1000);
+        assert_eq!(cpu.registers[2], 42);
+        assert_eq!(cpu.registers[1], 0);
+
+        let cpu = CpuBuilder::new().privilege(PrivilegeLevel::User).build();
+        assert_eq!(cpu.get_privilege_level(), PrivilegeLevel::User);
+    }
+
+    #[test]
+    fn test_halt_encoding_stops_step_loop() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+        memory.write_word(0x1000, ADDU_R1_1).unwrap();
+        memory.write_word(0x1004, decoder::HALT).unwrap();
+        memory.write_word(0x1008, ADDU_R1_1).unwrap();
+        cpu.pc = 0x1000;
+
+        // User mode may not halt the machine
+        assert_eq!(cpu.step(&mut memory), Ok(StepOutcome::Retired));
+        assert_eq!(
+            cpu.step(&mut memory),
+            Ok(StepOutcome::Exception {
+                vector: CPU::VECTOR_PRIVILEGE_VIOLATION,
+                address: None,
+            })
+        );
+        assert!(!cpu.halted);
+
+        cpu.clear_exceptions();
+        cpu.set_privilege_level(PrivilegeLevel::Supervisor);
+        cpu.pc = 0x1000;
+        assert_eq!(cpu.run(&mut memory, 10), Ok(StopReason::Halted));
+        assert!(cpu.halted);
+        assert_eq!(cpu.registers[1], 2);
+        assert_eq!(cpu.pc, 0x1008);
+    }
+
+    #[test]
+    fn test_halt_stops_run() {
+        use instructions::system::Halt;
+        use instructions::{Instruction, Operands};
+
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+        memory.write_word(0x1000, ADDU_R1_1).unwrap();
+        cpu.pc = 0x1000;
+
+        cpu.set_privilege_level(PrivilegeLevel::Supervisor);
+        Halt.execute(&mut cpu, &mut memory, &Operands::default());
+        assert!(cpu.halted);
+
+        assert_eq!(cpu.run(&mut memory, 10), Ok(StopReason::Halted));
+        assert_eq!(cpu.step(&mut memory), Ok(StepOutcome::Halted));
+        assert_eq!(cpu.pc, 0x1000);
+        assert_eq!(cpu.registers[1], 0);
+
+        // Clearing the flag resumes execution
+        cpu.halted = false;
+        assert_eq!(cpu.step(&mut memory), Ok(StepOutcome::Retired));
+        assert_eq!(cpu.registers[1], 1);
+    }
+
+    #[test]
+    fn test_clear_exceptions() {
+        let mut cpu = CPU::new();
+
+        cpu.cr0 = CPU::CR0_EQUAL | CPU::CR0_FP_LESS;
+        cpu.set_page_fault(0x1000);
+        cpu.set_privilege_violation();
+        cpu.cr0 |= CPU::CR0_BOUNDS_CHECK;
+
+        assert_eq!(
+            cpu.pending_exceptions(),
+            vec![
+                ExceptionKind::BoundsCheck,
+                ExceptionKind::PageFault,
+                ExceptionKind::PrivilegeViolation,
+            ]
+        );
+
+        cpu.clear_exceptions();
+        assert!(cpu.pending_exceptions().is_empty());
+        assert_eq!(cpu.cr0, CPU::CR0_EQUAL | CPU::CR0_FP_LESS);
+    }
+
+    #[test]
+    fn test_register_accessors() {
+        let mut cpu = CPU::new();
+
+        assert_eq!(cpu.set_reg(31, 0xDEAD_BEEF), Ok(()));
+        assert_eq!(cpu.reg(31), Ok(0xDEAD_BEEF));
+        assert_eq!(cpu.registers[31], 0xDEAD_BEEF);
+
+        assert_eq!(cpu.reg(32), Err(CpuError::InvalidRegister(32)));
+        assert_eq!(cpu.set_reg(32, 1), Err(CpuError::InvalidRegister(32)));
+    }
+
+    #[test]
+    fn test_effective_address() {
+        let mut cpu = CPU::new();
+        cpu.registers[1] = 0x1000;
+        cpu.registers[2] = 0xFFFF_FFF0;
+        cpu.registers[3] = 5;
+
+        assert_eq!(cpu.effective_address(1, -8), 0x0FF8);
+        assert_eq!(cpu.effective_address(2, 0x20), 0x10);
+        assert_eq!(cpu.overflowing_effective_address(1, -8), (0x0FF8, false));
+        assert_eq!(cpu.overflowing_effective_address(2, 0x20), (0x10, true));
+        assert_eq!(
+            cpu.overflowing_effective_address(3, -8),
+            (0xFFFF_FFFD, true)
+        );
+        assert_eq!(cpu.indexed_address(1, 3, 4), 0x1014);
+        assert_eq!(cpu.indexed_address(2, 3, 4), 0x4);
+        assert_eq!(cpu.overflowing_indexed_address(1, 3, 4), (0x1014, false));
+        assert_eq!(cpu.overflowing_indexed_address(2, 3, 4), (0x4, true));
+        assert_eq!(
+            cpu.overflowing_indexed_address(3, 2, 4),
+            (0xFFFF_FFC5, true)
+        );
+    }
+
+    #[test]
+    fn test_dump_registers() {
+        let mut cpu = CPU::new();
+        cpu.registers[7] = 0xCAFE_F00D;
+        cpu.pc = 0x0001_2340;
+
+        assert_eq!(cpu.registers_iter().count(), 32);
+        assert_eq!(cpu.registers_iter().nth(7), Some((7, 0xCAFE_F00D)));
+
+        let dump = cpu.dump_registers();
+        for idx in 0..32 {
+            assert!(
+                dump.contains(&format!("r{:<2} = ", idx)),
+                "missing r{}",
+                idx
+            );
+        }
+        assert!(dump.contains("r7  = cafef00d"));
+        assert!(dump.contains("pc  = 00012340"));
+        assert_eq!(dump.lines().count(), 9);
+    }
+
+    #[test]
+    fn test_set_fp_flag() {
+        let mut cpu = CPU::new();
 
         // Test setting a single flag
         cpu.set_fp_flag(CPU::CR0_FP_OVERFLOW);
@@ -238,11 +1931,39 @@ mod tests {
         assert!(!cpu.mmu_enabled());
 
         // Test MMU fault flags
-        cpu.set_page_fault();
+        cpu.set_page_fault(0x1000);
         assert_eq!(cpu.cr0 & CPU::CR0_PAGE_FAULT, CPU::CR0_PAGE_FAULT);
+        assert_eq!(cpu.fault_addr, 0x1000);
 
-        cpu.set_write_protect_fault();
+        cpu.set_write_protect_fault(0x2000);
         assert_eq!(cpu.cr0 & CPU::CR0_WRITE_PROTECT, CPU::CR0_WRITE_PROTECT);
+        assert_eq!(cpu.fault_addr, 0x2000);
+    }
+
+    #[test]
+    fn test_mmu_control_enables_translation() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        // Identity-map page 1 and leave the rest unmapped
+        memory.set_page_table_base(0x10000);
+        memory
+            .write_physical_u32(0x10004, PageTableEntry::new(0x1000).to_u32())
+            .unwrap();
+        memory.write_word(0x1000, ADDU_R1_1).unwrap();
+        memory.write_word(0x1004, ADDU_R1_1).unwrap();
+
+        cpu.mmu_control |= CPU::MMU_ENABLE;
+        cpu.pc = 0x1000;
+        assert_eq!(cpu.step(&mut memory), Ok(StepOutcome::Retired));
+        assert!(memory.is_mmu_enabled());
+
+        // Disabling through memory is reflected back in the control register
+        memory.set_mmu_enabled(false);
+        cpu.sync_mmu(&mut memory);
+        assert!(!cpu.mmu_enabled());
+        assert_eq!(cpu.step(&mut memory), Ok(StepOutcome::Retired));
+        assert!(!memory.is_mmu_enabled());
     }
 
     #[test]
@@ -280,8 +2001,8 @@ mod tests {
         let mut cpu = CPU::new();
 
         // Test multiple error conditions
-        cpu.set_page_fault();
-        cpu.set_write_protect_fault();
+        cpu.set_page_fault(0x1000);
+        cpu.set_write_protect_fault(0x1000);
         cpu.set_privilege_violation();
 
         assert_ne!(cpu.cr0 & CPU::CR0_PAGE_FAULT, 0);
@@ -294,4 +2015,825 @@ mod tests {
         assert_ne!(cpu.cr0 & CPU::CR0_PAGE_FAULT, 0);
         assert_ne!(cpu.cr0 & CPU::CR0_WRITE_PROTECT, 0);
     }
+
+    /// addu r1, r1, 1
+    const ADDU_R1_1: u32 = 0x6021_0001;
+
+    #[test]
+    fn test_step_executes_and_advances() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        memory.write_word(0x1000, ADDU_R1_1).unwrap();
+        memory.write_word(0x1004, ADDU_R1_1).unwrap();
+        cpu.pc = 0x1000;
+
+        cpu.step(&mut memory).unwrap();
+        cpu.step(&mut memory).unwrap();
+        assert_eq!(cpu.registers[1], 2);
+        assert_eq!(cpu.pc, 0x1008);
+    }
+
+    #[test]
+    fn test_step_unimplemented_opcode() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        memory.write_word(0x1000, 0xFFFF_FFFF).unwrap();
+        cpu.pc = 0x1000;
+
+        cpu.step(&mut memory).unwrap();
+        assert_eq!(cpu.vector, CPU::VECTOR_UNIMPLEMENTED_OPCODE);
+        assert_eq!(
+            cpu.pc,
+            cpu.exception_handler_address(CPU::VECTOR_UNIMPLEMENTED_OPCODE)
+        );
+        assert_eq!(cpu.sxip, 0x1000);
+    }
+
+    #[test]
+    fn test_illegal_instruction_handler() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        memory.write_word(0x1000, 0xFFFF_FFFF).unwrap();
+        memory.write_word(0x1004, 0xFFFF_FFFE).unwrap();
+        cpu.pc = 0x1000;
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let sink = Rc::clone(&seen);
+        cpu.set_illegal_instruction_handler(Box::new(move |word| {
+            sink.borrow_mut().push(word);
+            if word == 0xFFFF_FFFF {
+                IllegalAction::Skip
+            } else {
+                IllegalAction::Halt
+            }
+        }));
+
+        assert_eq!(cpu.step(&mut memory), Ok(StepOutcome::Retired));
+        assert_eq!(cpu.pc, 0x1004);
+        assert_eq!(cpu.cr0, 0);
+        assert_eq!(cpu.step(&mut memory), Ok(StepOutcome::Halted));
+        assert_eq!(cpu.pc, 0x1004);
+        assert_eq!(*seen.borrow(), vec![0xFFFF_FFFF, 0xFFFF_FFFE]);
+
+        // Without a handler the exception is raised
+        cpu.clear_illegal_instruction_handler();
+        cpu.halted = false;
+        cpu.step(&mut memory).unwrap();
+        assert_eq!(cpu.vector, CPU::VECTOR_UNIMPLEMENTED_OPCODE);
+    }
+
+    #[test]
+    fn test_privileged_instructions_fault_in_user_mode() {
+        let words = [
+            0x8040_4020, // ldcr r2, cr1
+            0x8001_8020, // stcr r1, cr1
+            0xF400_FC00, // rte
+        ];
+
+        for word in words {
+            let mut cpu = CPU::new();
+            let mut memory = Memory::new();
+            memory.write_word(0x1000, word).unwrap();
+            cpu.pc = 0x1000;
+            cpu.sxip = 0x2000;
+            cpu.registers[1] = 0x3000;
+            cpu.registers[2] = 0xDEAD;
+
+            assert_eq!(
+                cpu.step(&mut memory),
+                Ok(StepOutcome::Exception {
+                    vector: CPU::VECTOR_PRIVILEGE_VIOLATION,
+                    address: None,
+                }),
+                "{:08x}",
+                word
+            );
+            assert_eq!(cpu.sxip, 0x1000, "{:08x}", word);
+            assert_eq!(cpu.registers[2], 0xDEAD, "{:08x}", word);
+            assert_eq!(cpu.get_privilege_level(), PrivilegeLevel::Supervisor);
+        }
+    }
+
+    #[test]
+    fn test_float_register_helpers() {
+        let mut cpu = CPU::new();
+
+        cpu.set_freg_s(3, -1.25);
+        assert_eq!(cpu.registers[3], (-1.25f32).to_bits());
+        assert_eq!(cpu.freg_s(3), -1.25);
+
+        cpu.set_freg_d(4, std::f64::consts::PI);
+        let bits = std::f64::consts::PI.to_bits();
+        assert_eq!(cpu.registers[4], (bits >> 32) as u32);
+        assert_eq!(cpu.registers[5], bits as u32);
+        assert_eq!(cpu.freg_d(4), std::f64::consts::PI);
+
+        cpu.set_freg_d(6, f64::NEG_INFINITY);
+        assert_eq!(cpu.freg_d(6), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_vector_base_relocates_exceptions() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        memory.write_word(0x1000, 0x8001_80E0).unwrap(); // stcr r1, cr7
+        memory.write_word(0x1004, 0xFFFF_FFFF).unwrap();
+        cpu.set_privilege_level(PrivilegeLevel::Supervisor);
+        cpu.registers[1] = 0x4000;
+        cpu.pc = 0x1000;
+
+        cpu.step(&mut memory).unwrap();
+        assert_eq!(cpu.vbr, 0x4000);
+        assert_eq!(cpu.control_register(CPU::CR_VBR), Some(0x4000));
+
+        cpu.step(&mut memory).unwrap();
+        assert_eq!(cpu.vector, CPU::VECTOR_UNIMPLEMENTED_OPCODE);
+        assert_eq!(
+            cpu.pc,
+            0x4000 + CPU::VECTOR_UNIMPLEMENTED_OPCODE as u32 * CPU::VECTOR_SLOT_SIZE
+        );
+    }
+
+    #[test]
+    fn test_masked_interrupt_does_not_fire() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        memory.write_word(0x1000, ADDU_R1_1).unwrap();
+        cpu.pc = 0x1000;
+
+        cpu.assert_interrupt(3);
+        cpu.step(&mut memory).unwrap();
+
+        assert_eq!(cpu.registers[1], 1);
+        assert_eq!(cpu.pc, 0x1004);
+        assert_eq!(cpu.get_privilege_level(), PrivilegeLevel::User);
+        assert_ne!(cpu.interrupt_pending & (1 << 3), 0);
+    }
+
+    #[test]
+    fn test_unmasked_interrupt_vectors() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        memory.write_word(0x1000, ADDU_R1_1).unwrap();
+        cpu.pc = 0x1000;
+
+        cpu.set_interrupt_enabled(3, true);
+        cpu.assert_interrupt(3);
+        cpu.step(&mut memory).unwrap();
+
+        // The interrupt is taken before the instruction executes
+        assert_eq!(cpu.registers[1], 0);
+        assert_eq!(cpu.vector, CPU::VECTOR_INTERRUPT);
+        assert_eq!(cpu.interrupt_line, 3);
+        assert_eq!(cpu.pc, cpu.exception_handler_address(CPU::VECTOR_INTERRUPT));
+        assert_eq!(cpu.sxip, 0x1000);
+        assert_eq!(cpu.get_privilege_level(), PrivilegeLevel::Supervisor);
+        assert_eq!(cpu.interrupt_pending, 0);
+    }
+
+    #[test]
+    fn test_interrupt_globally_disabled() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        memory.write_word(0x1000, ADDU_R1_1).unwrap();
+        cpu.pc = 0x1000;
+        cpu.cr0 |= CPU::CR0_INTERRUPT_DISABLE;
+
+        cpu.set_interrupt_enabled(0, true);
+        cpu.assert_interrupt(0);
+        cpu.step(&mut memory).unwrap();
+
+        assert_eq!(cpu.registers[1], 1);
+        assert_eq!(cpu.pc, 0x1004);
+    }
+
+    #[test]
+    fn test_interrupt_deferred_in_delay_slot() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        memory.write_word(0x1000, ADDU_R1_1).unwrap();
+        cpu.pc = 0x1000;
+        cpu.branch_delayed(0x2000);
+
+        cpu.set_interrupt_enabled(5, true);
+        cpu.assert_interrupt(5);
+
+        // The delay slot retires and the branch completes first
+        cpu.step(&mut memory).unwrap();
+        assert_eq!(cpu.registers[1], 1);
+        assert_eq!(cpu.pc, 0x2000);
+        assert!(!cpu.in_delay_slot());
+
+        // The interrupt is delivered at the next instruction boundary
+        cpu.step(&mut memory).unwrap();
+        assert_eq!(cpu.vector, CPU::VECTOR_INTERRUPT);
+        assert_eq!(cpu.sxip, 0x2000);
+    }
+
+    #[test]
+    fn test_trace_hook() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        memory.write_word(0x1000, ADDU_R1_1).unwrap();
+        memory.write_word(0x1004, ADDU_R1_1).unwrap();
+        // subu r1, r1, 1
+        memory.write_word(0x1008, 0x6421_0001).unwrap();
+        cpu.pc = 0x1000;
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let sink = Rc::clone(&events);
+        cpu.set_trace_hook(Box::new(move |event| sink.borrow_mut().push(event.clone())));
+
+        for _ in 0..3 {
+            cpu.step(&mut memory).unwrap();
+        }
+
+        let events = events.borrow();
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events.iter().map(|e| e.pc).collect::<Vec<_>>(),
+            vec![0x1000, 0x1004, 0x1008]
+        );
+        assert_eq!(events[0].word, ADDU_R1_1);
+        assert_eq!(events[0].mnemonic, "addu");
+        assert_eq!(events[2].mnemonic, "subu");
+    }
+
+    #[test]
+    fn test_clear_trace_hook() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        memory.write_word(0x1000, ADDU_R1_1).unwrap();
+        memory.write_word(0x1004, ADDU_R1_1).unwrap();
+        cpu.pc = 0x1000;
+
+        let count = Rc::new(RefCell::new(0));
+        let counter = Rc::clone(&count);
+        cpu.set_trace_hook(Box::new(move |_| *counter.borrow_mut() += 1));
+
+        cpu.step(&mut memory).unwrap();
+        cpu.clear_trace_hook();
+        cpu.step(&mut memory).unwrap();
+
+        assert_eq!(*count.borrow(), 1);
+        assert_eq!(cpu.registers[1], 2);
+    }
+
+    #[test]
+    fn test_run_stops_at_breakpoint() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        for i in 0..5 {
+            memory.write_word(0x1000 + i * 4, ADDU_R1_1).unwrap();
+        }
+        cpu.pc = 0x1000;
+        cpu.add_breakpoint(0x1008);
+
+        assert_eq!(
+            cpu.run(&mut memory, 100).unwrap(),
+            StopReason::Breakpoint(0x1008)
+        );
+        assert_eq!(cpu.pc, 0x1008);
+        assert_eq!(cpu.registers[1], 2);
+    }
+
+    #[test]
+    fn test_run_continues_after_breakpoint_removed() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        for i in 0..5 {
+            memory.write_word(0x1000 + i * 4, ADDU_R1_1).unwrap();
+        }
+        cpu.pc = 0x1000;
+        cpu.add_breakpoint(0x1008);
+        assert_eq!(
+            cpu.run(&mut memory, 100).unwrap(),
+            StopReason::Breakpoint(0x1008)
+        );
+
+        cpu.remove_breakpoint(0x1008);
+        assert!(!cpu.has_breakpoint(0x1008));
+        assert_eq!(cpu.run(&mut memory, 3).unwrap(), StopReason::StepLimit);
+        assert_eq!(cpu.pc, 0x1014);
+        assert_eq!(cpu.registers[1], 5);
+    }
+
+    #[test]
+    fn test_run_stops_on_watchpoint_write() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        memory.write_word(0x1000, ADDU_R1_1).unwrap();
+        // st r1, r2, 0
+        memory.write_word(0x1004, 0x2422_0000).unwrap();
+        memory.write_word(0x1008, ADDU_R1_1).unwrap();
+        cpu.pc = 0x1000;
+        cpu.registers[2] = 0x2000;
+        memory.write_word(0x2000, 0xDEAD_BEEF).unwrap();
+        memory.add_watchpoint(0x2000, 4);
+
+        assert_eq!(
+            cpu.run(&mut memory, 100).unwrap(),
+            StopReason::Watchpoint {
+                addr: 0x2000,
+                old: 0xDEAD_BEEF,
+                new: 1,
+            }
+        );
+        assert_eq!(cpu.pc, 0x1008);
+        assert_eq!(memory.read_word(0x2000).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_step_outcome_retired() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        memory.write_word(0x1000, ADDU_R1_1).unwrap();
+        cpu.pc = 0x1000;
+
+        assert_eq!(cpu.step(&mut memory), Ok(StepOutcome::Retired));
+        assert_eq!(cpu.instret, 1);
+    }
+
+    #[test]
+    fn test_status() {
+        let mut cpu = CpuBuilder::new().supervisor().pc(0x2000).build();
+        cpu.set_page_fault(0x1234);
+        cpu.cr0 |= CPU::CR0_FP_LESS;
+
+        let status = cpu.status();
+        assert_eq!(status.pc, 0x2000);
+        assert_eq!(status.privilege_level, PrivilegeLevel::Supervisor);
+        assert!(!status.mmu_enabled);
+        assert_eq!(status.exceptions, vec![ExceptionKind::PageFault]);
+        assert_eq!(status.fp_compare, Some(FpCompare::Less));
+
+        let text = status.to_string();
+        assert!(text.starts_with(&cpu.dump_registers()));
+        assert!(text.contains("privilege = Supervisor  mmu = off"));
+        assert!(text.contains("exceptions = [PageFault]"));
+        assert!(text.contains("fp compare = Less"));
+    }
+
+    #[test]
+    fn test_psr_round_trip() {
+        let status = ProcessorStatus {
+            equal: true,
+            less: true,
+            greater: true,
+            unordered: true,
+            fp_divide_zero: true,
+            fp_inexact: true,
+            fp_invalid: true,
+            fp_overflow: true,
+            fp_underflow: true,
+            fp_compare: Some(FpCompare::Greater),
+            bounds_check: true,
+            trap: true,
+            page_fault: true,
+            write_protect: true,
+            privilege_violation: true,
+            divide_zero: true,
+            overflow: true,
+            instruction_fault: true,
+            interrupt_disable: true,
+        };
+
+        let mut cpu = CPU::new();
+        cpu.set_psr(status);
+        assert_eq!(
+            cpu.cr0,
+            ((1 << 22) - 1) & !(CPU::CR0_FP_COMPARE_MASK & !CPU::CR0_FP_GREATER)
+        );
+        assert_eq!(cpu.psr(), status);
+
+        // Fields left at their default clear the corresponding bits
+        cpu.set_psr(ProcessorStatus {
+            interrupt_disable: true,
+            ..Default::default()
+        });
+        assert_eq!(cpu.cr0, CPU::CR0_INTERRUPT_DISABLE);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+        cpu.registers[5] = 0xDEAD_BEEF;
+        cpu.pc = 0x4000;
+        cpu.cr0 = CPU::CR0_EQUAL | CPU::CR0_OVERFLOW;
+        cpu.fault_addr = 0x1234;
+        cpu.interrupt_mask = 0xF;
+        cpu.set_mmu_enabled(true);
+        cpu.sync_mmu(&mut memory);
+        cpu.add_breakpoint(0x100);
+
+        cpu.reset();
+        assert_eq!(cpu.registers, [0; 32]);
+        assert_eq!(cpu.pc, cpu.exception_handler_address(CPU::VECTOR_RESET));
+        assert_eq!(cpu.cr0, CPU::CR0_INTERRUPT_DISABLE);
+        assert_eq!(cpu.get_privilege_level(), PrivilegeLevel::Supervisor);
+        assert_eq!((cpu.fault_addr, cpu.interrupt_mask), (0, 0));
+        assert!(!cpu.mmu_enabled());
+        assert!(cpu.has_breakpoint(0x100));
+
+        // The disabled MMU reaches memory on the next sync
+        cpu.sync_mmu(&mut memory);
+        assert!(!memory.is_mmu_enabled());
+    }
+
+    #[test]
+    fn test_step_back_restores_state() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+        memory
+            .load_words(
+                0x1000,
+                &[
+                    0x6021_0001, // addu r1, r1, 1
+                    0x2420_2000, // st r1, r0, 0x2000
+                    0x6041_0005, // addu r2, r1, 5
+                ],
+            )
+            .unwrap();
+        memory.write_word(0x2000, 0xDEAD_BEEF).unwrap();
+        cpu.pc = 0x1000;
+        cpu.registers[1] = 41;
+        cpu.set_journaling(true);
+
+        let mut states = Vec::new();
+        for _ in 0..3 {
+            states.push((format!("{:?}", cpu), memory.read_word(0x2000).unwrap()));
+            cpu.step(&mut memory).unwrap();
+        }
+        assert_eq!(cpu.registers[2], 47);
+        assert_eq!(memory.read_word(0x2000).unwrap(), 42);
+
+        while let Some((state, word)) = states.pop() {
+            assert!(cpu.step_back(&mut memory));
+            assert_eq!(format!("{:?}", cpu), state);
+            assert_eq!(memory.read_word(0x2000).unwrap(), word);
+        }
+        assert!(!cpu.step_back(&mut memory));
+
+        // Stepping again after reverting re-executes the same instructions
+        for _ in 0..3 {
+            cpu.step(&mut memory).unwrap();
+        }
+        assert_eq!(cpu.registers[2], 47);
+        assert_eq!(cpu.instret, 3);
+    }
+
+    #[test]
+    fn test_step_back_restores_translation_state() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+        memory.install_identity_mapping(0x10_0000, 16).unwrap();
+        memory
+            .load_words(
+                0x1000,
+                &[
+                    0x8001_8080, // stcr r1, cr4
+                    0x1420_2000, // ld r1, r0, 0x2000
+                ],
+            )
+            .unwrap();
+        cpu.pc = 0x1000;
+        cpu.registers[1] = 0x10_0000;
+        cpu.set_privilege_level(PrivilegeLevel::Supervisor);
+        cpu.set_journaling(true);
+
+        cpu.step(&mut memory).unwrap();
+        assert_eq!(memory.page_table_base(), 0x10_0000);
+        memory.set_mmu_enabled(true);
+        let pte = memory.read_physical_u32(0x10_0008).unwrap();
+        cpu.step(&mut memory).unwrap();
+        assert_ne!(memory.read_physical_u32(0x10_0008).unwrap(), pte);
+
+        // The load's accessed bit is cleared again
+        assert!(cpu.step_back(&mut memory));
+        assert_eq!(memory.read_physical_u32(0x10_0008).unwrap(), pte);
+
+        assert!(cpu.step_back(&mut memory));
+        assert_eq!(memory.page_table_base(), 0);
+        assert_eq!(cpu.ptbr, 0);
+    }
+
+    #[test]
+    fn test_access_log_records_step_pc() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+        // st r1, r0, 0x2000
+        memory.load_words(0x1000, &[0x2420_2000]).unwrap();
+        memory.set_access_log(true);
+        cpu.pc = 0x1000;
+        cpu.step(&mut memory).unwrap();
+
+        // Accesses after the step are not attributed to it
+        memory.read_word(0x2000).unwrap();
+        let pcs: Vec<_> = memory.access_log().iter().map(|r| r.pc).collect();
+        assert_eq!(pcs, [Some(0x1000), None]);
+    }
+
+    #[test]
+    fn test_execute_word() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+        cpu.pc = 0x1000;
+        cpu.registers[2] = 40;
+
+        // addu r3, r2, 2
+        assert_eq!(cpu.execute_word(0x6062_0002, &mut memory), Ok(()));
+        assert_eq!(cpu.registers[3], 42);
+        assert_eq!(cpu.pc, 0x1000);
+        assert_eq!(cpu.instret, 0);
+
+        // jmp r1 does not move the program counter
+        cpu.registers[1] = 0x8000;
+        assert_eq!(cpu.execute_word(0xF400_C001, &mut memory), Ok(()));
+        assert_eq!(cpu.pc, 0x1000);
+
+        assert_eq!(
+            cpu.execute_word(0xFFFF_FFFF, &mut memory),
+            Err(ExecError::UnimplementedOpcode)
+        );
+        assert_eq!(cpu.registers[3], 42);
+    }
+
+    #[test]
+    fn test_execute_word_does_not_take_exceptions() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+        cpu.pc = 0x1000;
+        cpu.sxip = 0x4000;
+
+        // divu r3, r2, r4 with r4 = 0
+        assert_eq!(
+            cpu.execute_word(0xF462_6804, &mut memory),
+            Err(ExecError::DivideByZero)
+        );
+        assert_ne!(cpu.cr0 & CPU::CR0_DIVIDE_ZERO, 0);
+        assert_eq!(cpu.get_privilege_level(), PrivilegeLevel::User);
+        assert_eq!(cpu.pc, 0x1000);
+        assert_eq!(cpu.sxip, 0x4000);
+        assert_eq!(cpu.exception_taken, None);
+    }
+
+    #[test]
+    fn test_trace_steps() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        memory.write_word(0x1000, ADDU_R1_1).unwrap();
+        memory.write_word(0x1004, decoder::NOP).unwrap();
+        memory.write_word(0x1008, 0x6041_0010).unwrap(); // addu r2, r1, 0x10
+        cpu.pc = 0x1000;
+        cpu.registers[1] = 4;
+
+        let records = cpu.trace_steps(&mut memory, 3);
+        assert_eq!(
+            records,
+            vec![
+                StepRecord {
+                    pc: 0x1000,
+                    mnemonic: Some("addu"),
+                    deltas: vec![RegisterDelta {
+                        reg: 1,
+                        old: 4,
+                        new: 5,
+                    }],
+                },
+                StepRecord {
+                    pc: 0x1004,
+                    mnemonic: Some("nop"),
+                    deltas: vec![],
+                },
+                StepRecord {
+                    pc: 0x1008,
+                    mnemonic: Some("addu"),
+                    deltas: vec![RegisterDelta {
+                        reg: 2,
+                        old: 0,
+                        new: 0x15,
+                    }],
+                },
+            ]
+        );
+        assert_eq!(cpu.pc, 0x100C);
+    }
+
+    #[test]
+    fn test_page_fault_restarts_load() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        // Code and the vector table are mapped; the data page at 0x20000 is not
+        memory.install_identity_mapping(0x10_0000, 16).unwrap();
+        memory.set_page_table_base(0x10_0000);
+        memory.write_physical_u32(0x2_0000, 0x1234_5678).unwrap();
+        memory.write_word(0x1000, 0x1462_0000).unwrap(); // ld r3, r2, 0
+        let handler = cpu.exception_handler_address(CPU::VECTOR_DATA_ACCESS);
+        memory.write_word(handler, 0xF400_FC00).unwrap(); // rte
+        cpu.set_mmu_enabled(true);
+        cpu.pc = 0x1000;
+        cpu.registers[2] = 0x2_0000;
+        cpu.registers[3] = 0xDEAD;
+
+        assert_eq!(
+            cpu.step(&mut memory),
+            Ok(StepOutcome::Exception {
+                vector: CPU::VECTOR_DATA_ACCESS,
+                address: Some(0x2_0000),
+            })
+        );
+        // Nothing was committed and the restart address is the load itself
+        assert_eq!(cpu.registers[3], 0xDEAD);
+        assert_eq!(cpu.sxip, 0x1000);
+        assert_eq!(cpu.pc, handler);
+        assert_eq!(cpu.instret, 0);
+
+        // The handler maps the page and returns to the load
+        memory
+            .write_physical_u32(0x10_0000 + 0x20 * 4, PageTableEntry::new(0x2_0000).to_u32())
+            .unwrap();
+        cpu.clear_exceptions();
+        assert_eq!(cpu.step(&mut memory), Ok(StepOutcome::BranchTaken(0x1000)));
+        assert_eq!(cpu.get_privilege_level(), PrivilegeLevel::User);
+
+        assert_eq!(cpu.step(&mut memory), Ok(StepOutcome::Retired));
+        assert_eq!(cpu.registers[3], 0x1234_5678);
+        assert_eq!(cpu.pc, 0x1004);
+    }
+
+    #[test]
+    fn test_repeated_page_fault_with_flag_left_set() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        // The data page at 0x20000 stays unmapped and the handler returns
+        // without clearing cr0
+        memory.install_identity_mapping(0x10_0000, 16).unwrap();
+        memory.set_page_table_base(0x10_0000);
+        memory.write_word(0x1000, 0x1462_0000).unwrap(); // ld r3, r2, 0
+        let handler = cpu.exception_handler_address(CPU::VECTOR_DATA_ACCESS);
+        memory.write_word(handler, 0xF400_FC00).unwrap(); // rte
+        cpu.set_mmu_enabled(true);
+        cpu.pc = 0x1000;
+        cpu.registers[2] = 0x2_0000;
+
+        let fault = Ok(StepOutcome::Exception {
+            vector: CPU::VECTOR_DATA_ACCESS,
+            address: Some(0x2_0000),
+        });
+        assert_eq!(cpu.step(&mut memory), fault);
+        assert_ne!(cpu.cr0 & CPU::CR0_PAGE_FAULT, 0);
+        assert_eq!(cpu.step(&mut memory), Ok(StepOutcome::BranchTaken(0x1000)));
+
+        assert_eq!(cpu.step(&mut memory), fault);
+        assert_eq!(cpu.sxip, 0x1000);
+        assert_eq!(cpu.pc, handler);
+        assert_eq!(cpu.instret, 1);
+    }
+
+    #[test]
+    fn test_step_nop() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        memory.write_word(0x1000, decoder::NOP).unwrap();
+        cpu.pc = 0x1000;
+        cpu.registers[5] = 0x55;
+        cpu.cr0 = CPU::CR0_EQUAL;
+        let registers = cpu.registers;
+
+        assert_eq!(cpu.step(&mut memory), Ok(StepOutcome::Retired));
+        assert_eq!(cpu.pc, 0x1004);
+        assert_eq!(cpu.instret, 1);
+        assert_eq!(cpu.registers, registers);
+        assert_eq!(cpu.cr0, CPU::CR0_EQUAL);
+    }
+
+    #[test]
+    fn test_step_outcome_branch() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        // jmp r1
+        memory.write_word(0x1000, 0xF400_C001).unwrap();
+        cpu.pc = 0x1000;
+        cpu.registers[1] = 0x3000;
+
+        assert_eq!(cpu.step(&mut memory), Ok(StepOutcome::BranchTaken(0x3000)));
+        assert_eq!(cpu.pc, 0x3000);
+    }
+
+    #[test]
+    fn test_step_outcome_faulting_load() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        // ld r2, r1, 0
+        memory.write_word(0x1000, 0x1441_0000).unwrap();
+        cpu.pc = 0x1000;
+        cpu.registers[1] = 0xFFFF_0000;
+
+        assert_eq!(
+            cpu.step(&mut memory),
+            Ok(StepOutcome::Exception {
+                vector: CPU::VECTOR_DATA_ACCESS,
+                address: Some(0xFFFF_0000),
+            })
+        );
+        assert_eq!(cpu.sxip, 0x1000);
+        assert_eq!(
+            cpu.pc,
+            cpu.exception_handler_address(CPU::VECTOR_DATA_ACCESS)
+        );
+    }
+
+    #[test]
+    fn test_step_outcome_fetch_fault() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        cpu.pc = 0xFFFF_0000;
+
+        assert_eq!(
+            cpu.step(&mut memory),
+            Ok(StepOutcome::Exception {
+                vector: CPU::VECTOR_INSTRUCTION_ACCESS,
+                address: Some(0xFFFF_0000),
+            })
+        );
+    }
+
+    #[test]
+    fn test_step_fetch_from_unmapped_page() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        // Empty page table: every virtual page is unmapped
+        memory.set_page_table_base(0x1000);
+        memory.set_mmu_enabled(true);
+        cpu.pc = 0x0040_0000;
+
+        assert_eq!(
+            cpu.step(&mut memory),
+            Ok(StepOutcome::Exception {
+                vector: CPU::VECTOR_INSTRUCTION_ACCESS,
+                address: Some(0x0040_0000),
+            })
+        );
+        assert_ne!(cpu.cr0 & CPU::CR0_INSTR_FAULT, 0);
+        assert_eq!(cpu.cr0 & CPU::CR0_PAGE_FAULT, 0);
+    }
+
+    #[test]
+    fn test_step_outcome_bounds_trap() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        // tbnd r1, r2
+        memory.write_word(0x1000, 0xF401_F802).unwrap();
+        cpu.pc = 0x1000;
+        cpu.registers[1] = 10;
+        cpu.registers[2] = 5;
+
+        assert_eq!(
+            cpu.step(&mut memory),
+            Ok(StepOutcome::Exception {
+                vector: CPU::VECTOR_BOUNDS_CHECK,
+                address: None,
+            })
+        );
+        assert_eq!(
+            cpu.pc,
+            cpu.exception_handler_address(CPU::VECTOR_BOUNDS_CHECK)
+        );
+    }
+
+    #[test]
+    fn test_step_misaligned_pc() {
+        let mut cpu = CPU::new();
+        let mut memory = Memory::new();
+
+        cpu.pc = 0x1002;
+        assert_eq!(
+            cpu.step(&mut memory),
+            Err(EmulationError::MisalignedPc(0x1002))
+        );
+    }
 }
@@ -0,0 +1,217 @@
+//! ELF program loader for the Motorola 88000 emulator.
+//!
+//! This module loads statically linked ELF32 big-endian executables built for
+//! the 88000 (`EM_88K`) into physical memory:
+//! - Header validation (class, byte order, machine type)
+//! - Copying of `PT_LOAD` segments to their physical addresses
+//! - Zeroing of the uninitialized (BSS) part of each segment
+
+use crate::memory::{Memory, MemoryError};
+use std::fmt;
+
+/// ELF machine type for the Motorola 88000
+pub const EM_88K: u16 = 5;
+
+/// Program header type of a loadable segment
+const PT_LOAD: u32 = 1;
+/// Size of the ELF32 file header
+const EHDR_SIZE: usize = 52;
+/// Size of an ELF32 program header
+const PHDR_SIZE: usize = 32;
+
+/// ELF loader error types
+#[derive(Debug)]
+pub enum ElfError {
+    /// The file ends before a header or segment it describes
+    Truncated,
+    /// The file does not start with the ELF magic number
+    BadMagic,
+    /// The file is not a 32-bit ELF file
+    NotElf32,
+    /// The file is not big-endian
+    WrongEndianness,
+    /// The file targets a different machine type
+    WrongMachine(u16),
+    /// A segment could not be copied into memory
+    Memory(MemoryError),
+}
+
+impl fmt::Display for ElfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElfError::Truncated => write!(f, "ELF file is truncated"),
+            ElfError::BadMagic => write!(f, "not an ELF file (bad magic number)"),
+            ElfError::NotElf32 => write!(f, "not a 32-bit ELF file"),
+            ElfError::WrongEndianness => write!(f, "ELF file is not big-endian"),
+            ElfError::WrongMachine(machine) => {
+                write!(f, "ELF machine type {} is not EM_88K ({})", machine, EM_88K)
+            }
+            ElfError::Memory(err) => write!(f, "segment does not fit in memory: {:?}", err),
+        }
+    }
+}
+
+impl From<MemoryError> for ElfError {
+    fn from(err: MemoryError) -> Self {
+        ElfError::Memory(err)
+    }
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Result<u16, ElfError> {
+    let field = bytes.get(offset..offset + 2).ok_or(ElfError::Truncated)?;
+    Ok(u16::from_be_bytes([field[0], field[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, ElfError> {
+    let field = bytes.get(offset..offset + 4).ok_or(ElfError::Truncated)?;
+    Ok(u32::from_be_bytes([field[0], field[1], field[2], field[3]]))
+}
+
+/// Loads an ELF32 big-endian 88000 executable into physical memory.
+///
+/// # Arguments
+///
+/// * `memory` - The memory to load the program segments into
+/// * `bytes` - The contents of the ELF file
+///
+/// # Returns
+///
+/// The program entry point, to be placed in `pc`.
+pub fn load_elf(memory: &mut Memory, bytes: &[u8]) -> Result<u32, ElfError> {
+    if bytes.len() < EHDR_SIZE {
+        return Err(ElfError::Truncated);
+    }
+    if bytes[0..4] != [0x7F, b'E', b'L', b'F'] {
+        return Err(ElfError::BadMagic);
+    }
+    if bytes[4] != 1 {
+        return Err(ElfError::NotElf32);
+    }
+    if bytes[5] != 2 {
+        return Err(ElfError::WrongEndianness);
+    }
+    let machine = read_u16(bytes, 18)?;
+    if machine != EM_88K {
+        return Err(ElfError::WrongMachine(machine));
+    }
+
+    let entry = read_u32(bytes, 24)?;
+    let phoff = read_u32(bytes, 28)? as usize;
+    let phentsize = read_u16(bytes, 42)? as usize;
+    let phnum = read_u16(bytes, 44)? as usize;
+    if phnum > 0 && phentsize < PHDR_SIZE {
+        return Err(ElfError::Truncated);
+    }
+
+    for index in 0..phnum {
+        let header = phoff + index * phentsize;
+        if read_u32(bytes, header)? != PT_LOAD {
+            continue;
+        }
+        let offset = read_u32(bytes, header + 4)? as usize;
+        let paddr = read_u32(bytes, header + 12)?;
+        let filesz = read_u32(bytes, header + 16)? as usize;
+        let memsz = read_u32(bytes, header + 20)? as usize;
+
+        let data = bytes
+            .get(offset..offset + filesz)
+            .ok_or(ElfError::Truncated)?;
+        memory.load_bytes(paddr, data)?;
+
+        if memsz > filesz {
+            let bss = paddr.wrapping_add(filesz as u32);
+            memory.fill(bss, memsz - filesz, 0)?;
+        }
+    }
+
+    Ok(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds an ELF file with a single loadable segment.
+    fn build_elf(machine: u16, paddr: u32, code: &[u8], memsz: u32) -> Vec<u8> {
+        let mut elf = vec![0u8; EHDR_SIZE + PHDR_SIZE];
+        elf[0..4].copy_from_slice(&[0x7F, b'E', b'L', b'F']);
+        elf[4] = 1; // ELFCLASS32
+        elf[5] = 2; // ELFDATA2MSB
+        elf[6] = 1; // EV_CURRENT
+        elf[16..18].copy_from_slice(&2u16.to_be_bytes()); // ET_EXEC
+        elf[18..20].copy_from_slice(&machine.to_be_bytes());
+        elf[20..24].copy_from_slice(&1u32.to_be_bytes());
+        elf[24..28].copy_from_slice(&paddr.to_be_bytes());
+        elf[28..32].copy_from_slice(&(EHDR_SIZE as u32).to_be_bytes());
+        elf[40..42].copy_from_slice(&(EHDR_SIZE as u16).to_be_bytes());
+        elf[42..44].copy_from_slice(&(PHDR_SIZE as u16).to_be_bytes());
+        elf[44..46].copy_from_slice(&1u16.to_be_bytes());
+
+        let ph = EHDR_SIZE;
+        let offset = (EHDR_SIZE + PHDR_SIZE) as u32;
+        elf[ph..ph + 4].copy_from_slice(&PT_LOAD.to_be_bytes());
+        elf[ph + 4..ph + 8].copy_from_slice(&offset.to_be_bytes());
+        elf[ph + 8..ph + 12].copy_from_slice(&paddr.to_be_bytes());
+        elf[ph + 12..ph + 16].copy_from_slice(&paddr.to_be_bytes());
+        elf[ph + 16..ph + 20].copy_from_slice(&(code.len() as u32).to_be_bytes());
+        elf[ph + 20..ph + 24].copy_from_slice(&memsz.to_be_bytes());
+
+        elf.extend_from_slice(code);
+        elf
+    }
+
+    #[test]
+    fn test_load_elf_segment() {
+        let mut memory = Memory::new();
+        memory.load_words(0x4008, &[0xFFFFFFFF]).unwrap();
+
+        let code = [0x60, 0x21, 0x00, 0x01, 0x60, 0x21, 0x00, 0x02];
+        let elf = build_elf(EM_88K, 0x4000, &code, 12);
+
+        let entry = load_elf(&mut memory, &elf).unwrap();
+        assert_eq!(entry, 0x4000);
+        assert_eq!(memory.read_word(0x4000).unwrap(), 0x60210001);
+        assert_eq!(memory.read_word(0x4004).unwrap(), 0x60210002);
+        // BSS is zeroed
+        assert_eq!(memory.read_word(0x4008).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_load_elf_wrong_machine() {
+        let mut memory = Memory::new();
+        let elf = build_elf(3, 0x4000, &[0; 4], 4);
+
+        assert!(matches!(
+            load_elf(&mut memory, &elf),
+            Err(ElfError::WrongMachine(3))
+        ));
+    }
+
+    #[test]
+    fn test_load_elf_wrong_endianness() {
+        let mut memory = Memory::new();
+        let mut elf = build_elf(EM_88K, 0x4000, &[0; 4], 4);
+        elf[5] = 1; // ELFDATA2LSB
+
+        assert!(matches!(
+            load_elf(&mut memory, &elf),
+            Err(ElfError::WrongEndianness)
+        ));
+    }
+
+    #[test]
+    fn test_load_elf_bad_magic() {
+        let mut memory = Memory::new();
+        let mut elf = build_elf(EM_88K, 0x4000, &[0; 4], 4);
+        elf[0] = 0;
+
+        assert!(matches!(
+            load_elf(&mut memory, &elf),
+            Err(ElfError::BadMagic)
+        ));
+        assert!(matches!(
+            load_elf(&mut memory, &elf[..10]),
+            Err(ElfError::Truncated)
+        ));
+    }
+}
@@ -34,6 +34,8 @@
 //!
 //! - [`cpu`]: Core CPU implementation including registers and control flags
 //! - [`memory`]: Memory management and MMU implementation
+//! - `elf`: ELF32 program loader (requires the `elf` feature)
+//! - `asm`: Line assembler for a subset of the instruction set (requires the `asm` feature)
 //!
 //! The CPU module contains submodules for different instruction categories:
 //!
@@ -55,7 +57,11 @@
 //! For more information about specific components, see the module-level
 //! documentation.
 
+#[cfg(feature = "asm")]
+pub mod asm;
 pub mod cpu;
+#[cfg(feature = "elf")]
+pub mod elf;
 pub mod memory;
 
 // Re-export main types for convenience
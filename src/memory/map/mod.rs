@@ -0,0 +1,306 @@
+//! Named regions of the physical address space.
+//!
+//! A [`MemoryMap`] models a board with RAM, ROM and memory-mapped devices at
+//! distinct address ranges. Each [`Region`] has a name, a base address and a
+//! size, and accesses are routed to the region covering the address:
+//! - RAM regions are readable and writable
+//! - ROM regions are read-only and fault on write
+//! - Device regions forward every access to a [`Device`]
+//!
+//! [`Memory`](crate::memory::Memory) consults its map before the main RAM, so
+//! `Memory::new()` behaves as a single RAM region.
+
+use crate::memory::MemoryError;
+use std::fmt;
+
+/// A memory-mapped device occupying a region of the physical address space
+///
+/// Offsets are relative to the base of the device's region. Devices are
+/// accessed a byte at a time: a half-word or word access to a device region
+/// makes one call per byte, in address order, so a register whose read has
+/// side effects sees them once for each byte accessed.
+pub trait Device: fmt::Debug + Send {
+    /// Reads the byte at `offset`
+    fn read(&mut self, offset: u32) -> u8;
+
+    /// Writes the byte at `offset`
+    fn write(&mut self, offset: u32, value: u8);
+}
+
+/// Kind of a mapped region
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionKind {
+    /// Readable and writable memory
+    Ram,
+    /// Read-only memory; writes fault with `MemoryError::WriteProtection`
+    Rom,
+    /// Memory-mapped device registers
+    Device,
+}
+
+/// Storage behind a region
+#[derive(Debug)]
+enum Backing {
+    Ram(Vec<u8>),
+    Rom(Vec<u8>),
+    Device(Box<dyn Device>),
+}
+
+/// A named range of the physical address space
+#[derive(Debug)]
+pub struct Region {
+    name: String,
+    base: u32,
+    size: u32,
+    backing: Backing,
+}
+
+impl Region {
+    /// Returns the region's name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the first physical address of the region
+    pub fn base(&self) -> u32 {
+        self.base
+    }
+
+    /// Returns the size of the region in bytes
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Returns the kind of the region
+    pub fn kind(&self) -> RegionKind {
+        match self.backing {
+            Backing::Ram(_) => RegionKind::Ram,
+            Backing::Rom(_) => RegionKind::Rom,
+            Backing::Device(_) => RegionKind::Device,
+        }
+    }
+
+    /// Checks whether the region covers a physical address
+    pub fn contains(&self, addr: u32) -> bool {
+        addr.wrapping_sub(self.base) < self.size
+    }
+
+    /// Returns the contents of a RAM or ROM region
+    pub fn data(&self) -> Option<&[u8]> {
+        match &self.backing {
+            Backing::Ram(data) | Backing::Rom(data) => Some(data),
+            Backing::Device(_) => None,
+        }
+    }
+}
+
+/// Set of non-overlapping named regions
+#[derive(Debug, Default)]
+pub struct MemoryMap {
+    regions: Vec<Region>,
+}
+
+impl MemoryMap {
+    /// Creates an empty memory map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps `size` bytes of zeroed RAM at `base`
+    ///
+    /// Fails with `MemoryError::InvalidAddress` if the region is empty, runs
+    /// past the top of the address space or overlaps an existing region.
+    pub fn add_ram(&mut self, name: &str, base: u32, size: u32) -> Result<(), MemoryError> {
+        self.insert(name, base, size, Backing::Ram(vec![0; size as usize]))
+    }
+
+    /// Maps a ROM holding `image` at `base`
+    ///
+    /// Fails like [`MemoryMap::add_ram`].
+    pub fn add_rom(&mut self, name: &str, base: u32, image: Vec<u8>) -> Result<(), MemoryError> {
+        let size = u32::try_from(image.len()).map_err(|_| MemoryError::InvalidAddress(base))?;
+        self.insert(name, base, size, Backing::Rom(image))
+    }
+
+    /// Maps a device over `size` bytes at `base`
+    ///
+    /// Fails like [`MemoryMap::add_ram`].
+    pub fn add_device(
+        &mut self,
+        name: &str,
+        base: u32,
+        size: u32,
+        device: Box<dyn Device>,
+    ) -> Result<(), MemoryError> {
+        self.insert(name, base, size, Backing::Device(device))
+    }
+
+    fn insert(
+        &mut self,
+        name: &str,
+        base: u32,
+        size: u32,
+        backing: Backing,
+    ) -> Result<(), MemoryError> {
+        let end = base as u64 + size as u64;
+        let overlaps = self.regions.iter().any(|region| {
+            (base as u64) < region.base as u64 + region.size as u64 && (region.base as u64) < end
+        });
+        if size == 0 || end > 1 << 32 || overlaps {
+            return Err(MemoryError::InvalidAddress(base));
+        }
+        self.regions.push(Region {
+            name: name.to_string(),
+            base,
+            size,
+            backing,
+        });
+        Ok(())
+    }
+
+    /// Returns the mapped regions in the order they were added
+    pub fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    /// Returns the region with the given name
+    pub fn region(&self, name: &str) -> Option<&Region> {
+        self.regions.iter().find(|region| region.name == name)
+    }
+
+    /// Returns the region covering a physical address
+    pub fn region_at(&self, addr: u32) -> Option<&Region> {
+        self.regions.iter().find(|region| region.contains(addr))
+    }
+
+    /// Checks whether any region covers a physical address
+    pub fn contains(&self, addr: u32) -> bool {
+        self.region_at(addr).is_some()
+    }
+
+    fn region_at_mut(&mut self, addr: u32) -> Result<&mut Region, MemoryError> {
+        self.regions
+            .iter_mut()
+            .find(|region| region.contains(addr))
+            .ok_or(MemoryError::InvalidAddress(addr))
+    }
+
+    /// Reads a byte from the region covering a physical address
+    pub fn read_byte(&mut self, addr: u32) -> Result<u8, MemoryError> {
+        let region = self.region_at_mut(addr)?;
+        let offset = addr - region.base;
+        Ok(match &mut region.backing {
+            Backing::Ram(data) | Backing::Rom(data) => data[offset as usize],
+            Backing::Device(device) => device.read(offset),
+        })
+    }
+
+    /// Writes a byte to the region covering a physical address
+    ///
+    /// Writes to a ROM region fail with `MemoryError::WriteProtection`.
+    pub fn write_byte(&mut self, addr: u32, value: u8) -> Result<(), MemoryError> {
+        let region = self.region_at_mut(addr)?;
+        let offset = addr - region.base;
+        match &mut region.backing {
+            Backing::Ram(data) => data[offset as usize] = value,
+            Backing::Rom(_) => return Err(MemoryError::WriteProtection(addr)),
+            Backing::Device(device) => device.write(offset, value),
+        }
+        Ok(())
+    }
+
+    /// Reads a byte without side effects
+    ///
+    /// Device registers cannot be read without disturbing the device, so
+    /// device regions fail with `MemoryError::InvalidAddress`.
+    pub fn peek_byte(&self, addr: u32) -> Result<u8, MemoryError> {
+        self.region_at(addr)
+            .and_then(|region| Some(region.data()?[(addr - region.base) as usize]))
+            .ok_or(MemoryError::InvalidAddress(addr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Device that echoes offsets on read and records writes
+    #[derive(Debug, Default)]
+    struct Recorder {
+        writes: Arc<Mutex<Vec<(u32, u8)>>>,
+    }
+
+    impl Device for Recorder {
+        fn read(&mut self, offset: u32) -> u8 {
+            0x80 | offset as u8
+        }
+
+        fn write(&mut self, offset: u32, value: u8) {
+            self.writes.lock().unwrap().push((offset, value));
+        }
+    }
+
+    #[test]
+    fn test_rom_write_faults() {
+        let mut map = MemoryMap::new();
+        map.add_rom("boot", 0xFF80_0000, vec![0x12, 0x34]).unwrap();
+
+        assert_eq!(map.read_byte(0xFF80_0001).unwrap(), 0x34);
+        assert!(matches!(
+            map.write_byte(0xFF80_0001, 0),
+            Err(MemoryError::WriteProtection(0xFF80_0001))
+        ));
+        assert_eq!(map.peek_byte(0xFF80_0001).unwrap(), 0x34);
+    }
+
+    #[test]
+    fn test_accesses_dispatch_by_address() {
+        let writes = Arc::new(Mutex::new(Vec::new()));
+        let mut map = MemoryMap::new();
+        map.add_ram("ram", 0, 0x1000).unwrap();
+        map.add_rom("rom", 0x1000, vec![0xAA; 0x100]).unwrap();
+        map.add_device(
+            "uart",
+            0xFFF0_0000,
+            0x10,
+            Box::new(Recorder {
+                writes: Arc::clone(&writes),
+            }),
+        )
+        .unwrap();
+
+        map.write_byte(0x0FFF, 0x55).unwrap();
+        map.write_byte(0xFFF0_0004, 0x66).unwrap();
+        assert_eq!(map.read_byte(0x0FFF).unwrap(), 0x55);
+        assert_eq!(map.read_byte(0x1000).unwrap(), 0xAA);
+        assert_eq!(map.read_byte(0xFFF0_0003).unwrap(), 0x83);
+        assert_eq!(*writes.lock().unwrap(), [(4, 0x66)]);
+        assert_eq!(map.region("ram").unwrap().data().unwrap()[0x0FFF], 0x55);
+
+        assert_eq!(map.region_at(0x1000).unwrap().name(), "rom");
+        assert_eq!(
+            map.region_at(0xFFF0_000F).unwrap().kind(),
+            RegionKind::Device
+        );
+        assert!(map.region_at(0xFFF0_0010).is_none());
+        assert!(matches!(
+            map.read_byte(0x2000),
+            Err(MemoryError::InvalidAddress(0x2000))
+        ));
+        assert!(map.peek_byte(0xFFF0_0000).is_err());
+    }
+
+    #[test]
+    fn test_regions_must_not_overlap() {
+        let mut map = MemoryMap::new();
+        map.add_ram("low", 0x1000, 0x1000).unwrap();
+
+        assert!(map.add_ram("overlap", 0x1FFF, 0x10).is_err());
+        assert!(map.add_ram("empty", 0x4000, 0).is_err());
+        assert!(map.add_ram("wraps", 0xFFFF_FF00, 0x200).is_err());
+        map.add_ram("adjacent", 0x2000, 0x1000).unwrap();
+        map.add_ram("top", 0xFFFF_FF00, 0x100).unwrap();
+        assert_eq!(map.regions().len(), 3);
+    }
+}
@@ -5,6 +5,16 @@
 //! - Virtual memory translation
 //! - Page table management
 //! - Memory protection
+//! - Named RAM, ROM and device regions
+//! - Memory shared between cores (with the `shared-memory` feature)
+
+pub mod map;
+#[cfg(feature = "shared-memory")]
+pub mod shared;
+
+use crate::cpu::instructions::system::PrivilegeLevel;
+use map::{MemoryMap, RegionKind};
+use std::collections::{HashSet, VecDeque};
 
 /// Memory error types
 #[derive(Debug)]
@@ -12,6 +22,100 @@ pub enum MemoryError {
     PageFault(u32),
     WriteProtection(u32),
     InvalidAddress(u32),
+    /// Instruction fetch from an unmapped page
+    FetchFault(u32),
+    /// User mode access to a supervisor-only page
+    SupervisorViolation(u32),
+}
+
+impl MemoryError {
+    /// Returns the address that caused the error
+    pub fn address(&self) -> u32 {
+        match *self {
+            MemoryError::PageFault(addr)
+            | MemoryError::WriteProtection(addr)
+            | MemoryError::InvalidAddress(addr)
+            | MemoryError::FetchFault(addr)
+            | MemoryError::SupervisorViolation(addr) => addr,
+        }
+    }
+}
+
+/// Kind of memory access being translated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessType {
+    /// Instruction fetch
+    Fetch,
+    /// Data read
+    Read,
+    /// Data write
+    Write,
+}
+
+/// Byte order of multi-byte data accesses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Endian {
+    /// Most significant byte at the lowest address, as on the 88000
+    #[default]
+    Big,
+    /// Least significant byte at the lowest address
+    Little,
+}
+
+/// Access permitted to a protected physical range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtKind {
+    /// Every access faults
+    NoAccess,
+    /// Writes fault
+    ReadOnly,
+    /// Every access is permitted, lifting an earlier protection
+    ReadWrite,
+}
+
+/// A byte overwritten by an emulated store, as recorded by the write journal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteWrite {
+    /// Physical address of the byte
+    pub addr: u32,
+    /// Value before the write
+    pub old: u8,
+}
+
+/// A data access recorded by the memory access log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccessRecord {
+    /// Address of the instruction making the access, if known
+    pub pc: Option<u32>,
+    /// Virtual address of the first byte
+    pub addr: u32,
+    /// Physical address of the first byte
+    pub physical_addr: u32,
+    /// Number of bytes accessed
+    pub size: usize,
+    /// Whether the access was a write
+    pub is_write: bool,
+    /// The first (up to) eight bytes accessed, packed in memory order
+    pub value: u64,
+}
+
+/// A write to a watched range, with the range's value before and after
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchpointHit {
+    /// First address of the watched range
+    pub addr: u32,
+    /// The first (up to) eight bytes of the range before the write, packed in memory order
+    pub old: u64,
+    /// The first (up to) eight bytes of the range after the write, packed in memory order
+    pub new: u64,
+}
+
+/// Packs the first (up to) eight bytes of a span in memory order
+fn pack_bytes(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .take(8)
+        .fold(0u64, |value, &byte| (value << 8) | byte as u64)
 }
 
 /// Page table entry for virtual memory translation
@@ -21,6 +125,10 @@ pub struct PageTableEntry {
     pub valid: bool,
     pub writable: bool,
     pub supervisor: bool,
+    /// Set by the MMU when the page is accessed
+    pub accessed: bool,
+    /// Set by the MMU when the page is written
+    pub dirty: bool,
 }
 
 impl PageTableEntry {
@@ -30,6 +138,8 @@ impl PageTableEntry {
             valid: true,
             writable: true,
             supervisor: false,
+            accessed: false,
+            dirty: false,
         }
     }
 
@@ -44,17 +154,55 @@ impl PageTableEntry {
         if self.supervisor {
             value |= 1 << 2;
         }
+        if self.accessed {
+            value |= 1 << 3;
+        }
+        if self.dirty {
+            value |= 1 << 4;
+        }
         value
     }
 
+    /// Returns the valid, writable and supervisor bits as encoded in the entry
+    pub fn protection(&self) -> u32 {
+        self.to_u32() & 0x7
+    }
+
     pub fn from_u32(value: u32) -> Self {
         Self {
             physical_page: value & 0xFFFFF000,
             valid: value & (1 << 0) != 0,
             writable: value & (1 << 1) != 0,
             supervisor: value & (1 << 2) != 0,
+            accessed: value & (1 << 3) != 0,
+            dirty: value & (1 << 4) != 0,
         }
     }
+
+    /// Checks whether an access to `virtual_addr` through this entry is permitted
+    ///
+    /// Invalid pages always fault, supervisor pages are inaccessible in user
+    /// mode, and read-only pages cannot be written.
+    pub fn permits(
+        &self,
+        virtual_addr: u32,
+        access: AccessType,
+        priv_level: PrivilegeLevel,
+    ) -> Result<(), MemoryError> {
+        if !self.valid {
+            return Err(match access {
+                AccessType::Fetch => MemoryError::FetchFault(virtual_addr),
+                AccessType::Read | AccessType::Write => MemoryError::PageFault(virtual_addr),
+            });
+        }
+        if self.supervisor && priv_level != PrivilegeLevel::Supervisor {
+            return Err(MemoryError::SupervisorViolation(virtual_addr));
+        }
+        if access == AccessType::Write && !self.writable {
+            return Err(MemoryError::WriteProtection(virtual_addr));
+        }
+        Ok(())
+    }
 }
 
 /// Memory management unit for the Motorola 88000
@@ -62,22 +210,210 @@ impl PageTableEntry {
 pub struct Memory {
     /// Physical memory array
     memory: Vec<u8>,
+    /// Regions mapped over the physical address space, checked before `memory`
+    map: MemoryMap,
     /// MMU enabled flag
     pub(crate) mmu_enabled: bool,
     /// Page table base register
     page_table_base: u32,
+    /// Walk a segment table before the page table
+    two_level_paging: bool,
+    /// Watched ranges as (address, size in bytes) that report a hit when written
+    watchpoints: Vec<(u32, u32)>,
+    /// Most recent write to a watched range since the last check
+    watchpoint_hit: Option<WatchpointHit>,
+    /// Privilege level used for page protection checks
+    privilege_level: PrivilegeLevel,
+    /// Word addresses reserved by load-linked, as (core, address)
+    reservations: Vec<(usize, u32)>,
+    /// Processor performing the current accesses, which owns reservations
+    core: usize,
+    /// Wrap physical addresses past the end of memory instead of faulting
+    wrap_on_overflow: bool,
+    /// Allow effective addresses whose computation wrapped past 32 bits
+    address_wrap: bool,
+    /// Record fetched pages and flag writes to them
+    smc_detection: bool,
+    /// Virtual page numbers instructions have been fetched from
+    fetched_pages: HashSet<u32>,
+    /// A write landed in a page instructions were fetched from
+    smc_detected: bool,
+    /// Record written bytes and flag reads of bytes never written
+    uninit_tracking: bool,
+    /// One bit per physical byte, set once the byte has been written
+    written: Vec<u64>,
+    /// Address of the first read of a never-written byte
+    uninit_read: Option<u32>,
+    /// Byte order of word and half-word data accesses
+    data_endianness: Endian,
+    /// Protected physical ranges; later entries take precedence
+    protected_ranges: Vec<(std::ops::Range<u32>, ProtKind)>,
+    /// Record data accesses in `access_log`
+    access_logging: bool,
+    /// Most recent data accesses, oldest first
+    access_log: VecDeque<AccessRecord>,
+    /// Address of the executing instruction, attached to logged accesses
+    current_pc: Option<u32>,
+    /// Bytes overwritten since the write journal began, oldest first
+    write_journal: Option<Vec<ByteWrite>>,
 }
 
 impl Memory {
+    /// Number of accesses kept by the memory access log
+    pub const ACCESS_LOG_CAPACITY: usize = 4096;
+
     /// Creates a new memory instance with default 16MB of RAM
     pub fn new() -> Self {
         Self {
             memory: vec![0; 16 * 1024 * 1024],
+            map: MemoryMap::new(),
             mmu_enabled: false,
             page_table_base: 0,
+            two_level_paging: false,
+            watchpoints: Vec::new(),
+            watchpoint_hit: None,
+            privilege_level: PrivilegeLevel::default(),
+            reservations: Vec::new(),
+            core: 0,
+            wrap_on_overflow: false,
+            address_wrap: true,
+            smc_detection: false,
+            fetched_pages: HashSet::new(),
+            smc_detected: false,
+            uninit_tracking: false,
+            written: Vec::new(),
+            uninit_read: None,
+            data_endianness: Endian::Big,
+            protected_ranges: Vec::new(),
+            access_logging: false,
+            access_log: VecDeque::new(),
+            current_pc: None,
+            write_journal: None,
         }
     }
 
+    /// Creates a new memory instance whose zero page is protected
+    ///
+    /// Any access to the first 4KB of physical memory faults, which catches
+    /// null pointer dereferences.
+    pub fn with_guard_page() -> Self {
+        let mut memory = Self::new();
+        memory.protect_range(0, 0x1000, ProtKind::NoAccess);
+        memory
+    }
+
+    /// Creates a new memory instance filled with a poison byte
+    ///
+    /// Reads of memory the program never wrote return a recognizable pattern
+    /// such as 0xCC instead of zero, which makes use of uninitialized data
+    /// stand out.
+    pub fn with_poison(byte: u8) -> Self {
+        let mut memory = Self::new();
+        memory.memory.fill(byte);
+        memory
+    }
+
+    /// Creates a new memory instance with the regions of `map` over the default RAM
+    ///
+    /// Physical addresses covered by a region are routed to it, and all
+    /// others fall through to the 16MB of RAM at address 0.
+    pub fn with_map(map: MemoryMap) -> Self {
+        Self { map, ..Self::new() }
+    }
+
+    /// Returns the regions mapped over physical memory
+    pub fn map(&self) -> &MemoryMap {
+        &self.map
+    }
+
+    /// Returns the regions mapped over physical memory for modification
+    ///
+    /// The physical accessors (`load_bytes`, `physical_slice`, ...), page
+    /// table walks and uninitialized read tracking only cover the main RAM.
+    pub fn map_mut(&mut self) -> &mut MemoryMap {
+        &mut self.map
+    }
+
+    /// Restricts access to a range of physical memory
+    ///
+    /// The protection applies whether or not the MMU is enabled, on top of
+    /// any page protection. Loads through the physical accessors
+    /// (`load_bytes`, `physical_slice_mut`, ...) are not affected.
+    pub fn protect_range(&mut self, start: u32, len: u32, kind: ProtKind) {
+        let end = start.saturating_add(len);
+        self.protected_ranges.push((start..end, kind));
+    }
+
+    /// Adds a write watchpoint on the `size` bytes starting at `addr`
+    ///
+    /// Any write overlapping the range reports a hit carrying the range's
+    /// value before and after the write. Adding a watchpoint at an already
+    /// watched address replaces its size.
+    pub fn add_watchpoint(&mut self, addr: u32, size: u32) {
+        self.remove_watchpoint(addr);
+        self.watchpoints.push((addr, size.max(1)));
+    }
+
+    /// Removes the write watchpoint starting at an address
+    pub fn remove_watchpoint(&mut self, addr: u32) {
+        self.watchpoints.retain(|&(watched, _)| watched != addr);
+    }
+
+    /// Returns and clears the most recent watched write since the last call
+    pub fn take_watchpoint_hit(&mut self) -> Option<WatchpointHit> {
+        self.watchpoint_hit.take()
+    }
+
+    /// Finds a watchpoint whose range overlaps `len` bytes starting at `addr`
+    fn overlapping_watchpoint(&self, addr: u32, len: usize) -> Option<(u32, u32)> {
+        let end = addr as u64 + len as u64;
+        self.watchpoints.iter().copied().find(|&(start, size)| {
+            (start as u64) < end && (addr as u64) < start as u64 + size as u64
+        })
+    }
+
+    /// Reads the packed value of a watched range, or zero if it is not readable
+    fn watched_value(&self, addr: u32, size: u32) -> u64 {
+        let mut bytes = vec![0; size.min(8) as usize];
+        match self.peek_bytes(addr, &mut bytes) {
+            Ok(()) => pack_bytes(&bytes),
+            Err(_) => 0,
+        }
+    }
+
+    /// Records a load-linked reservation on the word containing `addr`
+    ///
+    /// The reservation belongs to the current core and replaces any it held.
+    pub fn reserve(&mut self, addr: u32) {
+        let core = self.core;
+        self.reservations.retain(|&(owner, _)| owner != core);
+        self.reservations.push((core, addr & !3));
+    }
+
+    /// Clears the current core's reservation, returning whether it was held on the word containing `addr`
+    ///
+    /// A reservation is lost when any byte of the reserved word is written,
+    /// by any core.
+    pub fn take_reservation(&mut self, addr: u32) -> bool {
+        let core = self.core;
+        match self
+            .reservations
+            .iter()
+            .position(|&(owner, _)| owner == core)
+        {
+            Some(index) => self.reservations.swap_remove(index).1 == addr & !3,
+            None => false,
+        }
+    }
+
+    /// Sets the processor that subsequent accesses are performed for
+    ///
+    /// Each core holds its own load-linked reservation. Memory used by a
+    /// single processor can leave this at the default core 0.
+    pub fn set_core(&mut self, core: usize) {
+        self.core = core;
+    }
+
     /// Sets the MMU enabled state
     pub fn set_mmu_enabled(&mut self, enabled: bool) {
         self.mmu_enabled = enabled;
@@ -88,13 +424,193 @@ impl Memory {
         self.mmu_enabled
     }
 
+    /// Sets the privilege level that page protection is checked against
+    pub fn set_privilege_level(&mut self, level: PrivilegeLevel) {
+        self.privilege_level = level;
+    }
+
+    /// Selects whether physical addresses past the end of memory wrap around
+    ///
+    /// When disabled, such accesses fault with `MemoryError::InvalidAddress`.
+    pub fn set_wrap_on_overflow(&mut self, enabled: bool) {
+        self.wrap_on_overflow = enabled;
+    }
+
+    /// Selects whether effective addresses may wrap around the address space
+    ///
+    /// Enabled by default, so `base + offset` is computed modulo 2^32. When
+    /// disabled, an address whose computation wrapped past either end of
+    /// the 32-bit space faults with `MemoryError::InvalidAddress`.
+    pub fn set_address_wrap(&mut self, enabled: bool) {
+        self.address_wrap = enabled;
+    }
+
+    /// Checks an effective address against the address wrap setting
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - The effective address
+    /// * `wrapped` - Whether computing the address wrapped past 32 bits
+    pub fn check_address_wrap(&self, addr: u32, wrapped: bool) -> Result<u32, MemoryError> {
+        if wrapped && !self.address_wrap {
+            return Err(MemoryError::InvalidAddress(addr));
+        }
+        Ok(addr)
+    }
+
+    /// Sets the byte order of word and half-word data accesses
+    ///
+    /// Affects [`Memory::read_word`], [`Memory::write_word`],
+    /// [`Memory::read_half`] and [`Memory::write_half`]. Instruction fetch
+    /// is always big-endian.
+    pub fn set_data_endianness(&mut self, endian: Endian) {
+        self.data_endianness = endian;
+    }
+
+    /// Enables or disables the memory access log
+    ///
+    /// While enabled, every successful data read and write is recorded, up
+    /// to the most recent [`Memory::ACCESS_LOG_CAPACITY`] accesses.
+    /// Instruction fetches and the physical accessors are not logged.
+    /// Changing the setting clears the log.
+    pub fn set_access_log(&mut self, enabled: bool) {
+        self.access_logging = enabled;
+        self.access_log.clear();
+    }
+
+    /// Returns the logged data accesses, oldest first
+    pub fn access_log(&mut self) -> &[AccessRecord] {
+        self.access_log.make_contiguous()
+    }
+
+    /// Sets the instruction address attached to subsequently logged accesses
+    pub fn set_current_pc(&mut self, pc: Option<u32>) {
+        self.current_pc = pc;
+    }
+
+    fn log_access(&mut self, addr: u32, physical_addr: usize, bytes: &[u8], is_write: bool) {
+        if !self.access_logging {
+            return;
+        }
+        if self.access_log.len() == Self::ACCESS_LOG_CAPACITY {
+            self.access_log.pop_front();
+        }
+        let value = pack_bytes(bytes);
+        self.access_log.push_back(AccessRecord {
+            pc: self.current_pc,
+            addr,
+            physical_addr: physical_addr as u32,
+            size: bytes.len(),
+            is_write,
+            value,
+        });
+    }
+
+    /// Starts recording the previous value of every byte written by emulated stores
+    ///
+    /// Page table entries updated by translation are recorded too. Writes through the physical accessors (`load_bytes`,
+    /// `physical_slice_mut`, ...) are not recorded. Any journal already in
+    /// progress is discarded.
+    pub fn begin_write_journal(&mut self) {
+        self.write_journal = Some(Vec::new());
+    }
+
+    /// Stops recording and returns the bytes overwritten since [`Memory::begin_write_journal`]
+    pub fn end_write_journal(&mut self) -> Vec<ByteWrite> {
+        self.write_journal.take().unwrap_or_default()
+    }
+
+    /// Restores journaled bytes to their previous values, newest first
+    pub fn undo_writes(&mut self, writes: &[ByteWrite]) {
+        for write in writes.iter().rev() {
+            if self.map.contains(write.addr) {
+                // Only RAM regions are journaled, so the write cannot fault
+                let _ = self.map.write_byte(write.addr, write.old);
+            } else if let Some(byte) = self.memory.get_mut(write.addr as usize) {
+                *byte = write.old;
+            }
+        }
+    }
+
+    /// Enables or disables self-modifying code detection
+    ///
+    /// While enabled, pages that instructions are fetched from are recorded
+    /// and a write to any of them sets the flag reported by
+    /// [`Memory::smc_detected`]. Changing the setting clears the recorded
+    /// pages and the flag.
+    pub fn set_smc_detection(&mut self, enabled: bool) {
+        self.smc_detection = enabled;
+        self.fetched_pages.clear();
+        self.smc_detected = false;
+    }
+
+    /// Checks whether a write has landed in a page instructions were fetched from
+    pub fn smc_detected(&self) -> bool {
+        self.smc_detected
+    }
+
+    /// Enables or disables tracking of reads from uninitialized memory
+    ///
+    /// While enabled, every written byte is recorded, including loads through
+    /// the physical accessors, and the first data read or instruction fetch
+    /// of a byte that was never written is reported by
+    /// [`Memory::uninit_read`]. Writes made before tracking was enabled are
+    /// not recorded. Changing the setting clears the recorded bytes and the
+    /// flag.
+    pub fn set_uninit_tracking(&mut self, enabled: bool) {
+        self.uninit_tracking = enabled;
+        self.written = if enabled {
+            vec![0; self.memory.len().div_ceil(64)]
+        } else {
+            Vec::new()
+        };
+        self.uninit_read = None;
+    }
+
+    /// Returns the address of the first read from a never-written byte
+    pub fn uninit_read(&self) -> Option<u32> {
+        self.uninit_read
+    }
+
+    /// Records physical bytes as written
+    fn mark_written(&mut self, range: std::ops::Range<usize>) {
+        if self.uninit_tracking {
+            for index in range {
+                self.written[index / 64] |= 1 << (index % 64);
+            }
+        }
+    }
+
+    /// Flags a read of a physical byte that was never written
+    fn check_initialized(&mut self, addr: u32, index: usize) {
+        if self.uninit_tracking
+            && self.uninit_read.is_none()
+            && self.written[index / 64] & (1 << (index % 64)) == 0
+        {
+            self.uninit_read = Some(addr);
+        }
+    }
+
     /// Sets the page table base register
     pub fn set_page_table_base(&mut self, base: u32) {
         self.page_table_base = base & 0xFFFFF000;
     }
 
+    /// Returns the page table base register
+    pub fn page_table_base(&self) -> u32 {
+        self.page_table_base
+    }
+
+    /// Selects two-level (segment then page) or single-level translation
+    ///
+    /// With two-level paging the page table base points at a segment table
+    /// whose entries hold the addresses of the page tables.
+    pub fn set_two_level_paging(&mut self, enabled: bool) {
+        self.two_level_paging = enabled;
+    }
+
     /// Reads a word from physical memory
-    pub fn read_physical_u32(&mut self, addr: u32) -> Result<u32, MemoryError> {
+    pub fn read_physical_u32(&self, addr: u32) -> Result<u32, MemoryError> {
         let addr = addr as usize;
         if addr + 3 >= self.memory.len() {
             return Err(MemoryError::InvalidAddress(addr as u32));
@@ -116,57 +632,403 @@ impl Memory {
         self.memory[addr + 1] = ((value >> 16) & 0xFF) as u8;
         self.memory[addr + 2] = ((value >> 8) & 0xFF) as u8;
         self.memory[addr + 3] = (value & 0xFF) as u8;
+        self.mark_written(addr..addr + 4);
         Ok(())
     }
 
+    /// Writes a single-level page table mapping the first `num_pages` pages to themselves
+    ///
+    /// Each entry at `pt_base` is valid and writable and accessible in user
+    /// mode. The page table base itself is left for the caller to set. More
+    /// pages than the 2^20 in the address space fail with
+    /// `MemoryError::InvalidAddress`.
+    pub fn install_identity_mapping(
+        &mut self,
+        pt_base: u32,
+        num_pages: u32,
+    ) -> Result<(), MemoryError> {
+        if num_pages > 1 << 20 {
+            return Err(MemoryError::InvalidAddress(pt_base));
+        }
+        for page in 0..num_pages {
+            let pte = PageTableEntry::new(page << 12);
+            self.write_physical_u32(pt_base.wrapping_add(page * 4), pte.to_u32())?;
+        }
+        Ok(())
+    }
+
+    /// Copies a byte slice into physical memory, bypassing translation
+    pub fn load_bytes(&mut self, addr: u32, data: &[u8]) -> Result<(), MemoryError> {
+        self.physical_slice_mut(addr, data.len())?
+            .copy_from_slice(data);
+        Ok(())
+    }
+
+    /// Fills a range of physical memory with a byte, bypassing translation
+    pub fn fill(&mut self, addr: u32, len: usize, byte: u8) -> Result<(), MemoryError> {
+        self.physical_slice_mut(addr, len)?.fill(byte);
+        Ok(())
+    }
+
+    /// Fills a range of physical memory with a big-endian word pattern, bypassing translation
+    pub fn fill_word(&mut self, addr: u32, len_words: usize, word: u32) -> Result<(), MemoryError> {
+        let len = len_words
+            .checked_mul(4)
+            .ok_or(MemoryError::InvalidAddress(addr))?;
+        let pattern = word.to_be_bytes();
+        for chunk in self.physical_slice_mut(addr, len)?.chunks_exact_mut(4) {
+            chunk.copy_from_slice(&pattern);
+        }
+        Ok(())
+    }
+
+    /// Returns a view of physical memory, bypassing translation
+    pub fn physical_slice(&self, start: u32, len: usize) -> Result<&[u8], MemoryError> {
+        let range = self.physical_range(start, len)?;
+        Ok(&self.memory[range])
+    }
+
+    /// Returns a mutable view of physical memory, bypassing translation
+    ///
+    /// Writes through the slice do not trigger watchpoints. With
+    /// uninitialized read tracking enabled the whole range counts as written.
+    pub fn physical_slice_mut(&mut self, start: u32, len: usize) -> Result<&mut [u8], MemoryError> {
+        let range = self.physical_range(start, len)?;
+        self.mark_written(range.clone());
+        Ok(&mut self.memory[range])
+    }
+
+    fn physical_range(
+        &self,
+        start: u32,
+        len: usize,
+    ) -> Result<std::ops::Range<usize>, MemoryError> {
+        let begin = start as usize;
+        let end = begin
+            .checked_add(len)
+            .filter(|&end| end <= self.memory.len())
+            .ok_or(MemoryError::InvalidAddress(start))?;
+        Ok(begin..end)
+    }
+
+    /// Copies words into physical memory in big-endian byte order
+    pub fn load_words(&mut self, addr: u32, words: &[u32]) -> Result<(), MemoryError> {
+        let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_be_bytes()).collect();
+        self.load_bytes(addr, &bytes)
+    }
+
     /// Translates a virtual address to a physical address
-    pub fn translate_address(&mut self, virtual_addr: u32) -> Result<usize, MemoryError> {
+    ///
+    /// Accesses are checked against the page protection with
+    /// [`PageTableEntry::permits`]. With the MMU enabled, the page's
+    /// `accessed` bit (and `dirty` bit, for writes) is set and the updated
+    /// entry is written back to the page table.
+    pub fn translate_address(
+        &mut self,
+        virtual_addr: u32,
+        access: AccessType,
+    ) -> Result<usize, MemoryError> {
+        let pte = if self.mmu_enabled {
+            let (pte_addr, mut pte) = self.walk_page_table(virtual_addr, access)?;
+            let write = access == AccessType::Write;
+            if !pte.accessed || (write && !pte.dirty) {
+                pte.accessed = true;
+                pte.dirty |= write;
+                if let Some(journal) = &mut self.write_journal {
+                    // The walk read this entry, so it lies within RAM
+                    let old = &self.memory[pte_addr as usize..pte_addr as usize + 4];
+                    journal.extend(
+                        old.iter()
+                            .zip(pte_addr..)
+                            .map(|(&old, addr)| ByteWrite { addr, old }),
+                    );
+                }
+                self.write_physical_u32(pte_addr, pte.to_u32())?;
+            }
+            pte
+        } else {
+            PageTableEntry::new(virtual_addr)
+        };
+        Ok(Self::physical_address(&pte, virtual_addr))
+    }
+
+    /// Resolves the page table entry mapping a virtual address
+    ///
+    /// This only reads the page table, so it never needs exclusive access to
+    /// memory and leaves the accessed and dirty bits untouched. With the MMU
+    /// disabled the identity mapping is reported as a valid, writable user page.
+    pub fn lookup_page(
+        &self,
+        virtual_addr: u32,
+        access: AccessType,
+    ) -> Result<PageTableEntry, MemoryError> {
         if !self.mmu_enabled {
-            return Ok(virtual_addr as usize);
+            return Ok(PageTableEntry::new(virtual_addr));
         }
+        self.walk_page_table(virtual_addr, access)
+            .map(|(_, pte)| pte)
+    }
 
-        let page_index = (virtual_addr >> 12) as usize;
-        let pte_addr = self.page_table_base + (page_index as u32 * 4);
+    /// Walks the page tables, returning the physical address of the entry and the entry
+    fn walk_page_table(
+        &self,
+        virtual_addr: u32,
+        access: AccessType,
+    ) -> Result<(u32, PageTableEntry), MemoryError> {
+        // Table addresses come from guest-controlled registers and entries,
+        // so an entry past the top of the address space is a fault
+        let entry_addr = |table: u32, index: u32| {
+            table
+                .checked_add(index * 4)
+                .ok_or(MemoryError::PageFault(virtual_addr))
+        };
+        let pte_addr = if self.two_level_paging {
+            let segment_index = virtual_addr >> 22;
+            let ste_addr = entry_addr(self.page_table_base, segment_index)?;
+            let ste = PageTableEntry::from_u32(self.read_physical_u32(ste_addr)?);
+            ste.permits(virtual_addr, access, self.privilege_level)?;
+
+            let page_index = (virtual_addr >> 12) & 0x3FF;
+            entry_addr(ste.physical_page, page_index)?
+        } else {
+            let page_index = virtual_addr >> 12;
+            entry_addr(self.page_table_base, page_index)?
+        };
         let pte_value = self.read_physical_u32(pte_addr)?;
         let pte = PageTableEntry::from_u32(pte_value);
 
-        if !pte.valid {
-            return Err(MemoryError::PageFault(virtual_addr));
-        }
+        pte.permits(virtual_addr, access, self.privilege_level)?;
+        Ok((pte_addr, pte))
+    }
 
+    fn physical_address(pte: &PageTableEntry, virtual_addr: u32) -> usize {
         let offset = virtual_addr & 0xFFF;
-        Ok((pte.physical_page as usize & 0xFFFFF000) | (offset as usize))
+        (pte.physical_page as usize & 0xFFFFF000) | (offset as usize)
     }
 
     /// Reads a byte from memory
     pub fn read_byte(&mut self, addr: u32) -> Result<u8, MemoryError> {
-        let physical_addr = self.translate_address(addr)?;
-        Ok(self.memory[physical_addr])
+        let mut byte = [0];
+        self.read_bytes(addr, &mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn read_byte_as(&mut self, addr: u32, access: AccessType) -> Result<u8, MemoryError> {
+        let index = self.physical_index(addr, access)?;
+        self.load_physical(addr, index)
+    }
+
+    /// Reads the byte at a physical index from the region covering it or RAM
+    fn load_physical(&mut self, addr: u32, index: usize) -> Result<u8, MemoryError> {
+        if self.map.contains(index as u32) {
+            return self.map.read_byte(index as u32);
+        }
+        self.check_initialized(addr, index);
+        Ok(self.memory[index])
+    }
+
+    /// Resolves a virtual address to an index into physical memory
+    fn physical_index(&mut self, addr: u32, access: AccessType) -> Result<usize, MemoryError> {
+        let physical_addr = self.translate_address(addr, access)?;
+        if let Some(region) = self.map.region_at(physical_addr as u32) {
+            if region.kind() == RegionKind::Rom && access == AccessType::Write {
+                return Err(MemoryError::WriteProtection(addr));
+            }
+            self.check_protected_range(addr, physical_addr as u32, access)?;
+            return Ok(physical_addr);
+        }
+        let index = if physical_addr < self.memory.len() {
+            physical_addr
+        } else if self.wrap_on_overflow && !self.memory.is_empty() {
+            physical_addr % self.memory.len()
+        } else {
+            return Err(MemoryError::InvalidAddress(addr));
+        };
+        self.check_protected_range(addr, index as u32, access)?;
+        Ok(index)
+    }
+
+    /// Checks an access against the protected physical ranges
+    fn check_protected_range(
+        &self,
+        addr: u32,
+        physical_addr: u32,
+        access: AccessType,
+    ) -> Result<(), MemoryError> {
+        let kind = self
+            .protected_ranges
+            .iter()
+            .rev()
+            .find(|(range, _)| range.contains(&physical_addr))
+            .map(|&(_, kind)| kind);
+        match (kind, access) {
+            (Some(ProtKind::NoAccess), AccessType::Fetch) => Err(MemoryError::FetchFault(addr)),
+            (Some(ProtKind::NoAccess), _) => Err(MemoryError::PageFault(addr)),
+            (Some(ProtKind::ReadOnly), AccessType::Write) => {
+                Err(MemoryError::WriteProtection(addr))
+            }
+            _ => Ok(()),
+        }
     }
 
     /// Writes a byte to memory
     pub fn write_byte(&mut self, addr: u32, value: u8) -> Result<(), MemoryError> {
-        let physical_addr = self.translate_address(addr)?;
-        self.memory[physical_addr] = value;
+        self.write_bytes(addr, &[value])
+    }
+
+    /// Stores a byte, returning its physical index
+    fn store_byte(&mut self, addr: u32, value: u8) -> Result<usize, MemoryError> {
+        let index = self.physical_index(addr, AccessType::Write)?;
+        let mapped = self.map.contains(index as u32);
+        let old = if mapped {
+            self.map.peek_byte(index as u32).ok()
+        } else {
+            Some(self.memory[index])
+        };
+        if let (Some(journal), Some(old)) = (&mut self.write_journal, old) {
+            journal.push(ByteWrite {
+                addr: index as u32,
+                old,
+            });
+        }
+        if mapped {
+            self.map.write_byte(index as u32, value)?;
+        } else {
+            self.memory[index] = value;
+            self.mark_written(index..index + 1);
+        }
+        self.reservations.retain(|&(_, word)| word != addr & !3);
+        if self.smc_detection && self.fetched_pages.contains(&(addr >> 12)) {
+            self.smc_detected = true;
+        }
+        Ok(index)
+    }
+
+    /// Writes bytes to consecutive addresses as a single access
+    ///
+    /// Every byte is checked before any is written, so a faulting access
+    /// leaves memory unmodified.
+    pub fn write_bytes(&mut self, addr: u32, data: &[u8]) -> Result<(), MemoryError> {
+        for i in 0..data.len() {
+            self.physical_index(addr.wrapping_add(i as u32), AccessType::Write)?;
+        }
+        let watched = self
+            .overlapping_watchpoint(addr, data.len())
+            .map(|(start, size)| (start, size, self.watched_value(start, size)));
+        let mut physical_addr = 0;
+        for (i, &value) in data.iter().enumerate() {
+            let index = self.store_byte(addr.wrapping_add(i as u32), value)?;
+            if i == 0 {
+                physical_addr = index;
+            }
+        }
+        if let Some((start, size, old)) = watched {
+            self.watchpoint_hit = Some(WatchpointHit {
+                addr: start,
+                old,
+                new: self.watched_value(start, size),
+            });
+        }
+        self.log_access(addr, physical_addr, data, true);
+        Ok(())
+    }
+
+    /// Reads bytes from consecutive addresses into `buf`
+    ///
+    /// Addresses wrap at the top of the address space, so a span crossing
+    /// it faults cleanly instead of overflowing.
+    pub fn read_bytes(&mut self, addr: u32, buf: &mut [u8]) -> Result<(), MemoryError> {
+        let mut physical_addr = 0;
+        for (i, byte) in buf.iter_mut().enumerate() {
+            let byte_addr = addr.wrapping_add(i as u32);
+            let index = self.physical_index(byte_addr, AccessType::Read)?;
+            if i == 0 {
+                physical_addr = index;
+            }
+            *byte = self.load_physical(byte_addr, index)?;
+        }
+        self.log_access(addr, physical_addr, buf, false);
         Ok(())
     }
 
     /// Reads a word (4 bytes) from memory
+    ///
+    /// On a device region this makes four separate device reads, one per
+    /// byte, each with its own side effects.
     pub fn read_word(&mut self, addr: u32) -> Result<u32, MemoryError> {
-        let b0 = self.read_byte(addr)? as u32;
-        let b1 = self.read_byte(addr + 1)? as u32;
-        let b2 = self.read_byte(addr + 2)? as u32;
-        let b3 = self.read_byte(addr + 3)? as u32;
-        Ok((b0 << 24) | (b1 << 16) | (b2 << 8) | b3)
+        let mut bytes = [0; 4];
+        self.read_bytes(addr, &mut bytes)?;
+        Ok(match self.data_endianness {
+            Endian::Big => u32::from_be_bytes(bytes),
+            Endian::Little => u32::from_le_bytes(bytes),
+        })
+    }
+
+    /// Reads a half-word (2 bytes) from memory
+    pub fn read_half(&mut self, addr: u32) -> Result<u16, MemoryError> {
+        let mut bytes = [0; 2];
+        self.read_bytes(addr, &mut bytes)?;
+        Ok(match self.data_endianness {
+            Endian::Big => u16::from_be_bytes(bytes),
+            Endian::Little => u16::from_le_bytes(bytes),
+        })
+    }
+
+    /// Fetches an instruction word from memory
+    pub fn fetch_word(&mut self, addr: u32) -> Result<u32, MemoryError> {
+        let mut bytes = [0; 4];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = self.read_byte_as(addr.wrapping_add(i as u32), AccessType::Fetch)?;
+        }
+        if self.smc_detection {
+            self.fetched_pages.insert(addr >> 12);
+        }
+        Ok(u32::from_be_bytes(bytes))
+    }
+
+    /// Reads a word for a debugger without affecting emulation state
+    pub fn peek_word(&self, addr: u32) -> Result<u32, MemoryError> {
+        let mut bytes = [0; 4];
+        self.peek_bytes(addr, &mut bytes)?;
+        Ok(u32::from_be_bytes(bytes))
+    }
+
+    /// Reads bytes without affecting emulation state
+    fn peek_bytes(&self, addr: u32, buf: &mut [u8]) -> Result<(), MemoryError> {
+        for (i, byte) in buf.iter_mut().enumerate() {
+            let byte_addr = addr.wrapping_add(i as u32);
+            let pte = self.lookup_page(byte_addr, AccessType::Read)?;
+            let physical_addr = Self::physical_address(&pte, byte_addr);
+            *byte = if self.map.contains(physical_addr as u32) {
+                self.map
+                    .peek_byte(physical_addr as u32)
+                    .map_err(|_| MemoryError::InvalidAddress(addr))?
+            } else {
+                *self
+                    .memory
+                    .get(physical_addr)
+                    .ok_or(MemoryError::InvalidAddress(addr))?
+            };
+        }
+        Ok(())
     }
 
     /// Writes a word (4 bytes) to memory
     pub fn write_word(&mut self, addr: u32, value: u32) -> Result<(), MemoryError> {
-        self.write_byte(addr, ((value >> 24) & 0xFF) as u8)?;
-        self.write_byte(addr + 1, ((value >> 16) & 0xFF) as u8)?;
-        self.write_byte(addr + 2, ((value >> 8) & 0xFF) as u8)?;
-        self.write_byte(addr + 3, (value & 0xFF) as u8)?;
-        Ok(())
+        let bytes = match self.data_endianness {
+            Endian::Big => value.to_be_bytes(),
+            Endian::Little => value.to_le_bytes(),
+        };
+        self.write_bytes(addr, &bytes)
+    }
+
+    /// Writes a half-word (2 bytes) to memory
+    pub fn write_half(&mut self, addr: u32, value: u16) -> Result<(), MemoryError> {
+        let bytes = match self.data_endianness {
+            Endian::Big => value.to_be_bytes(),
+            Endian::Little => value.to_le_bytes(),
+        };
+        self.write_bytes(addr, &bytes)
     }
 }
 
@@ -195,6 +1057,99 @@ mod tests {
         assert_eq!(memory.read_word(0x2000).unwrap(), 0xDEADBEEF);
     }
 
+    #[test]
+    fn test_peek_word_shared_borrow() {
+        let mut memory = Memory::new();
+        memory.write_word(0x3000, 0xCAFEBABE).unwrap();
+
+        let view = &memory;
+        let also_view = &memory;
+        assert_eq!(view.peek_word(0x3000).unwrap(), 0xCAFEBABE);
+        assert_eq!(also_view.peek_word(0x3000).unwrap(), 0xCAFEBABE);
+        assert!(matches!(
+            view.peek_word(16 * 1024 * 1024),
+            Err(MemoryError::InvalidAddress(_))
+        ));
+    }
+
+    #[test]
+    fn test_out_of_range_access() {
+        let mut memory = Memory::new();
+        let end = (16 * 1024 * 1024) as u32;
+
+        assert!(matches!(
+            memory.read_byte(end),
+            Err(MemoryError::InvalidAddress(_))
+        ));
+        assert!(matches!(
+            memory.write_byte(end, 0),
+            Err(MemoryError::InvalidAddress(_))
+        ));
+        assert!(memory.write_byte(end - 1, 0x42).is_ok());
+    }
+
+    #[test]
+    fn test_load_bytes() {
+        let mut memory = Memory::new();
+
+        memory
+            .load_bytes(0x1000, &[0xDE, 0xAD, 0xBE, 0xEF, 0x42])
+            .unwrap();
+        assert_eq!(memory.read_word(0x1000).unwrap(), 0xDEADBEEF);
+        assert_eq!(memory.read_byte(0x1004).unwrap(), 0x42);
+    }
+
+    #[test]
+    fn test_load_words() {
+        let mut memory = Memory::new();
+
+        memory
+            .load_words(0x2000, &[0x12345678, 0x9ABCDEF0])
+            .unwrap();
+        assert_eq!(memory.read_byte(0x2000).unwrap(), 0x12);
+        assert_eq!(memory.read_byte(0x2003).unwrap(), 0x78);
+        assert_eq!(memory.read_word(0x2004).unwrap(), 0x9ABCDEF0);
+    }
+
+    #[test]
+    fn test_load_bytes_out_of_range() {
+        let mut memory = Memory::new();
+        let end = (16 * 1024 * 1024) as u32;
+
+        assert!(matches!(
+            memory.load_bytes(end - 2, &[1, 2, 3, 4]),
+            Err(MemoryError::InvalidAddress(_))
+        ));
+        // Nothing is written when the range does not fit
+        assert_eq!(memory.read_byte(end - 2).unwrap(), 0);
+        assert!(memory.load_words(end - 4, &[0x12345678]).is_ok());
+    }
+
+    #[test]
+    fn test_physical_slice() {
+        let mut memory = Memory::new();
+        let end = (16 * 1024 * 1024) as u32;
+
+        memory.write_word(0x1000, 0xDEADBEEF).unwrap();
+        assert_eq!(
+            memory.physical_slice(0x1000, 4).unwrap(),
+            &[0xDE, 0xAD, 0xBE, 0xEF]
+        );
+
+        memory.physical_slice_mut(0x1002, 2).unwrap().fill(0);
+        assert_eq!(memory.read_word(0x1000).unwrap(), 0xDEAD0000);
+
+        assert!(memory.physical_slice(end - 2, 2).is_ok());
+        assert!(matches!(
+            memory.physical_slice(end - 2, 4),
+            Err(MemoryError::InvalidAddress(_))
+        ));
+        assert!(matches!(
+            memory.physical_slice_mut(end, 1),
+            Err(MemoryError::InvalidAddress(_))
+        ));
+    }
+
     #[test]
     fn test_page_table_entry() {
         let pte = PageTableEntry::new(0x1000);
@@ -202,9 +1157,343 @@ mod tests {
         let decoded = PageTableEntry::from_u32(value);
 
         assert_eq!(decoded.physical_page, 0x1000);
-        assert_eq!(decoded.valid, true);
-        assert_eq!(decoded.writable, true);
-        assert_eq!(decoded.supervisor, false);
+        assert!(decoded.valid);
+        assert!(decoded.writable);
+        assert!(!decoded.supervisor);
+    }
+
+    #[test]
+    fn test_page_protection_matrix() {
+        use AccessType::{Fetch, Read, Write};
+        use PrivilegeLevel::{Supervisor, User};
+
+        // (valid, writable, supervisor, access, level, permitted)
+        let cases = [
+            (false, true, false, Read, Supervisor, false),
+            (false, true, false, Write, Supervisor, false),
+            (false, true, false, Fetch, Supervisor, false),
+            (false, true, false, Read, User, false),
+            (true, true, false, Read, User, true),
+            (true, true, false, Write, User, true),
+            (true, true, false, Fetch, User, true),
+            (true, false, false, Read, User, true),
+            (true, false, false, Write, User, false),
+            (true, false, false, Fetch, User, true),
+            (true, true, true, Read, User, false),
+            (true, true, true, Write, User, false),
+            (true, true, true, Fetch, User, false),
+            (true, true, true, Read, Supervisor, true),
+            (true, true, true, Write, Supervisor, true),
+            (true, true, true, Fetch, Supervisor, true),
+            (true, false, true, Write, Supervisor, false),
+            (true, false, true, Read, Supervisor, true),
+        ];
+
+        for (valid, writable, supervisor, access, level, permitted) in cases {
+            let pte = PageTableEntry {
+                physical_page: 0x2000,
+                valid,
+                writable,
+                supervisor,
+                accessed: false,
+                dirty: false,
+            };
+            let result = pte.permits(0x1000, access, level);
+            assert_eq!(
+                result.is_ok(),
+                permitted,
+                "valid={valid} writable={writable} supervisor={supervisor} {access:?} {level:?}"
+            );
+            match result {
+                Err(MemoryError::FetchFault(_)) => assert!(!valid && access == Fetch),
+                Err(MemoryError::PageFault(_)) => assert!(!valid && access != Fetch),
+                Err(MemoryError::SupervisorViolation(_)) => assert!(valid && supervisor),
+                Err(MemoryError::WriteProtection(_)) => assert!(valid && !writable),
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_translation_checks_protection() {
+        let mut memory = Memory::new();
+        memory.set_mmu_enabled(true);
+        memory.set_page_table_base(0x1000);
+
+        let mut pte = PageTableEntry::new(0x2000);
+        pte.writable = false;
+        memory.write_physical_u32(0x1000, pte.to_u32()).unwrap();
+
+        assert!(memory.read_byte(0x0010).is_ok());
+        assert!(matches!(
+            memory.write_byte(0x0010, 1),
+            Err(MemoryError::WriteProtection(0x0010))
+        ));
+
+        pte.supervisor = true;
+        memory.write_physical_u32(0x1000, pte.to_u32()).unwrap();
+        assert!(matches!(
+            memory.read_byte(0x0010),
+            Err(MemoryError::SupervisorViolation(0x0010))
+        ));
+        memory.set_privilege_level(PrivilegeLevel::Supervisor);
+        assert!(memory.read_byte(0x0010).is_ok());
+    }
+
+    #[test]
+    fn test_data_endianness() {
+        let mut memory = Memory::new();
+        memory
+            .load_bytes(0x1000, &[0x12, 0x34, 0x56, 0x78])
+            .unwrap();
+
+        assert_eq!(memory.read_word(0x1000).unwrap(), 0x1234_5678);
+        assert_eq!(memory.read_half(0x1000).unwrap(), 0x1234);
+
+        memory.set_data_endianness(Endian::Little);
+        assert_eq!(memory.read_word(0x1000).unwrap(), 0x7856_3412);
+        assert_eq!(memory.read_half(0x1000).unwrap(), 0x3412);
+        // Instruction fetch stays big-endian
+        assert_eq!(memory.fetch_word(0x1000).unwrap(), 0x1234_5678);
+
+        memory.write_word(0x2000, 0xAABB_CCDD).unwrap();
+        memory.write_half(0x2004, 0xEEFF).unwrap();
+        assert_eq!(
+            memory.physical_slice(0x2000, 6).unwrap(),
+            &[0xDD, 0xCC, 0xBB, 0xAA, 0xFF, 0xEE]
+        );
+    }
+
+    #[test]
+    fn test_guard_page() {
+        let mut memory = Memory::with_guard_page();
+
+        assert!(matches!(
+            memory.read_word(0),
+            Err(MemoryError::PageFault(0))
+        ));
+        assert!(matches!(
+            memory.write_byte(0xFFF, 1),
+            Err(MemoryError::PageFault(0xFFF))
+        ));
+        assert!(matches!(
+            memory.fetch_word(0x10),
+            Err(MemoryError::FetchFault(0x10))
+        ));
+        // A word straddling the boundary faults on its guarded bytes
+        assert!(memory.read_word(0xFFE).is_err());
+
+        memory.write_word(0x1000, 0xDEAD_BEEF).unwrap();
+        assert_eq!(memory.read_word(0x1000).unwrap(), 0xDEAD_BEEF);
+
+        // The guard also applies to translated accesses
+        memory.set_mmu_enabled(true);
+        memory.set_page_table_base(0x2000);
+        memory
+            .write_physical_u32(0x2000, PageTableEntry::new(0).to_u32())
+            .unwrap();
+        assert!(matches!(
+            memory.read_byte(0x10),
+            Err(MemoryError::PageFault(0x10))
+        ));
+    }
+
+    #[test]
+    fn test_protect_range_kinds() {
+        let mut memory = Memory::new();
+        memory.protect_range(0x4000, 0x100, ProtKind::ReadOnly);
+
+        assert!(memory.read_word(0x4000).is_ok());
+        assert!(matches!(
+            memory.write_word(0x40FC, 1),
+            Err(MemoryError::WriteProtection(0x40FC))
+        ));
+        assert!(memory.write_word(0x4100, 1).is_ok());
+
+        memory.protect_range(0x4000, 0x100, ProtKind::ReadWrite);
+        assert!(memory.write_word(0x40FC, 1).is_ok());
+    }
+
+    #[test]
+    fn test_access_log() {
+        let mut memory = Memory::new();
+        memory.write_word(0x1000, 0x1122_3344).unwrap();
+        assert!(memory.access_log().is_empty());
+
+        memory.set_access_log(true);
+        memory.set_current_pc(Some(0x400));
+        memory.write_word(0x2000, 0xAABB_CCDD).unwrap();
+        memory.set_current_pc(None);
+        assert_eq!(memory.read_half(0x1002).unwrap(), 0x3344);
+        // Failed accesses are not logged
+        assert!(memory.read_word(0xFFFF_FFFC).is_err());
+
+        assert_eq!(
+            memory.access_log(),
+            &[
+                AccessRecord {
+                    pc: Some(0x400),
+                    addr: 0x2000,
+                    physical_addr: 0x2000,
+                    size: 4,
+                    is_write: true,
+                    value: 0xAABB_CCDD,
+                },
+                AccessRecord {
+                    pc: None,
+                    addr: 0x1002,
+                    physical_addr: 0x1002,
+                    size: 2,
+                    is_write: false,
+                    value: 0x3344,
+                },
+            ]
+        );
+
+        // Physical addresses reflect translation
+        memory.set_access_log(true);
+        memory.set_mmu_enabled(true);
+        memory.set_page_table_base(0x8000);
+        memory
+            .write_physical_u32(0x8000, PageTableEntry::new(0x3000).to_u32())
+            .unwrap();
+        memory.read_byte(0x0010).unwrap();
+        assert_eq!(memory.access_log()[0].physical_addr, 0x3010);
+    }
+
+    #[test]
+    fn test_accessed_and_dirty_bits() {
+        let mut memory = Memory::new();
+        memory.set_mmu_enabled(true);
+        memory.set_page_table_base(0x1000);
+        memory
+            .write_physical_u32(0x1000, PageTableEntry::new(0x2000).to_u32())
+            .unwrap();
+        let pte =
+            |memory: &Memory| PageTableEntry::from_u32(memory.read_physical_u32(0x1000).unwrap());
+
+        // Debugger reads leave the entry untouched
+        memory.peek_word(0x0010).unwrap();
+        assert!(!pte(&memory).accessed);
+
+        memory.read_word(0x0010).unwrap();
+        assert!(pte(&memory).accessed);
+        assert!(!pte(&memory).dirty);
+
+        memory.write_word(0x0010, 0x1234_5678).unwrap();
+        assert!(pte(&memory).accessed);
+        assert!(pte(&memory).dirty);
+        assert_eq!(memory.read_physical_u32(0x2010).unwrap(), 0x1234_5678);
+    }
+
+    #[test]
+    fn test_two_level_translation() {
+        let mut memory = Memory::new();
+        memory.set_mmu_enabled(true);
+        memory.set_two_level_paging(true);
+        memory.set_page_table_base(0x1000);
+
+        // Segment table at 0x1000, one page table per segment at 0x10000 + n * 0x1000
+        for segment in 0..1024u32 {
+            let page_table = 0x10000 + segment * 0x1000;
+            memory
+                .write_physical_u32(
+                    0x1000 + segment * 4,
+                    PageTableEntry::new(page_table).to_u32(),
+                )
+                .unwrap();
+        }
+        // Map virtual page 0x00C0_3000 (segment 3, page 3) to physical 0x5000
+        memory
+            .write_physical_u32(0x13000 + 3 * 4, PageTableEntry::new(0x5000).to_u32())
+            .unwrap();
+
+        assert_eq!(
+            memory
+                .translate_address(0x00C0_3ABC, AccessType::Read)
+                .unwrap(),
+            0x5ABC
+        );
+        // Valid segment but unmapped page faults at the page level
+        assert!(matches!(
+            memory.translate_address(0x00C0_4000, AccessType::Read),
+            Err(MemoryError::PageFault(0x00C0_4000))
+        ));
+    }
+
+    #[test]
+    fn test_page_table_at_top_of_address_space() {
+        let mut memory = Memory::new();
+        memory.set_mmu_enabled(true);
+        memory.set_page_table_base(0xFFFF_F000);
+        assert!(matches!(
+            memory.translate_address(0xFFFF_F000, AccessType::Read),
+            Err(MemoryError::PageFault(0xFFFF_F000))
+        ));
+    }
+
+    #[test]
+    fn test_two_level_segment_fault() {
+        let mut memory = Memory::new();
+        memory.set_mmu_enabled(true);
+        memory.set_two_level_paging(true);
+        memory.set_page_table_base(0x1000);
+
+        // Segment 0 has a page table mapping page 0; segment 1 is invalid
+        memory
+            .write_physical_u32(0x1000, PageTableEntry::new(0x2000).to_u32())
+            .unwrap();
+        memory
+            .write_physical_u32(0x2000, PageTableEntry::new(0x8000).to_u32())
+            .unwrap();
+
+        assert_eq!(
+            memory.translate_address(0x0123, AccessType::Read).unwrap(),
+            0x8123
+        );
+        assert!(matches!(
+            memory.translate_address(0x0040_0000, AccessType::Read),
+            Err(MemoryError::PageFault(0x0040_0000))
+        ));
+        assert!(matches!(
+            memory.translate_address(0x0040_0000, AccessType::Fetch),
+            Err(MemoryError::FetchFault(0x0040_0000))
+        ));
+    }
+
+    #[test]
+    fn test_identity_mapping() {
+        let mut memory = Memory::new();
+        memory.install_identity_mapping(0x10_0000, 16).unwrap();
+        memory.set_page_table_base(0x10_0000);
+        memory.set_mmu_enabled(true);
+
+        for addr in [0x0000, 0x1234, 0xFFFC] {
+            assert_eq!(
+                memory.translate_address(addr, AccessType::Write).unwrap(),
+                addr as usize
+            );
+        }
+
+        memory.write_word(0x3000, 0xCAFE_F00D).unwrap();
+        assert_eq!(memory.read_physical_u32(0x3000).unwrap(), 0xCAFE_F00D);
+        assert_eq!(memory.read_word(0x3000).unwrap(), 0xCAFE_F00D);
+
+        // Pages past the mapping are not present
+        assert!(matches!(
+            memory.read_word(0x10000),
+            Err(MemoryError::PageFault(0x10000))
+        ));
+
+        // The address space has only 2^20 pages
+        assert!(matches!(
+            memory.install_identity_mapping(0x10_0000, (1 << 20) + 1),
+            Err(MemoryError::InvalidAddress(0x10_0000))
+        ));
+        assert!(matches!(
+            memory.install_identity_mapping(0x10_0000, u32::MAX),
+            Err(MemoryError::InvalidAddress(0x10_0000))
+        ));
     }
 
     #[test]
@@ -218,13 +1507,321 @@ mod tests {
         memory.write_physical_u32(0x1000, pte.to_u32()).unwrap();
 
         // Test translation
-        let physical_addr = memory.translate_address(0x0FFF).unwrap();
+        let physical_addr = memory.translate_address(0x0FFF, AccessType::Read).unwrap();
         assert_eq!(physical_addr, 0x2FFF);
 
         // Test page fault
         assert!(matches!(
-            memory.translate_address(0x2000),
+            memory.translate_address(0x2000, AccessType::Read),
             Err(MemoryError::PageFault(_))
         ));
+
+        // Instruction fetches report a distinct fault
+        assert!(matches!(
+            memory.translate_address(0x2000, AccessType::Fetch),
+            Err(MemoryError::FetchFault(0x2000))
+        ));
+        assert!(matches!(
+            memory.fetch_word(0x2000),
+            Err(MemoryError::FetchFault(_))
+        ));
+    }
+
+    #[test]
+    fn test_watchpoint_hit() {
+        let mut memory = Memory::new();
+        memory.add_watchpoint(0x2002, 1);
+
+        memory.write_word(0x1000, 0x12345678).unwrap();
+        assert_eq!(memory.take_watchpoint_hit(), None);
+
+        memory.write_word(0x2000, 0x12345678).unwrap();
+        assert_eq!(
+            memory.take_watchpoint_hit(),
+            Some(WatchpointHit {
+                addr: 0x2002,
+                old: 0,
+                new: 0x56,
+            })
+        );
+        assert_eq!(memory.take_watchpoint_hit(), None);
+
+        // Reads never trigger a watchpoint
+        memory.read_word(0x2000).unwrap();
+        assert_eq!(memory.take_watchpoint_hit(), None);
+
+        memory.remove_watchpoint(0x2002);
+        memory.write_word(0x2000, 0).unwrap();
+        assert_eq!(memory.take_watchpoint_hit(), None);
+    }
+
+    #[test]
+    fn test_watchpoint_range_captures_values() {
+        let mut memory = Memory::new();
+        memory.write_word(0x2000, 0x1122_3344).unwrap();
+        memory.add_watchpoint(0x2000, 4);
+
+        // A byte write inside the range reports the whole watched word
+        memory.write_byte(0x2003, 0xFF).unwrap();
+        assert_eq!(
+            memory.take_watchpoint_hit(),
+            Some(WatchpointHit {
+                addr: 0x2000,
+                old: 0x1122_3344,
+                new: 0x1122_33FF,
+            })
+        );
+
+        // Writes ending just before or starting just after the range do not hit
+        memory.write_word(0x1FFC, 0).unwrap();
+        memory.write_word(0x2004, 0).unwrap();
+        assert_eq!(memory.take_watchpoint_hit(), None);
+    }
+
+    #[test]
+    fn test_write_at_end_of_memory_is_transactional() {
+        let mut memory = Memory::new();
+        let end = memory.memory.len() as u32;
+        memory.write_byte(end - 2, 0xAA).unwrap();
+        memory.write_byte(end - 1, 0xBB).unwrap();
+
+        assert!(matches!(
+            memory.write_word(end - 2, 0x1122_3344),
+            Err(MemoryError::InvalidAddress(_))
+        ));
+        assert_eq!(memory.read_byte(end - 2).unwrap(), 0xAA);
+        assert_eq!(memory.read_byte(end - 1).unwrap(), 0xBB);
+    }
+
+    #[test]
+    fn test_address_wrap() {
+        let mut memory = Memory::new();
+        assert_eq!(memory.check_address_wrap(0x10, true).unwrap(), 0x10);
+
+        memory.set_address_wrap(false);
+        assert!(matches!(
+            memory.check_address_wrap(0x10, true),
+            Err(MemoryError::InvalidAddress(0x10))
+        ));
+        assert_eq!(memory.check_address_wrap(0x10, false).unwrap(), 0x10);
+    }
+
+    #[test]
+    fn test_memory_map_regions() {
+        let mut map = MemoryMap::new();
+        map.add_rom("boot", 0xFF80_0000, vec![0xDE, 0xAD, 0xBE, 0xEF])
+            .unwrap();
+        map.add_ram("sram", 0x2000_0000, 0x100).unwrap();
+        let mut memory = Memory::with_map(map);
+
+        // ROM reads succeed and writes fault without modifying it
+        assert_eq!(memory.read_word(0xFF80_0000).unwrap(), 0xDEAD_BEEF);
+        assert_eq!(memory.fetch_word(0xFF80_0000).unwrap(), 0xDEAD_BEEF);
+        assert!(matches!(
+            memory.write_word(0xFF80_0000, 0),
+            Err(MemoryError::WriteProtection(0xFF80_0000))
+        ));
+        assert_eq!(memory.peek_word(0xFF80_0000).unwrap(), 0xDEAD_BEEF);
+
+        // Accesses go to the region covering the address, or RAM otherwise
+        memory.write_word(0x2000_0010, 0x1234_5678).unwrap();
+        memory.write_word(0x10, 0x9ABC_DEF0).unwrap();
+        let sram = memory.map().region("sram").unwrap().data().unwrap();
+        assert_eq!(sram[0x10..0x14], [0x12, 0x34, 0x56, 0x78]);
+        assert_eq!(memory.read_word(0x10).unwrap(), 0x9ABC_DEF0);
+        assert!(matches!(
+            memory.read_word(0x3000_0000),
+            Err(MemoryError::InvalidAddress(_))
+        ));
+
+        // Writes to mapped RAM are journaled like any other
+        memory.begin_write_journal();
+        memory.write_word(0x2000_0010, 0).unwrap();
+        let writes = memory.end_write_journal();
+        memory.undo_writes(&writes);
+        assert_eq!(memory.read_word(0x2000_0010).unwrap(), 0x1234_5678);
+    }
+
+    #[test]
+    fn test_device_byte_access() {
+        use crate::memory::map::Device;
+        use std::sync::{Arc, Mutex};
+
+        /// Offsets accessed, with the value of each write
+        type Accesses = Arc<Mutex<Vec<(u32, Option<u8>)>>>;
+
+        /// Device whose reads count up and which records every access
+        #[derive(Debug)]
+        struct Counter {
+            accesses: Accesses,
+            next: u8,
+        }
+
+        impl Device for Counter {
+            fn read(&mut self, offset: u32) -> u8 {
+                self.accesses.lock().unwrap().push((offset, None));
+                self.next += 1;
+                self.next
+            }
+
+            fn write(&mut self, offset: u32, value: u8) {
+                self.accesses.lock().unwrap().push((offset, Some(value)));
+            }
+        }
+
+        let accesses = Accesses::default();
+        let mut map = MemoryMap::new();
+        map.add_device(
+            "timer",
+            0xFFF0_0000,
+            0x10,
+            Box::new(Counter {
+                accesses: Arc::clone(&accesses),
+                next: 0,
+            }),
+        )
+        .unwrap();
+        let mut memory = Memory::with_map(map);
+
+        // Byte accesses reach the device at any offset
+        assert_eq!(memory.read_byte(0xFFF0_0003).unwrap(), 1);
+        memory.write_byte(0xFFF0_0005, 0x42).unwrap();
+        assert_eq!(*accesses.lock().unwrap(), [(3, None), (5, Some(0x42))]);
+
+        // A word read is four device reads
+        accesses.lock().unwrap().clear();
+        assert_eq!(memory.read_word(0xFFF0_0008).unwrap(), 0x0203_0405);
+        assert_eq!(
+            *accesses.lock().unwrap(),
+            [(8, None), (9, None), (10, None), (11, None)]
+        );
+    }
+
+    #[test]
+    fn test_wrap_on_overflow() {
+        let mut memory = Memory::new();
+        let end = memory.memory.len() as u32;
+        memory.set_wrap_on_overflow(true);
+
+        memory.write_word(end - 2, 0x1122_3344).unwrap();
+        assert_eq!(memory.read_byte(end - 2).unwrap(), 0x11);
+        assert_eq!(memory.read_byte(end - 1).unwrap(), 0x22);
+        assert_eq!(memory.read_byte(0).unwrap(), 0x33);
+        assert_eq!(memory.read_byte(1).unwrap(), 0x44);
+        assert_eq!(memory.read_word(end - 2).unwrap(), 0x1122_3344);
+    }
+
+    #[test]
+    fn test_smc_detection() {
+        let mut memory = Memory::new();
+        memory.write_word(0x1000, 0x6021_0001).unwrap();
+        memory.set_smc_detection(true);
+
+        memory.fetch_word(0x1000).unwrap();
+        // Writes outside the fetched page are not flagged
+        memory.write_word(0x2000, 0).unwrap();
+        assert!(!memory.smc_detected());
+
+        memory.write_word(0x1004, 0).unwrap();
+        assert!(memory.smc_detected());
+    }
+
+    #[test]
+    fn test_poisoned_memory() {
+        let mut memory = Memory::with_poison(0xCC);
+        assert_eq!(memory.read_byte(0x1000).unwrap(), 0xCC);
+        assert_eq!(memory.read_word(0x2000).unwrap(), 0xCCCC_CCCC);
+
+        memory.write_half(0x2000, 0x1234).unwrap();
+        assert_eq!(memory.read_word(0x2000).unwrap(), 0x1234_CCCC);
+    }
+
+    #[test]
+    fn test_uninit_tracking() {
+        let mut memory = Memory::new();
+        memory.set_uninit_tracking(true);
+        memory.write_word(0x1000, 0xDEAD_BEEF).unwrap();
+        memory.load_words(0x2000, &[0x6021_0001]).unwrap();
+
+        memory.read_word(0x1000).unwrap();
+        memory.fetch_word(0x2000).unwrap();
+        assert_eq!(memory.uninit_read(), None);
+
+        // A word that is only partly written reports its first unwritten byte
+        memory.write_half(0x3000, 0x1234).unwrap();
+        memory.read_word(0x3000).unwrap();
+        assert_eq!(memory.uninit_read(), Some(0x3002));
+
+        // The first unwritten read is kept until tracking is reset
+        memory.read_byte(0x4000).unwrap();
+        assert_eq!(memory.uninit_read(), Some(0x3002));
+        memory.set_uninit_tracking(true);
+        assert_eq!(memory.uninit_read(), None);
+    }
+
+    #[test]
+    fn test_write_journal() {
+        let mut memory = Memory::new();
+        memory.write_word(0x1000, 0x1122_3344).unwrap();
+
+        memory.begin_write_journal();
+        memory.write_half(0x1002, 0xAAAA).unwrap();
+        memory.write_byte(0x1003, 0xBB).unwrap();
+        let writes = memory.end_write_journal();
+        assert_eq!(
+            writes,
+            vec![
+                ByteWrite {
+                    addr: 0x1002,
+                    old: 0x33
+                },
+                ByteWrite {
+                    addr: 0x1003,
+                    old: 0x44
+                },
+                ByteWrite {
+                    addr: 0x1003,
+                    old: 0xAA
+                },
+            ]
+        );
+
+        // Writes after the journal ends are not recorded
+        memory.write_byte(0x1000, 0).unwrap();
+        assert!(memory.end_write_journal().is_empty());
+
+        memory.undo_writes(&writes);
+        assert_eq!(memory.read_word(0x1000).unwrap(), 0x0022_3344);
+    }
+
+    #[test]
+    fn test_fill() {
+        let mut memory = Memory::new();
+        memory.fill(0x1001, 6, 0xAB).unwrap();
+        assert_eq!(memory.read_byte(0x1000).unwrap(), 0);
+        assert_eq!(memory.physical_slice(0x1001, 6).unwrap(), &[0xAB; 6]);
+        assert_eq!(memory.read_byte(0x1007).unwrap(), 0);
+
+        memory.fill_word(0x2000, 3, 0xDEAD_BEEF).unwrap();
+        for i in 0..3 {
+            assert_eq!(memory.read_word(0x2000 + i * 4).unwrap(), 0xDEAD_BEEF);
+        }
+        assert_eq!(memory.read_word(0x200C).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_fill_out_of_range() {
+        let mut memory = Memory::new();
+        let end = memory.memory.len() as u32;
+
+        assert!(matches!(
+            memory.fill(end - 4, 8, 0xFF),
+            Err(MemoryError::InvalidAddress(_))
+        ));
+        assert!(matches!(
+            memory.fill_word(end - 4, 2, 0xFFFF_FFFF),
+            Err(MemoryError::InvalidAddress(_))
+        ));
+        assert_eq!(memory.physical_slice(end - 4, 4).unwrap(), &[0; 4]);
     }
 }
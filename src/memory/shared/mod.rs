@@ -0,0 +1,206 @@
+//! Memory shared between several emulated processors.
+//!
+//! [`SharedMemory`] wraps a [`Memory`] in a mutex so that `CPU` instances on
+//! different threads can run against the same backing store. Each handle is
+//! bound to a core number, which owns that core's load-linked reservation.
+//! A step holds the lock for the whole instruction, so `xmem` and the
+//! load-linked/store-conditional pair are atomic across cores. It is only
+//! built with the `shared-memory` feature.
+//!
+//! Only the reservation is per core. The rest of the memory's state, such as
+//! whether translation is enabled, the page table base, the privilege level,
+//! the data byte order and the watchpoints, is shared by every handle: a
+//! core that enables its MMU or writes its page table base changes them for
+//! the other cores too. Cores that share memory should therefore agree on
+//! their translation setup.
+
+use crate::cpu::{EmulationError, StepOutcome, CPU};
+use crate::memory::{Memory, MemoryError};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// Handle to memory shared between cores
+///
+/// Cloning a handle or calling [`SharedMemory::for_core`] gives another
+/// handle onto the same memory.
+#[derive(Debug, Clone)]
+pub struct SharedMemory {
+    /// The shared backing store
+    memory: Arc<Mutex<Memory>>,
+    /// Core that accesses through this handle are performed for
+    core: usize,
+}
+
+impl SharedMemory {
+    /// Shares a memory instance, returning the handle for core 0
+    pub fn new(memory: Memory) -> Self {
+        Self {
+            memory: Arc::new(Mutex::new(memory)),
+            core: 0,
+        }
+    }
+
+    /// Returns a handle onto the same memory for another core
+    pub fn for_core(&self, core: usize) -> Self {
+        Self {
+            memory: Arc::clone(&self.memory),
+            core,
+        }
+    }
+
+    /// Returns the core this handle performs accesses for
+    pub fn core(&self) -> usize {
+        self.core
+    }
+
+    /// Locks the memory for exclusive use by this handle's core
+    ///
+    /// A panic on another thread while it held the lock does not poison the
+    /// memory for the remaining cores.
+    pub fn lock(&self) -> MutexGuard<'_, Memory> {
+        let mut memory = self.memory.lock().unwrap_or_else(PoisonError::into_inner);
+        memory.set_core(self.core);
+        memory
+    }
+
+    /// Runs a closure with exclusive access to the memory
+    pub fn with<R>(&self, f: impl FnOnce(&mut Memory) -> R) -> R {
+        f(&mut self.lock())
+    }
+
+    /// Executes a single instruction on `cpu`, holding the lock throughout
+    pub fn step(&self, cpu: &mut CPU) -> Result<StepOutcome, EmulationError> {
+        cpu.step(&mut self.lock())
+    }
+
+    /// Reads a byte from memory
+    pub fn read_byte(&self, addr: u32) -> Result<u8, MemoryError> {
+        self.lock().read_byte(addr)
+    }
+
+    /// Reads a half-word (2 bytes) from memory
+    pub fn read_half(&self, addr: u32) -> Result<u16, MemoryError> {
+        self.lock().read_half(addr)
+    }
+
+    /// Reads a word (4 bytes) from memory
+    pub fn read_word(&self, addr: u32) -> Result<u32, MemoryError> {
+        self.lock().read_word(addr)
+    }
+
+    /// Writes a byte to memory
+    pub fn write_byte(&self, addr: u32, value: u8) -> Result<(), MemoryError> {
+        self.lock().write_byte(addr, value)
+    }
+
+    /// Writes a half-word (2 bytes) to memory
+    pub fn write_half(&self, addr: u32, value: u16) -> Result<(), MemoryError> {
+        self.lock().write_half(addr, value)
+    }
+
+    /// Writes a word (4 bytes) to memory
+    pub fn write_word(&self, addr: u32, value: u32) -> Result<(), MemoryError> {
+        self.lock().write_word(addr, value)
+    }
+
+    /// Copies a byte slice into physical memory, bypassing translation
+    pub fn load_bytes(&self, addr: u32, data: &[u8]) -> Result<(), MemoryError> {
+        self.lock().load_bytes(addr, data)
+    }
+
+    /// Copies words into physical memory in big-endian byte order
+    pub fn load_words(&self, addr: u32, words: &[u32]) -> Result<(), MemoryError> {
+        self.lock().load_words(addr, words)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::instructions::memory_access::{LoadLinked, StoreConditional};
+    use crate::cpu::instructions::{Instruction, Operands};
+    use std::thread;
+
+    #[test]
+    fn test_shared_counter_has_no_lost_updates() {
+        const COUNTER: u32 = 0x1000;
+        const INCREMENTS: u32 = 2000;
+
+        let shared = SharedMemory::new(Memory::new());
+        let workers: Vec<_> = (0..2)
+            .map(|core| {
+                let memory = shared.for_core(core);
+                thread::spawn(move || {
+                    let mut cpu = CPU::new();
+                    let ops = Operands {
+                        d: 2,
+                        s1: 1,
+                        ..Default::default()
+                    };
+                    cpu.registers[1] = COUNTER;
+                    for _ in 0..INCREMENTS {
+                        // Compare-and-swap loop; each instruction locks
+                        // separately so the cores interleave between them
+                        loop {
+                            memory.with(|m| LoadLinked.execute(&mut cpu, m, &ops));
+                            cpu.registers[2] += 1;
+                            memory.with(|m| StoreConditional.execute(&mut cpu, m, &ops));
+                            if cpu.cr0 & CPU::CR0_EQUAL != 0 {
+                                break;
+                            }
+                        }
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        assert_eq!(shared.read_word(COUNTER).unwrap(), 2 * INCREMENTS);
+    }
+
+    #[test]
+    fn test_reservations_are_per_core() {
+        let core0 = SharedMemory::new(Memory::new());
+        let core1 = core0.for_core(1);
+        core0.write_word(0x1000, 1).unwrap();
+
+        // Both cores reserve the word; core 1 stores first and wins
+        core0.with(|m| m.reserve(0x1000));
+        core1.with(|m| m.reserve(0x1000));
+        assert!(core1.with(|m| m.take_reservation(0x1000)));
+        core1.write_word(0x1000, 2).unwrap();
+
+        // Core 1 reserving again does not revive core 0's lost reservation
+        core1.with(|m| m.reserve(0x1000));
+        assert!(!core0.with(|m| m.take_reservation(0x1000)));
+    }
+
+    #[test]
+    fn test_translation_state_is_shared() {
+        let core0 = SharedMemory::new(Memory::new());
+        let core1 = core0.for_core(1);
+
+        core0.with(|m| {
+            m.set_page_table_base(0x2000);
+            m.set_mmu_enabled(true);
+        });
+        assert_eq!(core1.with(|m| m.page_table_base()), 0x2000);
+        assert!(core1.with(|m| m.is_mmu_enabled()));
+    }
+
+    #[test]
+    fn test_step_on_shared_memory() {
+        let core0 = SharedMemory::new(Memory::new());
+        let core1 = core0.for_core(1);
+        // addu r1, r1, 1
+        core0.load_words(0x1000, &[0x6021_0001]).unwrap();
+
+        for memory in [&core0, &core1] {
+            let mut cpu = CPU::new();
+            cpu.pc = 0x1000;
+            assert_eq!(memory.step(&mut cpu), Ok(StepOutcome::Retired));
+            assert_eq!(cpu.registers[1], 1);
+        }
+    }
+}